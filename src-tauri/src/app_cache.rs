@@ -0,0 +1,75 @@
+use std::{fs, path::PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{diagnostics::unix_now, models::ApplicationInfo, windows_utils::local_data_dir};
+
+const CACHE_FILE: &str = "apps.json";
+/// Bump whenever [`ApplicationInfo`] changes in a way older caches can't be read as.
+const SCHEMA_VERSION: u32 = 1;
+/// Caches older than this are ignored; the startup reindex replaces them anyway, but a very old
+/// index would mostly show apps that are gone.
+const MAX_AGE_SECS: u64 = 30 * 24 * 60 * 60;
+
+#[derive(Serialize)]
+struct CacheOut<'a> {
+    version: u32,
+    /// Unix seconds.
+    saved_at: u64,
+    apps: &'a [ApplicationInfo],
+}
+
+#[derive(Deserialize)]
+struct CacheIn {
+    version: u32,
+    saved_at: u64,
+    apps: Vec<ApplicationInfo>,
+}
+
+/// Reads the app index saved by the previous run. `None` when there is no cache, it can't be
+/// read, or it has another schema version or is too old.
+pub fn load() -> Option<Vec<ApplicationInfo>> {
+    let path = cache_path()?;
+    let content = fs::read_to_string(&path).ok()?;
+    let cache: CacheIn = match serde_json::from_str(&content) {
+        Ok(cache) => cache,
+        Err(err) => {
+            log::warn!(
+                "ignoring unreadable app index cache {}: {err}",
+                path.display()
+            );
+            return None;
+        }
+    };
+    if cache.version != SCHEMA_VERSION {
+        log::info!("应用索引缓存版本 {} 已过期，忽略", cache.version);
+        return None;
+    }
+    if unix_now().saturating_sub(cache.saved_at) > MAX_AGE_SECS {
+        log::info!("应用索引缓存过旧，忽略");
+        return None;
+    }
+    Some(cache.apps)
+}
+
+/// Replaces the cache with `apps`. Written to a temporary file first so a crash mid-write
+/// never leaves a truncated cache behind.
+pub fn save(apps: &[ApplicationInfo]) -> Result<(), String> {
+    let path = cache_path().ok_or_else(|| "无法确定缓存目录".to_string())?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|err| err.to_string())?;
+    }
+    let data = serde_json::to_string(&CacheOut {
+        version: SCHEMA_VERSION,
+        saved_at: unix_now(),
+        apps,
+    })
+    .map_err(|err| err.to_string())?;
+    let temp_path = path.with_extension("json.tmp");
+    fs::write(&temp_path, data).map_err(|err| err.to_string())?;
+    fs::rename(&temp_path, &path).map_err(|err| err.to_string())
+}
+
+pub(crate) fn cache_path() -> Option<PathBuf> {
+    Some(local_data_dir()?.join("index").join(CACHE_FILE))
+}
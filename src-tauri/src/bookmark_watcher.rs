@@ -0,0 +1,110 @@
+use std::{
+    collections::HashSet,
+    path::PathBuf,
+    sync::mpsc::{self, RecvTimeoutError},
+    thread,
+    time::{Duration, Instant},
+};
+
+use log::{debug, info, warn};
+use notify::{Event, RecommendedWatcher, RecursiveMode, Watcher};
+use tauri::{AppHandle, Manager};
+
+use crate::{bookmarks, commands::start_bookmark_reindex, state::AppState};
+
+const BOOKMARKS_FILE_NAME: &str = "Bookmarks";
+/// Chrome rewrites the file in several steps; wait for the burst to settle before reindexing.
+const DEBOUNCE: Duration = Duration::from_secs(2);
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+/// How often roots that didn't exist yet are retried.
+const ATTACH_RETRY_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Watches the browser profile directories and triggers a bookmark-only reindex when a
+/// `Bookmarks` file changes.
+///
+/// Directories are watched instead of the file itself because Chrome replaces the file via
+/// rename, which would orphan a watch on the original inode. Roots that don't exist yet are
+/// attached once they appear. Events are ignored while `watch_bookmarks` is disabled.
+pub fn spawn_bookmark_watcher(app_handle: AppHandle) {
+    let (tx, rx) = mpsc::channel::<notify::Result<Event>>();
+    let mut watcher: RecommendedWatcher = match notify::recommended_watcher(tx) {
+        Ok(watcher) => watcher,
+        Err(err) => {
+            warn!("failed to create bookmark watcher: {err}");
+            return;
+        }
+    };
+
+    let spawned = thread::Builder::new()
+        .name("bookmark-watcher".into())
+        .spawn(move || {
+            let mut attached: HashSet<PathBuf> = HashSet::new();
+            let mut last_attach_attempt: Option<Instant> = None;
+            let mut pending_since: Option<Instant> = None;
+
+            loop {
+                if last_attach_attempt.is_none_or(|at| at.elapsed() >= ATTACH_RETRY_INTERVAL) {
+                    attach_missing_roots(&mut watcher, &mut attached);
+                    last_attach_attempt = Some(Instant::now());
+                }
+
+                match rx.recv_timeout(POLL_INTERVAL) {
+                    Ok(Ok(event)) => {
+                        if touches_bookmarks_file(&event) {
+                            pending_since = Some(Instant::now());
+                        }
+                    }
+                    Ok(Err(err)) => debug!("bookmark watcher error: {err}"),
+                    Err(RecvTimeoutError::Timeout) => {}
+                    Err(RecvTimeoutError::Disconnected) => break,
+                }
+
+                let Some(since) = pending_since else {
+                    continue;
+                };
+                if since.elapsed() < DEBOUNCE {
+                    continue;
+                }
+                pending_since = None;
+
+                let Some(state) = app_handle.try_state::<AppState>() else {
+                    continue;
+                };
+                let enabled = state
+                    .config
+                    .lock()
+                    .map(|cfg| cfg.watch_bookmarks)
+                    .unwrap_or(false);
+                if enabled && start_bookmark_reindex(&state) {
+                    info!("bookmarks changed on disk, reindexing");
+                }
+            }
+        });
+
+    if let Err(err) = spawned {
+        warn!("failed to spawn bookmark watcher: {err}");
+    }
+}
+
+fn attach_missing_roots(watcher: &mut RecommendedWatcher, attached: &mut HashSet<PathBuf>) {
+    for root in bookmarks::bookmark_watch_roots() {
+        if attached.contains(&root) || !root.is_dir() {
+            continue;
+        }
+        match watcher.watch(&root, RecursiveMode::Recursive) {
+            Ok(()) => {
+                debug!("watching {:?} for bookmark changes", root);
+                attached.insert(root);
+            }
+            Err(err) => warn!("failed to watch {:?}: {err}", root),
+        }
+    }
+}
+
+fn touches_bookmarks_file(event: &Event) -> bool {
+    event.paths.iter().any(|path| {
+        path.file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| name == BOOKMARKS_FILE_NAME)
+    })
+}
@@ -1,13 +1,15 @@
 use std::{
+    collections::{HashMap, HashSet},
     env, fs,
     path::{Path, PathBuf},
 };
 
 use log::{debug, warn};
+use rusqlite::{Connection, OpenFlags};
 use serde_json::Value;
 use sha1::{Digest, Sha1};
 
-use crate::text_utils::extend_keywords_with_pinyin;
+use crate::{favicons::apply_cached_favicons, text_utils::extend_keywords_with_pinyin};
 
 #[derive(Debug, Clone)]
 pub struct BookmarkEntry {
@@ -15,14 +17,193 @@ pub struct BookmarkEntry {
     pub title: String,
     pub url: String,
     pub folder_path: Option<String>,
+    /// Browser the bookmark comes from. After merging across browsers this lists every
+    /// browser that has the same bookmark, e.g. `Chrome / Edge`.
+    pub browser: String,
+    /// Display name of the browser profile the bookmark comes from.
+    pub profile: String,
     pub keywords: Vec<String>,
+    pub icon_b64: String,
+    /// When the bookmark was added, in Unix seconds; `None` if the browser didn't record it.
+    pub date_added: Option<i64>,
 }
 
-/// Loads Chrome bookmark entries from all detected profiles under LOCALAPPDATA.
-pub fn load_chrome_bookmarks() -> Vec<BookmarkEntry> {
+/// All bookmarks directly inside one folder of one profile, so the folder can be opened at once.
+#[derive(Debug, Clone)]
+pub struct BookmarkFolder {
+    pub id: String,
+    /// Last segment of `path`.
+    pub name: String,
+    /// Full folder path as in [`BookmarkEntry::folder_path`], e.g. `Bookmarks bar / Work`.
+    pub path: String,
+    pub browser: String,
+    pub profile: String,
+    /// URLs of the folder's bookmarks, in bookmark order.
+    pub urls: Vec<String>,
+}
+
+/// Groups `entries` by profile and folder. Bookmarks outside any folder aren't grouped, and
+/// subfolders form their own groups rather than being merged into their parent.
+pub fn group_folders(entries: &[BookmarkEntry]) -> Vec<BookmarkFolder> {
+    let mut folders: Vec<BookmarkFolder> = Vec::new();
+    let mut positions: HashMap<(String, String), usize> = HashMap::new();
+    for entry in entries {
+        let Some(path) = &entry.folder_path else {
+            continue;
+        };
+        let key = (entry.profile.clone(), path.clone());
+        let position = *positions.entry(key).or_insert_with(|| {
+            let name = path
+                .rsplit('/')
+                .next()
+                .map(str::trim)
+                .unwrap_or(path)
+                .to_string();
+            folders.push(BookmarkFolder {
+                id: format!("{}|{path}", entry.profile).to_lowercase(),
+                name,
+                path: path.clone(),
+                browser: entry.browser.clone(),
+                profile: entry.profile.clone(),
+                urls: Vec::new(),
+            });
+            folders.len() - 1
+        });
+        folders[position].urls.push(entry.url.clone());
+    }
+    folders
+}
+
+pub const CHROME: &str = "Chrome";
+pub const EDGE: &str = "Edge";
+pub const FIREFOX: &str = "Firefox";
+
+type BrowserLoader = fn(&[String], bool) -> Vec<BookmarkEntry>;
+
+/// Bookmark loaders per browser, in the default preference order.
+const BROWSER_LOADERS: &[(&str, BrowserLoader)] = &[
+    (CHROME, load_chrome_bookmarks),
+    (EDGE, load_edge_bookmarks),
+    (FIREFOX, load_firefox_bookmarks),
+];
+
+/// Whether `name` (case-insensitive) is a browser bookmarks can be loaded from.
+pub fn is_supported_browser(name: &str) -> bool {
+    BROWSER_LOADERS
+        .iter()
+        .any(|(browser, _)| browser.eq_ignore_ascii_case(name.trim()))
+}
+
+/// Loads bookmarks from every supported browser and merges them into one index.
+///
+/// `browsers` restricts loading to the named browsers (case-insensitive) and also sets the
+/// preference order; an empty list loads all of them in the default order. A bookmark with
+/// the same URL and title in several browsers is kept once, from the most preferred browser,
+/// with the other browsers appended to its `browser` label.
+///
+/// Edge and Firefox are opt-in: each is skipped unless enabled in `opt_in`, even when listed.
+pub fn load_bookmarks(
+    browsers: &[String],
+    profiles: &[String],
+    dedupe: bool,
+    opt_in: OptInBrowsers,
+) -> Vec<BookmarkEntry> {
+    let mut loaders: Vec<(&str, BrowserLoader)> = if browsers.is_empty() {
+        BROWSER_LOADERS.to_vec()
+    } else {
+        browsers
+            .iter()
+            .filter_map(|wanted| {
+                BROWSER_LOADERS
+                    .iter()
+                    .find(|(name, _)| name.eq_ignore_ascii_case(wanted.trim()))
+                    .copied()
+            })
+            .collect()
+    };
+    let mut seen = HashSet::new();
+    loaders.retain(|(name, _)| seen.insert(*name) && opt_in.allows(name));
+
+    let mut entries = Vec::new();
+    for (_, loader) in loaders {
+        entries.extend(loader(profiles, dedupe));
+    }
+    merge_across_browsers(entries)
+}
+
+/// Browsers whose bookmarks are only loaded when switched on in the settings.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct OptInBrowsers {
+    pub edge: bool,
+    pub firefox: bool,
+}
+
+impl OptInBrowsers {
+    fn allows(&self, browser: &str) -> bool {
+        match browser {
+            EDGE => self.edge,
+            FIREFOX => self.firefox,
+            _ => true,
+        }
+    }
+}
+
+/// Collapses bookmarks with the same URL and title from different browsers into the first
+/// one, listing the other browsers in its label. Duplicates within one browser are left to
+/// the per-profile `dedupe` setting.
+fn merge_across_browsers(entries: Vec<BookmarkEntry>) -> Vec<BookmarkEntry> {
+    let mut merged: Vec<BookmarkEntry> = Vec::with_capacity(entries.len());
+    let mut positions: HashMap<(String, String), (usize, String)> = HashMap::new();
+
+    for entry in entries {
+        let key = (entry.url.clone(), entry.title.clone());
+        match positions.get(&key) {
+            Some((index, first_browser)) if *first_browser != entry.browser => {
+                let kept = &mut merged[*index];
+                if !kept.browser.split(" / ").any(|name| name == entry.browser) {
+                    kept.browser = format!("{} / {}", kept.browser, entry.browser);
+                    kept.keywords.push(entry.browser);
+                }
+            }
+            Some(_) => merged.push(entry),
+            None => {
+                positions.insert(key, (merged.len(), entry.browser.clone()));
+                merged.push(entry);
+            }
+        }
+    }
+
+    merged
+}
+
+/// Loads Chrome bookmark entries from the detected profiles under LOCALAPPDATA.
+///
+/// `profiles` restricts loading to profiles whose folder or display name matches
+/// (case-insensitive); an empty list loads all of them. With `dedupe`, a URL bookmarked in
+/// several profiles is kept only once.
+pub fn load_chrome_bookmarks(profiles: &[String], dedupe: bool) -> Vec<BookmarkEntry> {
+    load_chromium_bookmarks(CHROME, chrome_user_data_dir(), profiles, dedupe)
+}
+
+/// Loads Edge bookmark entries, which use Chrome's format, like [`load_chrome_bookmarks`].
+pub fn load_edge_bookmarks(profiles: &[String], dedupe: bool) -> Vec<BookmarkEntry> {
+    load_chromium_bookmarks(EDGE, edge_user_data_dir(), profiles, dedupe)
+}
+
+/// Loads the `Bookmarks` file of every profile under a Chromium `User Data` directory.
+fn load_chromium_bookmarks(
+    browser: &'static str,
+    user_data_dir: Option<PathBuf>,
+    profiles: &[String],
+    dedupe: bool,
+) -> Vec<BookmarkEntry> {
     let mut all_entries = Vec::new();
+    let Some(user_data_dir) = user_data_dir else {
+        return all_entries;
+    };
+    let profile_names = chromium_profile_names(&user_data_dir, browser);
 
-    for profile_dir in chrome_profile_dirs() {
+    for profile_dir in chromium_profile_dirs(&user_data_dir) {
         let Some(profile_name) = profile_dir
             .file_name()
             .and_then(|os| os.to_str())
@@ -30,7 +211,14 @@ pub fn load_chrome_bookmarks() -> Vec<BookmarkEntry> {
         else {
             continue;
         };
-        let display_name = profile_display_label(&profile_name);
+        let display_name = profile_names
+            .iter()
+            .find(|(dir, _)| *dir == profile_name)
+            .map(|(_, name)| name.clone())
+            .unwrap_or_else(|| profile_display_label(&profile_name));
+        if !profile_selected(profiles, &profile_name, &display_name) {
+            continue;
+        }
         let bookmarks_path = profile_dir.join("Bookmarks");
         if !bookmarks_path.is_file() {
             continue;
@@ -39,38 +227,336 @@ pub fn load_chrome_bookmarks() -> Vec<BookmarkEntry> {
         match fs::read_to_string(&bookmarks_path) {
             Ok(content) => match serde_json::from_str::<Value>(&content) {
                 Ok(json) => {
-                    collect_entries_from_file(&json, &display_name, &mut all_entries);
+                    // Chrome 的 id 保持原格式，其他浏览器加上名称以免与 Chrome 同名配置文件冲突
+                    let id_scope = if browser == CHROME {
+                        profile_name.clone()
+                    } else {
+                        format!("{browser}/{profile_name}")
+                    };
+                    let profile = ProfileLabels {
+                        dir: &id_scope,
+                        display: &display_name,
+                        browser,
+                    };
+                    collect_entries_from_file(&json, &profile, &mut all_entries);
                 }
                 Err(err) => warn!(
-                    "failed to parse Chrome bookmarks {:?}: {err}",
+                    "failed to parse {browser} bookmarks {:?}: {err}",
                     bookmarks_path
                 ),
             },
             Err(err) => warn!(
-                "failed to read Chrome bookmarks {:?}: {err}",
+                "failed to read {browser} bookmarks {:?}: {err}",
                 bookmarks_path
             ),
         }
     }
 
-    debug!("loaded {} Chrome bookmark entries", all_entries.len());
+    if dedupe {
+        let mut seen = HashSet::new();
+        all_entries.retain(|entry| seen.insert(entry.url.clone()));
+    }
+
+    apply_cached_favicons(&mut all_entries);
+    debug!("loaded {} {browser} bookmark entries", all_entries.len());
     all_entries
 }
 
-fn chrome_profile_dirs() -> Vec<PathBuf> {
-    let mut results = Vec::new();
-    let Ok(local_app_data) = env::var("LOCALAPPDATA") else {
-        return results;
+/// Loads Firefox bookmarks from `places.sqlite` of every profile under APPDATA.
+///
+/// The database is locked while Firefox runs, so it is copied to the temp directory (with its
+/// write-ahead log) and read from there. `profiles` and `dedupe` work as for
+/// [`load_chrome_bookmarks`]; a profile matches by folder name, e.g. `abcd1234.default-release`,
+/// or by the name after the dot.
+pub fn load_firefox_bookmarks(profiles: &[String], dedupe: bool) -> Vec<BookmarkEntry> {
+    let mut all_entries = Vec::new();
+    for profile_dir in firefox_profile_dirs() {
+        let Some(profile_name) = profile_dir
+            .file_name()
+            .and_then(|os| os.to_str())
+            .map(|s| s.to_string())
+        else {
+            continue;
+        };
+        let display_name = firefox_profile_label(&profile_name);
+        if !profile_selected(profiles, &profile_name, &display_name) {
+            continue;
+        }
+        match read_places_file(
+            &profile_dir.join("places.sqlite"),
+            &profile_name,
+            &display_name,
+        ) {
+            Ok(entries) => all_entries.extend(entries),
+            Err(err) => warn!(
+                "failed to read Firefox bookmarks in {:?}: {err}",
+                profile_dir
+            ),
+        }
+    }
+
+    if dedupe {
+        let mut seen = HashSet::new();
+        all_entries.retain(|entry| seen.insert(entry.url.clone()));
+    }
+
+    apply_cached_favicons(&mut all_entries);
+    debug!("loaded {} Firefox bookmark entries", all_entries.len());
+    all_entries
+}
+
+fn firefox_profile_dirs() -> Vec<PathBuf> {
+    let Ok(app_data) = env::var("APPDATA") else {
+        return Vec::new();
+    };
+    let base_path = Path::new(&app_data)
+        .join("Mozilla")
+        .join("Firefox")
+        .join("Profiles");
+    let Ok(entries) = fs::read_dir(&base_path) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir() && path.join("places.sqlite").is_file())
+        .collect()
+}
+
+/// `default-release` for a profile folder named `abcd1234.default-release`.
+fn firefox_profile_label(folder: &str) -> String {
+    folder
+        .split_once('.')
+        .map(|(_, name)| name)
+        .filter(|name| !name.is_empty())
+        .unwrap_or(folder)
+        .to_string()
+}
+
+fn read_places_file(
+    path: &Path,
+    profile_dir: &str,
+    profile_label: &str,
+) -> Result<Vec<BookmarkEntry>, String> {
+    let temp_path = env::temp_dir().join(format!(
+        "rustlauncher-places-{}.sqlite",
+        profile_dir.replace(' ', "_")
+    ));
+    let temp_wal = PathBuf::from(format!("{}-wal", temp_path.display()));
+    fs::copy(path, &temp_path).map_err(|err| err.to_string())?;
+    // 未合并的改动还在 WAL 文件中，一并复制才能读到最新的书签
+    let wal = PathBuf::from(format!("{}-wal", path.display()));
+    let _ = fs::remove_file(&temp_wal);
+    if wal.is_file() {
+        let _ = fs::copy(&wal, &temp_wal);
+    }
+
+    let result = query_places(&temp_path, profile_dir, profile_label);
+    let _ = fs::remove_file(&temp_path);
+    let _ = fs::remove_file(&temp_wal);
+    result
+}
+
+/// GUID of the Firefox folder above the toolbar, menu and other roots.
+const FIREFOX_ROOT_GUID: &str = "root________";
+/// Children of this root are tag names, and bookmarks under them are tag assignments.
+const FIREFOX_TAGS_GUID: &str = "tags________";
+
+/// Label used in folder paths for Firefox's built-in root folders.
+fn firefox_root_label(guid: &str) -> Option<&'static str> {
+    match guid {
+        "toolbar_____" => Some("书签工具栏"),
+        "menu________" => Some("书签菜单"),
+        "unfiled_____" => Some("其他书签"),
+        "mobile______" => Some("移动设备书签"),
+        _ => None,
+    }
+}
+
+/// Folder id to `(parent id, title, guid)` for every folder in `moz_bookmarks`.
+type FirefoxFolders = HashMap<i64, (i64, String, String)>;
+
+fn query_places(
+    path: &Path,
+    profile_dir: &str,
+    profile_label: &str,
+) -> Result<Vec<BookmarkEntry>, String> {
+    // 复制出的文件带有 WAL，只读打开时 SQLite 仍需创建共享内存文件，因此以读写方式打开临时副本
+    let connection = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_WRITE)
+        .map_err(|err| err.to_string())?;
+
+    let mut folders: FirefoxFolders = HashMap::new();
+    {
+        let mut statement = connection
+            .prepare("SELECT id, parent, IFNULL(title, ''), guid FROM moz_bookmarks WHERE type = 2")
+            .map_err(|err| err.to_string())?;
+        let rows = statement
+            .query_map([], |row| {
+                Ok((
+                    row.get::<_, i64>(0)?,
+                    row.get::<_, i64>(1)?,
+                    row.get::<_, String>(2)?,
+                    row.get::<_, String>(3)?,
+                ))
+            })
+            .map_err(|err| err.to_string())?;
+        for (id, parent, title, guid) in rows.flatten() {
+            folders.insert(id, (parent, title, guid));
+        }
+    }
+
+    let mut statement = connection
+        .prepare(
+            "SELECT b.guid, IFNULL(b.title, ''), p.url, b.dateAdded, b.parent \
+             FROM moz_bookmarks b JOIN moz_places p ON p.id = b.fk \
+             WHERE b.type = 1 ORDER BY b.parent, b.position",
+        )
+        .map_err(|err| err.to_string())?;
+    let rows = statement
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, Option<i64>>(3)?,
+                row.get::<_, i64>(4)?,
+            ))
+        })
+        .map_err(|err| err.to_string())?;
+
+    let mut entries = Vec::new();
+    for (guid, title, url, date_added, parent) in rows.flatten() {
+        let title = title.trim();
+        let url = url.trim();
+        if title.is_empty() || !is_supported_url(url) {
+            continue;
+        }
+        let Some(folder_path) = firefox_folder_path(&folders, parent) else {
+            continue;
+        };
+        let folder_path = (!folder_path.is_empty()).then_some(folder_path);
+        let keywords =
+            bookmark_keywords(title, url, folder_path.as_deref(), profile_label, FIREFOX);
+        entries.push(BookmarkEntry {
+            id: format!("{profile_dir}:{guid}"),
+            title: title.to_string(),
+            url: url.to_string(),
+            folder_path,
+            browser: FIREFOX.to_string(),
+            profile: profile_label.to_string(),
+            keywords,
+            icon_b64: String::new(),
+            // Firefox 以微秒记录 Unix 时间
+            date_added: date_added
+                .filter(|micros| *micros > 0)
+                .map(|micros| micros / 1_000_000),
+        });
+    }
+    Ok(entries)
+}
+
+/// Joins the folder titles from the root down to `folder`, e.g. `书签工具栏 / Work`. `None`
+/// for bookmarks under the tags root, which are tag assignments rather than bookmarks.
+fn firefox_folder_path(folders: &FirefoxFolders, mut folder: i64) -> Option<String> {
+    let mut segments = Vec::new();
+    // 父链最多几十层，限制步数以防数据损坏形成环
+    for _ in 0..64 {
+        let Some((parent, title, guid)) = folders.get(&folder) else {
+            break;
+        };
+        if guid == FIREFOX_TAGS_GUID {
+            return None;
+        }
+        if guid == FIREFOX_ROOT_GUID {
+            break;
+        }
+        if let Some(label) = firefox_root_label(guid) {
+            segments.push(label.to_string());
+            break;
+        }
+        let title = title.trim();
+        if !title.is_empty() {
+            segments.push(title.to_string());
+        }
+        folder = *parent;
+    }
+    segments.reverse();
+    Some(segments.join(" / "))
+}
+
+/// Directories whose contents should be watched for bookmark changes. They may not exist yet
+/// (e.g. the browser was never started), so callers must tolerate missing paths.
+pub fn bookmark_watch_roots() -> Vec<PathBuf> {
+    chrome_user_data_dir()
+        .into_iter()
+        .chain(edge_user_data_dir())
+        .collect()
+}
+
+fn chrome_user_data_dir() -> Option<PathBuf> {
+    let local_app_data = env::var("LOCALAPPDATA").ok()?;
+    Some(
+        Path::new(&local_app_data)
+            .join("Google")
+            .join("Chrome")
+            .join("User Data"),
+    )
+}
+
+fn edge_user_data_dir() -> Option<PathBuf> {
+    let local_app_data = env::var("LOCALAPPDATA").ok()?;
+    Some(
+        Path::new(&local_app_data)
+            .join("Microsoft")
+            .join("Edge")
+            .join("User Data"),
+    )
+}
+
+/// Reads `(folder, display name)` pairs from `profile.info_cache` in the `Local State` file of
+/// a Chromium `User Data` directory.
+fn chromium_profile_names(user_data_dir: &Path, browser: &str) -> Vec<(String, String)> {
+    let local_state_path = user_data_dir.join("Local State");
+    let Ok(content) = fs::read_to_string(&local_state_path) else {
+        return Vec::new();
+    };
+    let Ok(json) = serde_json::from_str::<Value>(&content) else {
+        warn!(
+            "failed to parse {browser} Local State {:?}",
+            local_state_path
+        );
+        return Vec::new();
     };
-    let base_path = Path::new(&local_app_data)
-        .join("Google")
-        .join("Chrome")
-        .join("User Data");
-    if !base_path.is_dir() {
+
+    json.pointer("/profile/info_cache")
+        .and_then(|value| value.as_object())
+        .map(|cache| {
+            cache
+                .iter()
+                .filter_map(|(dir, info)| {
+                    let name = info.get("name")?.as_str()?.trim();
+                    (!name.is_empty()).then(|| (dir.clone(), name.to_string()))
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn profile_selected(profiles: &[String], folder: &str, display_name: &str) -> bool {
+    profiles.is_empty()
+        || profiles.iter().any(|wanted| {
+            let wanted = wanted.trim();
+            wanted.eq_ignore_ascii_case(folder) || wanted.eq_ignore_ascii_case(display_name)
+        })
+}
+
+fn chromium_profile_dirs(user_data_dir: &Path) -> Vec<PathBuf> {
+    let mut results = Vec::new();
+    if !user_data_dir.is_dir() {
         return results;
     }
 
-    if let Ok(entries) = fs::read_dir(&base_path) {
+    if let Ok(entries) = fs::read_dir(user_data_dir) {
         for entry in entries.flatten() {
             let path = entry.path();
             if path.is_dir() && path.join("Bookmarks").is_file() {
@@ -82,30 +568,42 @@ fn chrome_profile_dirs() -> Vec<PathBuf> {
     results
 }
 
-fn collect_entries_from_file(json: &Value, profile_label: &str, acc: &mut Vec<BookmarkEntry>) {
+fn collect_entries_from_file(
+    json: &Value,
+    profile: &ProfileLabels<'_>,
+    acc: &mut Vec<BookmarkEntry>,
+) {
     let Some(roots) = json.get("roots").and_then(|value| value.as_object()) else {
         return;
     };
 
     for (key, node) in roots.iter() {
-        let mut path_stack = vec![profile_label.to_string()];
+        let mut path_stack = Vec::new();
         if let Some(label) = root_display_label(key) {
             path_stack.push(label.to_string());
         }
 
         if let Some(children) = node.get("children").and_then(|value| value.as_array()) {
             for child in children {
-                collect_node(child, profile_label, &mut path_stack, acc);
+                collect_node(child, profile, &mut path_stack, acc);
             }
         } else {
-            collect_node(node, profile_label, &mut path_stack, acc);
+            collect_node(node, profile, &mut path_stack, acc);
         }
     }
 }
 
+/// Id scope (stable, derived from the profile folder), display name (shown to the user) and
+/// browser of a profile.
+struct ProfileLabels<'a> {
+    dir: &'a str,
+    display: &'a str,
+    browser: &'static str,
+}
+
 fn collect_node(
     node: &Value,
-    profile_label: &str,
+    profile: &ProfileLabels<'_>,
     path_stack: &mut Vec<String>,
     acc: &mut Vec<BookmarkEntry>,
 ) {
@@ -127,7 +625,7 @@ fn collect_node(
 
             if let Some(children) = node.get("children").and_then(|value| value.as_array()) {
                 for child in children {
-                    collect_node(child, profile_label, path_stack, acc);
+                    collect_node(child, profile, path_stack, acc);
                 }
             }
 
@@ -158,32 +656,67 @@ fn collect_node(
                 Some(path_stack.join(" / "))
             };
 
-            let mut keywords = Vec::new();
-            keywords.push(title.to_string());
-            keywords.push(url.to_string());
-            if let Some(folder) = &folder_path {
-                keywords.push(folder.clone());
-                keywords.extend(folder.split('/').map(|segment| segment.trim().to_string()));
-            }
-            keywords.push(profile_label.to_string());
-            keywords.retain(|value| !value.trim().is_empty());
-            extend_keywords_with_pinyin(&mut keywords);
-            keywords.sort();
-            keywords.dedup();
-
-            let id = derive_bookmark_id(profile_label, node, url);
+            let keywords = bookmark_keywords(
+                title,
+                url,
+                folder_path.as_deref(),
+                profile.display,
+                profile.browser,
+            );
+            let id = derive_bookmark_id(profile.dir, node, url);
             acc.push(BookmarkEntry {
                 id,
                 title: title.to_string(),
                 url: url.to_string(),
                 folder_path,
+                browser: profile.browser.to_string(),
+                profile: profile.display.to_string(),
                 keywords,
+                icon_b64: String::new(),
+                date_added: parse_chrome_timestamp(node.get("date_added")),
             });
         }
         _ => {}
     }
 }
 
+/// Search keywords of a bookmark: its title, URL, folder path and each folder, profile and
+/// browser, plus their pinyin forms.
+fn bookmark_keywords(
+    title: &str,
+    url: &str,
+    folder_path: Option<&str>,
+    profile: &str,
+    browser: &str,
+) -> Vec<String> {
+    let mut keywords = Vec::new();
+    keywords.push(title.to_string());
+    keywords.push(url.to_string());
+    if let Some(folder) = folder_path {
+        keywords.push(folder.to_string());
+        keywords.extend(folder.split('/').map(|segment| segment.trim().to_string()));
+    }
+    keywords.push(profile.to_string());
+    keywords.push(browser.to_string());
+    keywords.retain(|value| !value.trim().is_empty());
+    extend_keywords_with_pinyin(&mut keywords);
+    keywords.sort();
+    keywords.dedup();
+    keywords
+}
+
+/// Seconds between 1601-01-01 (the Windows/Chrome epoch) and 1970-01-01.
+const WINDOWS_TO_UNIX_EPOCH_SECS: i64 = 11_644_473_600;
+
+/// Chrome stores timestamps as a string of microseconds since 1601-01-01; `0` means unset.
+fn parse_chrome_timestamp(value: Option<&Value>) -> Option<i64> {
+    let micros: i64 = value?.as_str()?.trim().parse().ok()?;
+    if micros <= 0 {
+        return None;
+    }
+    Some(micros / 1_000_000 - WINDOWS_TO_UNIX_EPOCH_SECS)
+}
+
 fn root_display_label(key: &str) -> Option<&'static str> {
     match key {
         "bookmark_bar" => Some("书签栏"),
@@ -227,3 +760,184 @@ mod hex {
             .collect()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const PROFILE: ProfileLabels<'static> = ProfileLabels {
+        dir: "Default",
+        display: "默认",
+        browser: CHROME,
+    };
+
+    fn collect(root: Value) -> Vec<BookmarkEntry> {
+        let mut entries = Vec::new();
+        collect_node(&root, &PROFILE, &mut Vec::new(), &mut entries);
+        entries
+    }
+
+    fn link(name: &str, url: &str) -> Value {
+        json!({ "type": "url", "name": name, "url": url })
+    }
+
+    #[test]
+    fn folders_group_their_direct_bookmarks_in_order() {
+        let entries = collect(json!({
+            "type": "folder",
+            "name": "Bookmarks bar",
+            "children": [
+                link("Home", "https://home.example"),
+                {
+                    "type": "folder",
+                    "name": "Work",
+                    "children": [
+                        link("Mail", "https://mail.example"),
+                        {
+                            "type": "folder",
+                            "name": "Docs",
+                            "children": [link("Wiki", "https://wiki.example")],
+                        },
+                        link("Tracker", "https://tracker.example"),
+                        link("Local", "file:///C:/notes.txt"),
+                    ],
+                },
+            ],
+        }));
+        let folders = group_folders(&entries);
+
+        let summary: Vec<(&str, &str, Vec<&str>)> = folders
+            .iter()
+            .map(|folder| {
+                (
+                    folder.name.as_str(),
+                    folder.path.as_str(),
+                    folder.urls.iter().map(String::as_str).collect(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                (
+                    "Bookmarks bar",
+                    "Bookmarks bar",
+                    vec!["https://home.example"]
+                ),
+                (
+                    "Work",
+                    "Bookmarks bar / Work",
+                    vec!["https://mail.example", "https://tracker.example"]
+                ),
+                (
+                    "Docs",
+                    "Bookmarks bar / Work / Docs",
+                    vec!["https://wiki.example"]
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn bookmarks_outside_any_folder_are_not_grouped() {
+        let entries = collect(link("Home", "https://home.example"));
+        assert_eq!(entries.len(), 1);
+        assert!(group_folders(&entries).is_empty());
+    }
+
+    #[test]
+    fn same_folder_in_two_profiles_forms_two_groups() {
+        let mut entries = collect(json!({
+            "type": "folder",
+            "name": "Work",
+            "children": [link("Mail", "https://mail.example")],
+        }));
+        let mut other = entries[0].clone();
+        other.profile = "Work profile".to_string();
+        entries.push(other);
+        let folders = group_folders(&entries);
+
+        assert_eq!(folders.len(), 2);
+        assert_ne!(folders[0].id, folders[1].id);
+        assert!(folders.iter().all(|folder| folder.urls.len() == 1));
+    }
+
+    fn entry(browser: &str, title: &str, url: &str) -> BookmarkEntry {
+        BookmarkEntry {
+            id: format!("{browser}:{url}"),
+            title: title.to_string(),
+            url: url.to_string(),
+            folder_path: None,
+            browser: browser.to_string(),
+            profile: "默认".to_string(),
+            keywords: vec![title.to_string()],
+            icon_b64: String::new(),
+            date_added: None,
+        }
+    }
+
+    #[test]
+    fn same_bookmark_in_two_browsers_is_kept_once() {
+        let merged = merge_across_browsers(vec![
+            entry(CHROME, "Docs", "https://docs.example"),
+            entry(CHROME, "Mail", "https://mail.example"),
+            entry(EDGE, "Docs", "https://docs.example"),
+            entry(FIREFOX, "Docs", "https://docs.example"),
+            entry(EDGE, "Docs", "https://docs.example"),
+        ]);
+
+        assert_eq!(merged.len(), 2);
+        assert_eq!(merged[0].id, "Chrome:https://docs.example");
+        assert_eq!(merged[0].browser, "Chrome / Edge / Firefox");
+        assert!(merged[0].keywords.iter().any(|k| k == EDGE));
+        assert_eq!(merged[1].browser, CHROME);
+    }
+
+    #[test]
+    fn merging_keeps_duplicates_within_one_browser_and_different_titles() {
+        let merged = merge_across_browsers(vec![
+            entry(CHROME, "Docs", "https://docs.example"),
+            entry(CHROME, "Docs", "https://docs.example"),
+            entry(EDGE, "Documentation", "https://docs.example"),
+        ]);
+
+        assert_eq!(merged.len(), 3);
+        assert!(merged.iter().all(|entry| !entry.browser.contains(" / ")));
+    }
+
+    #[test]
+    fn chromium_date_added_converts_from_the_windows_epoch() {
+        // 2020-01-01T00:00:00Z，以 1601 年起的微秒数表示
+        let entries = collect(json!({
+            "type": "url",
+            "name": "Docs",
+            "url": "https://docs.example",
+            "date_added": "13222310400000000",
+        }));
+        assert_eq!(entries[0].date_added, Some(1_577_836_800));
+        assert_eq!(
+            parse_chrome_timestamp(Some(&json!(" 13222310400999999 "))),
+            Some(1_577_836_800)
+        );
+    }
+
+    #[test]
+    fn missing_or_garbage_date_added_is_none() {
+        assert_eq!(
+            collect(link("Docs", "https://docs.example"))[0].date_added,
+            None
+        );
+        for value in [
+            json!("0"),
+            json!("-5"),
+            json!("soon"),
+            json!(""),
+            json!(13222310400000000u64),
+            Value::Null,
+        ] {
+            assert_eq!(parse_chrome_timestamp(Some(&value)), None, "{value}");
+        }
+        assert_eq!(parse_chrome_timestamp(None), None);
+    }
+}
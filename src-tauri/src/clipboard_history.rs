@@ -0,0 +1,95 @@
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex},
+    thread,
+    time::Duration,
+};
+
+use chrono::{DateTime, Local};
+use log::warn;
+
+use crate::{
+    config::AppConfig,
+    windows_utils::{clipboard_sequence_number, read_clipboard_text},
+};
+
+/// How often the clipboard sequence number is checked while the history is enabled.
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+const IDLE_INTERVAL: Duration = Duration::from_secs(5);
+/// Longer copies are skipped so a pasted log file can't bloat the history.
+const MAX_ENTRY_CHARS: usize = 10_000;
+pub const MIN_HISTORY_SIZE: u32 = 1;
+pub const MAX_HISTORY_SIZE: u32 = 500;
+
+#[derive(Debug, Clone)]
+pub struct ClipboardEntry {
+    pub text: String,
+    pub copied_at: DateTime<Local>,
+}
+
+/// Spawns the monitor that records copied text into `history`, newest first.
+///
+/// The history lives in memory only. The task re-reads the config on every step, so toggling
+/// `enable_clipboard_history` takes effect without a restart; disabling it also clears what
+/// was recorded.
+pub fn spawn_clipboard_monitor(
+    history: Arc<Mutex<VecDeque<ClipboardEntry>>>,
+    config: Arc<Mutex<AppConfig>>,
+) {
+    let spawned = thread::Builder::new()
+        .name("clipboard-monitor".into())
+        .spawn(move || {
+            let mut last_sequence = clipboard_sequence_number();
+            loop {
+                let Some(limit) = history_limit(&config) else {
+                    if let Ok(mut guard) = history.lock() {
+                        guard.clear();
+                    }
+                    thread::sleep(IDLE_INTERVAL);
+                    // 关闭期间复制的内容不补录
+                    last_sequence = clipboard_sequence_number();
+                    continue;
+                };
+
+                thread::sleep(POLL_INTERVAL);
+                let sequence = clipboard_sequence_number();
+                if sequence == last_sequence {
+                    continue;
+                }
+                last_sequence = sequence;
+
+                if let Some(text) = read_clipboard_text() {
+                    if let Ok(mut guard) = history.lock() {
+                        record(&mut guard, text, limit);
+                    }
+                }
+            }
+        });
+
+    if let Err(err) = spawned {
+        warn!("failed to spawn clipboard monitor: {err}");
+    }
+}
+
+/// `clipboard_history_size` while the history is enabled, `None` otherwise.
+fn history_limit(config: &Mutex<AppConfig>) -> Option<usize> {
+    let cfg = config.lock().ok()?;
+    cfg.enable_clipboard_history.then(|| {
+        cfg.clipboard_history_size
+            .clamp(MIN_HISTORY_SIZE, MAX_HISTORY_SIZE) as usize
+    })
+}
+
+/// Puts `text` at the front of `history`. Text copied again moves up instead of being stored
+/// twice; blank or oversized copies are ignored.
+fn record(history: &mut VecDeque<ClipboardEntry>, text: String, limit: usize) {
+    if text.trim().is_empty() || text.chars().count() > MAX_ENTRY_CHARS {
+        return;
+    }
+    history.retain(|entry| entry.text != text);
+    history.push_front(ClipboardEntry {
+        text,
+        copied_at: Local::now(),
+    });
+    history.truncate(limit);
+}
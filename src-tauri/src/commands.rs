@@ -1,14 +1,14 @@
 use std::{
     collections::HashMap,
+    env,
     ffi::{OsStr, OsString},
-    path::Path,
+    path::{Path, PathBuf},
     ptr,
-    sync::Arc,
+    sync::{atomic::Ordering, Arc, Mutex},
+    time::{Duration, Instant},
 };
 
-use fuzzy_matcher::skim::SkimMatcherV2;
-use fuzzy_matcher::FuzzyMatcher;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use tauri::{AppHandle, Emitter, Manager, State};
 use tauri_plugin_opener::OpenerExt;
 use windows::{
@@ -26,126 +26,433 @@ use windows::{
     },
 };
 
-use crate::windows_utils::{os_str_to_wide, ComGuard};
+use crate::windows_utils::{
+    copy_png_to_clipboard, copy_text_to_clipboard, expand_env_vars, extract_icon_from_path,
+    icon_cache_dir, invalidate_cached_icon, os_str_to_wide, ComGuard,
+};
 
 use crate::{
-    bookmarks::{self, BookmarkEntry},
-    config::AppConfig,
-    hotkey::bind_hotkey,
-    indexer,
-    models::{AppType, ApplicationInfo, SearchResult},
-    state::{AppState, PendingAction},
+    app_cache,
+    bookmarks::{self, OptInBrowsers},
+    clipboard_history,
+    config::{
+        self, normalize_prefix, AdvancedConfig, AppConfig, ConfigProfiles, ContentSearchConfig,
+        EmptyEnterAction, EnterOnRunningApp, EscapeBehavior, ForceEnglishByMode, MatchAlgorithm,
+        ModeHotkey, TypedLaunchArgs, WorkingDirMode, MAX_WINDOW_OPACITY, MIN_WINDOW_OPACITY,
+    },
+    content_search,
+    custom_commands::{
+        join_windows_arguments, normalize_launch_arguments, split_windows_arguments, CustomCommand,
+    },
+    diagnostics,
+    error::{AppError, AppResult},
+    favicons, file_search, history,
+    hotkey::{self, bind_hotkey, RegisteredShortcuts},
+    i18n::{tr_with, Locale},
+    indexer::{self, ExeFilter, IndexOptions},
+    models::{
+        AppOrigin, AppPaths, AppType, ApplicationInfo, ExecuteOutcome, GroupedSearchResponse,
+        HealthReport, SearchResponse, SearchResult,
+    },
+    processes, run_history,
+    search::{self, QueryMode, SearchSources},
+    state::{AppState, PendingAction, QuickKeys},
+    tray,
+    usage::{self, UsageAnalytics, UsageEvent},
+    validation::{self, ConfigIssue},
 };
 
-const MIN_QUERY_DELAY_MS: u64 = 50;
-const MAX_QUERY_DELAY_MS: u64 = 2000;
-const MIN_RESULT_LIMIT: u32 = 10;
-const MAX_RESULT_LIMIT: u32 = 60;
-const MIN_WINDOW_OPACITY: f32 = 0.6;
-const MAX_WINDOW_OPACITY: f32 = 1.0;
+const MIN_FAVICON_MAX_AGE_HOURS: u64 = 1;
 pub const HIDE_WINDOW_EVENT: &str = "hide_window";
+/// Emitted with an [`ActionFailedPayload`] when running a result fails.
+pub const ACTION_FAILED_EVENT: &str = "action_failed";
 pub const OPEN_SETTINGS_EVENT: &str = "open_settings";
 pub const SETTINGS_UPDATED_EVENT: &str = "settings_updated";
 pub const FOCUS_INPUT_EVENT: &str = "focus_input";
+pub const CONFIG_UNAVAILABLE_EVENT: &str = "config_unavailable";
+/// Emitted with an [`crate::models::IndexDiff`] when a reindex adds apps and `notify_new_apps` is on.
+pub const NEW_APPS_DETECTED_EVENT: &str = "new_apps_detected";
 
 #[derive(Debug, Default, Deserialize)]
 pub struct SettingsUpdatePayload {
     pub global_hotkey: Option<String>,
+    pub mode_hotkeys: Option<Vec<ModeHotkey>>,
+    /// An empty string turns double tap off.
+    pub double_tap_modifier: Option<String>,
     pub query_delay_ms: Option<u64>,
     pub max_results: Option<u32>,
+    pub max_app_results: Option<u32>,
+    pub max_bookmark_results: Option<u32>,
     pub enable_app_results: Option<bool>,
+    pub extract_icons: Option<bool>,
+    pub icon_size: Option<u32>,
+    pub exclude_exe_patterns: Option<Vec<String>>,
+    pub enable_steam_games: Option<bool>,
     pub enable_bookmark_results: Option<bool>,
-    // 新增：三种模式的可配置前缀
+    pub enable_history_results: Option<bool>,
+    pub enable_file_results: Option<bool>,
+    pub file_search_roots: Option<Vec<String>>,
+    pub enable_clipboard_history: Option<bool>,
+    pub clipboard_history_size: Option<u32>,
+    pub max_history_entries: Option<u32>,
+    pub max_history_results: Option<u32>,
+    pub max_visible_results: Option<u32>,
+    // 新增：各模式的可配置前缀
     pub prefix_app: Option<String>,
     pub prefix_bookmark: Option<String>,
     pub prefix_search: Option<String>,
+    pub prefix_file: Option<String>,
+    pub prefix_clipboard: Option<String>,
+    pub prefix_run: Option<String>,
     pub launch_on_startup: Option<bool>,
     pub force_english_input: Option<bool>,
+    pub force_english_first_show_only: Option<bool>,
     pub debug_mode: Option<bool>,
     pub window_opacity: Option<f32>,
+    pub fallback_on_empty_results: Option<bool>,
+    pub empty_enter_action: Option<EmptyEnterAction>,
+    pub search_engine_template: Option<String>,
+    pub search_engine_name: Option<String>,
+    pub search_bangs: Option<HashMap<String, String>>,
+    pub enable_instant_answers: Option<bool>,
+    pub clear_query_on_hide: Option<bool>,
+    pub watch_bookmarks: Option<bool>,
+    pub reindex_interval_minutes: Option<u64>,
+    pub bookmark_browsers: Option<Vec<String>>,
+    pub enable_edge_bookmarks: Option<bool>,
+    pub enable_firefox_bookmarks: Option<bool>,
+    pub bookmark_profiles: Option<Vec<String>>,
+    pub dedupe_bookmarks_across_profiles: Option<bool>,
+    pub favicon_refresh_enabled: Option<bool>,
+    pub favicon_max_age_hours: Option<u64>,
+    pub relative_score_cutoff: Option<f32>,
+    pub prefix_boost: Option<i64>,
+    pub usage_boost: Option<f32>,
+    pub keyword_penalty: Option<i64>,
+    pub path_penalty: Option<i64>,
+    pub url_penalty: Option<i64>,
+    pub min_score_threshold: Option<i64>,
+    pub match_algorithm: Option<MatchAlgorithm>,
+    pub ignore_case: Option<bool>,
+    pub ignore_accents: Option<bool>,
+    pub regex_queries: Option<bool>,
+    pub exclude_unavailable_drives: Option<bool>,
+    pub bookmark_recency_tiebreak: Option<bool>,
+    pub prewarm_icons: Option<bool>,
+    pub notify_new_apps: Option<bool>,
+    pub advanced: Option<AdvancedConfig>,
+    pub content_search: Option<ContentSearchConfig>,
+    pub custom_commands: Option<Vec<CustomCommand>>,
+    pub force_english_by_mode: Option<ForceEnglishByMode>,
+    pub escape_behavior: Option<EscapeBehavior>,
+    pub locale: Option<Locale>,
+    pub working_dir_mode: Option<WorkingDirMode>,
+    pub custom_working_dir: Option<String>,
+    pub launch_cooldown_ms: Option<u64>,
+    pub bookmark_folder_confirm_threshold: Option<u32>,
+    pub enter_on_running_app: Option<EnterOnRunningApp>,
+    pub typed_launch_args: Option<TypedLaunchArgs>,
+    pub nav_wrap: Option<bool>,
+    pub page_jump_size: Option<u32>,
 }
 
-#[derive(Clone, Copy, PartialEq, Eq)]
-enum QueryMode {
-    All,
-    Bookmark,
-    Application,
-    Search,
+/// Ranking settings a tuning screen can try out with [`preview_query_with_config`] before
+/// saving them through `update_settings`. Values are normalized the same way as there.
+#[derive(Debug, Default, Deserialize)]
+pub struct RelevanceOverrides {
+    pub max_results: Option<u32>,
+    pub max_app_results: Option<u32>,
+    pub max_bookmark_results: Option<u32>,
+    pub max_history_results: Option<u32>,
+    pub max_visible_results: Option<u32>,
+    pub relative_score_cutoff: Option<f32>,
+    pub prefix_boost: Option<i64>,
+    pub usage_boost: Option<f32>,
+    pub keyword_penalty: Option<i64>,
+    pub path_penalty: Option<i64>,
+    pub url_penalty: Option<i64>,
+    pub min_score_threshold: Option<i64>,
+    pub match_algorithm: Option<MatchAlgorithm>,
+    pub ignore_case: Option<bool>,
+    pub ignore_accents: Option<bool>,
+    pub bookmark_recency_tiebreak: Option<bool>,
+    pub regex_queries: Option<bool>,
 }
 
-impl QueryMode {
-    fn from_option(mode: Option<String>) -> Self {
-        match mode
-            .as_deref()
-            .map(|value| value.trim().to_lowercase())
-            .as_deref()
-        {
-            Some("bookmark") | Some("bookmarks") | Some("b") => Self::Bookmark,
-            Some("app") | Some("apps") | Some("application") | Some("r") => Self::Application,
-            Some("search") | Some("s") => Self::Search,
-            _ => Self::All,
+impl RelevanceOverrides {
+    fn apply(&self, config: &mut AppConfig) {
+        config.max_results =
+            normalize_max_results(self.max_results, config.max_results, &config.advanced);
+        if let Some(value) = self.max_app_results {
+            config.max_app_results = value;
+        }
+        if let Some(value) = self.max_bookmark_results {
+            config.max_bookmark_results = value;
+        }
+        if let Some(value) = self.max_history_results {
+            config.max_history_results = value;
+        }
+        if let Some(value) = self.max_visible_results {
+            config.max_visible_results = value;
+        }
+        if let Some(value) = self.relative_score_cutoff {
+            config.relative_score_cutoff = clamp_relative_score_cutoff(value);
+        }
+        if let Some(value) = self.prefix_boost {
+            config.prefix_boost = value.max(0);
+        }
+        if let Some(value) = self.usage_boost {
+            config.usage_boost = clamp_usage_boost(value);
+        }
+        if let Some(value) = self.keyword_penalty {
+            config.keyword_penalty = value.max(0);
         }
+        if let Some(value) = self.path_penalty {
+            config.path_penalty = value.max(0);
+        }
+        if let Some(value) = self.url_penalty {
+            config.url_penalty = value.max(0);
+        }
+        if let Some(value) = self.min_score_threshold {
+            config.min_score_threshold = value.max(0);
+        }
+        if let Some(value) = self.match_algorithm {
+            config.match_algorithm = value;
+        }
+        if let Some(value) = self.ignore_case {
+            config.ignore_case = value;
+        }
+        if let Some(value) = self.ignore_accents {
+            config.ignore_accents = value;
+        }
+        if let Some(value) = self.bookmark_recency_tiebreak {
+            config.bookmark_recency_tiebreak = value;
+        }
+        if let Some(value) = self.regex_queries {
+            config.regex_queries = value;
+        }
+    }
+}
+
+/// Payload of [`HIDE_WINDOW_EVENT`], telling the frontend whether to reset the search.
+#[derive(Debug, Clone, Serialize)]
+pub struct HideWindowPayload {
+    pub clear_query: bool,
+    pub reason: HideReason,
+}
+
+/// Payload of [`ACTION_FAILED_EVENT`].
+#[derive(Debug, Serialize)]
+pub struct ActionFailedPayload<'a> {
+    /// The result that was run; `None` for the empty-query fallback.
+    pub id: Option<&'a str>,
+    pub error: &'a AppError,
+}
+
+/// Payload of [`FOCUS_INPUT_EVENT`].
+#[derive(Debug, Clone, Serialize)]
+pub struct FocusInputPayload {
+    /// [`QueryMode::label`] of the mode a mode hotkey opened the launcher in; `None` keeps the
+    /// current input.
+    pub mode: Option<&'static str>,
+}
+
+/// Why the launcher is being hidden. Only [`HideReason::User`] is subject to `escape_behavior`;
+/// the other reasons are programmatic and always hide.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HideReason {
+    User,
+    Blur,
+    Tray,
+    Action,
+}
+
+/// Ranks `query` against the indexes and makes the ranking the current result set.
+///
+/// Calls are debounced by `query_delay_ms`: each waits that long first and gives up, without
+/// touching the indexes, when a newer query arrived meanwhile. The frontend then drops the
+/// response because its generation is not newer than what it shows.
+#[tauri::command]
+pub async fn submit_query(
+    query: String,
+    mode: Option<String>,
+    state: State<'_, AppState>,
+) -> AppResult<SearchResponse> {
+    run_query(query, mode, &state, false).await
+}
+
+/// Same as [`submit_query`], but with the results bucketed by category (apps, bookmarks,
+/// web search, URL, instant answers) for a UI with section headers. Quick keys follow the
+/// grouped order.
+#[tauri::command]
+pub async fn submit_query_grouped(
+    query: String,
+    mode: Option<String>,
+    state: State<'_, AppState>,
+) -> AppResult<GroupedSearchResponse> {
+    let response = run_query(query, mode, &state, true).await?;
+    Ok(GroupedSearchResponse {
+        groups: search::group_results(response.results),
+        stale_index: response.stale_index,
+        generation: response.generation,
+    })
+}
+
+/// Debounces, ranks and publishes a query. With `grouped` the results are published in the
+/// order [`submit_query_grouped`] displays them, so quick keys line up with that layout.
+async fn run_query(
+    query: String,
+    mode: Option<String>,
+    state: &AppState,
+    grouped: bool,
+) -> AppResult<SearchResponse> {
+    let ticket = next_query_ticket(state);
+    if !debounce_query(state, ticket).await {
+        return Ok(publish_results(
+            state,
+            ticket,
+            Vec::new(),
+            HashMap::new(),
+            false,
+        ));
+    }
+    // 先读取标记再查询：查询期间刚完成的重建最多导致一次多余的提示
+    let stale_index = state.is_reindexing();
+    state
+        .query_non_latin
+        .store(crate::has_non_latin(&query), Ordering::Release);
+    if let Ok(mut guard) = state.last_query_mode.lock() {
+        *guard = Some(QueryMode::from_option(mode.clone()));
+    }
+    let (mut results, pending_actions) = collect_results(&query, mode, state, None);
+    if grouped {
+        results = search::order_by_group(results);
+    }
+
+    Ok(publish_results(
+        state,
+        ticket,
+        results,
+        pending_actions,
+        stale_index,
+    ))
+}
+
+fn next_query_ticket(state: &AppState) -> u64 {
+    state.query_ticket.fetch_add(1, Ordering::AcqRel) + 1
+}
+
+/// Waits `query_delay_ms`, then reports whether `ticket` is still the latest query.
+async fn debounce_query(state: &AppState, ticket: u64) -> bool {
+    let delay_ms = state
+        .config
+        .lock()
+        .map(|cfg| cfg.query_delay_ms)
+        .unwrap_or_default();
+    if delay_ms > 0 {
+        tokio::time::sleep(Duration::from_millis(delay_ms)).await;
+    }
+    state.query_ticket.load(Ordering::Acquire) == ticket
+}
+
+/// Makes `results` the current result set: records their pending actions and quick keys
+/// under a new query generation and wraps them in the response.
+///
+/// When a newer query started after `ticket` was taken, nothing is recorded and the response
+/// keeps the current generation, so the frontend drops it instead of showing older results.
+fn publish_results(
+    state: &AppState,
+    ticket: u64,
+    results: Vec<SearchResult>,
+    pending_actions: HashMap<String, PendingAction>,
+    stale_index: bool,
+) -> SearchResponse {
+    if state.query_ticket.load(Ordering::Acquire) != ticket {
+        return SearchResponse {
+            results,
+            stale_index,
+            generation: state.query_generation.load(Ordering::Acquire),
+        };
     }
 
-    fn allows_bookmarks(&self) -> bool {
-        matches!(self, Self::All | Self::Bookmark)
+    if let Ok(mut guard) = state.pending_actions.lock() {
+        guard.clear();
+        guard.extend(pending_actions);
+    } else {
+        log::warn!("无法记录搜索结果缓存，可能导致执行失败");
     }
 
-    fn allows_applications(&self) -> bool {
-        matches!(self, Self::All | Self::Application)
+    let generation = state.query_generation.fetch_add(1, Ordering::AcqRel) + 1;
+    if let Ok(mut guard) = state.quick_keys.lock() {
+        guard.generation = generation;
+        guard.ids = results
+            .iter()
+            .filter(|result| result.quick_key.is_some())
+            .map(|result| result.id.clone())
+            .collect();
     }
 
-    fn allows_web_search(&self) -> bool {
-        matches!(self, Self::All | Self::Search)
+    SearchResponse {
+        results,
+        stale_index,
+        generation,
     }
 }
 
+/// Runs the same ranking as [`submit_query`] without recording pending actions,
+/// so tuning screens can inspect scores without side effects.
 #[tauri::command]
-pub fn submit_query(
+pub fn preview_query(
     query: String,
     mode: Option<String>,
     state: State<'_, AppState>,
 ) -> Vec<SearchResult> {
-    let trimmed = query.trim();
-    if trimmed.is_empty() {
-        return Vec::new();
-    }
+    collect_results(&query, mode, &state, None).0
+}
 
+/// Like [`preview_query`], but ranks with the stored config merged with `overrides`. The merged
+/// config only lives for this call; nothing is saved.
+#[tauri::command]
+pub fn preview_query_with_config(
+    query: String,
+    mode: Option<String>,
+    config_overrides: RelevanceOverrides,
+    state: State<'_, AppState>,
+) -> Vec<SearchResult> {
+    collect_results(&query, mode, &state, Some(&config_overrides)).0
+}
+
+/// Snapshots the indexes allowed by `mode` and hands them to the pure ranking core, applying
+/// `overrides` to the config snapshot only.
+fn collect_results(
+    query: &str,
+    mode: Option<String>,
+    state: &AppState,
+    overrides: Option<&RelevanceOverrides>,
+) -> (Vec<SearchResult>, HashMap<String, PendingAction>) {
     let query_mode = QueryMode::from_option(mode);
-    let config_snapshot = state
+    // 剪贴板模式只输入前缀时也列出全部历史
+    if query_mode == QueryMode::Clipboard {
+        return clipboard_query_results(query, state);
+    }
+    // 运行模式只输入前缀时列出最近运行的命令
+    if query_mode == QueryMode::Run {
+        return run_query_results(query, state);
+    }
+    if query.trim().is_empty() {
+        return (Vec::new(), HashMap::new());
+    }
+
+    let mut config_snapshot = state
         .config
         .lock()
         .map(|cfg| cfg.clone())
         .unwrap_or_default();
-    let include_apps = config_snapshot.enable_app_results;
-    let include_bookmarks = config_snapshot.enable_bookmark_results;
-    let mut result_limit = config_snapshot
-        .max_results
-        .clamp(MIN_RESULT_LIMIT, MAX_RESULT_LIMIT) as usize;
-    if result_limit == 0 {
-        result_limit = MIN_RESULT_LIMIT as usize;
-    }
-
-    let mut results = Vec::new();
-    let mut counter = 0usize;
-    let mut pending_actions: HashMap<String, PendingAction> = HashMap::new();
-
-    if is_url_like(trimmed) {
-        let result_id = format!("url-{counter}");
-        pending_actions.insert(result_id.clone(), PendingAction::Url(trimmed.to_string()));
-        results.push(SearchResult {
-            id: result_id,
-            title: format!("打开网址: {trimmed}"),
-            subtitle: trimmed.to_string(),
-            icon: String::new(),
-            score: 200,
-            action_id: "url".to_string(),
-        });
-        counter += 1;
+    if let Some(overrides) = overrides {
+        overrides.apply(&mut config_snapshot);
     }
-
-    let matcher = SkimMatcherV2::default();
-    let apps = if query_mode.allows_applications() && include_apps {
+    let apps = if query_mode.allows_applications() && config_snapshot.enable_app_results {
         Some(
             state
                 .app_index
@@ -156,7 +463,7 @@ pub fn submit_query(
     } else {
         None
     };
-    let bookmarks = if query_mode.allows_bookmarks() && include_bookmarks {
+    let bookmarks = if query_mode.allows_bookmarks() && config_snapshot.enable_bookmark_results {
         Some(
             state
                 .bookmark_index
@@ -167,312 +474,2179 @@ pub fn submit_query(
     } else {
         None
     };
+    let bookmark_folders = bookmarks.as_ref().and_then(|_| {
+        state
+            .bookmark_folders
+            .lock()
+            .ok()
+            .map(|folders| folders.clone())
+    });
 
-    if let Some(apps) = apps.as_ref() {
-        for app in apps.iter() {
-            if let Some(score) = match_application(&matcher, app, trimmed) {
-                counter += 1;
-                let result_id = format!("app-{}", app.id);
-                pending_actions.insert(result_id.clone(), PendingAction::Application(app.clone()));
-                let subtitle = app
-                    .description
-                    .clone()
-                    .filter(|d| !d.is_empty())
-                    .or_else(|| app.source_path.clone())
-                    .unwrap_or_else(|| app.path.clone());
-                results.push(SearchResult {
-                    id: result_id,
-                    title: app.name.clone(),
-                    subtitle,
-                    icon: app.icon_b64.clone(),
-                    score,
-                    action_id: match app.app_type {
-                        AppType::Win32 => "app".to_string(),
-                        AppType::Uwp => "uwp".to_string(),
-                    },
-                });
-            }
-        }
-    }
+    // 仅在有应用参与排序时才读取进程快照
+    let running_exes = apps.as_ref().and_then(|_| {
+        state
+            .process_cache
+            .lock()
+            .ok()
+            .map(|mut cache| cache.running_exe_paths())
+    });
 
-    if let Some(bookmarks) = bookmarks.as_ref() {
-        for bookmark in bookmarks.iter() {
-            if let Some(score) = match_bookmark(&matcher, bookmark, trimmed) {
-                counter += 1;
-                let subtitle = match &bookmark.folder_path {
-                    Some(path) => format!("收藏夹 · {path} · {}", bookmark.url),
-                    None => format!("收藏夹 · {}", bookmark.url),
-                };
-                let result_id = format!("bookmark-{}", bookmark.id);
-                pending_actions
-                    .insert(result_id.clone(), PendingAction::Bookmark(bookmark.clone()));
-                results.push(SearchResult {
-                    id: result_id,
-                    title: bookmark.title.clone(),
-                    subtitle,
-                    icon: String::new(),
-                    score,
-                    action_id: "bookmark".to_string(),
-                });
-            }
-        }
-    }
+    let available_drives = apps.as_ref().and_then(|_| {
+        state
+            .drive_cache
+            .lock()
+            .ok()
+            .map(|mut cache| cache.available_letters())
+    });
+
+    let launch_counts = state
+        .launch_counts
+        .lock()
+        .map(|counts| counts.clone())
+        .unwrap_or_default();
+
+    let hidden_ids = state
+        .session_hidden
+        .lock()
+        .map(|hidden| hidden.clone())
+        .unwrap_or_default();
 
-    results.sort_by(|a, b| b.score.cmp(&a.score));
-    if result_limit > 1 && results.len() >= result_limit {
-        results.truncate(result_limit - 1);
+    // 历史记录与书签同属浏览器数据，沿用书签模式的范围
+    let history = if query_mode.allows_bookmarks() && config_snapshot.enable_history_results {
+        Some(
+            state
+                .history_index
+                .lock()
+                .expect("failed to lock history index")
+                .clone(),
+        )
     } else {
-        results.truncate(result_limit);
+        None
+    };
+
+    search::rank_results(
+        query,
+        query_mode,
+        &config_snapshot,
+        SearchSources {
+            apps: apps.as_deref(),
+            bookmarks: bookmarks.as_deref(),
+            bookmark_folders: bookmark_folders.as_deref(),
+            history: history.as_deref(),
+            running_exes: running_exes.as_ref(),
+            available_drives: available_drives.as_ref(),
+            hidden_ids: (!hidden_ids.is_empty()).then_some(&hidden_ids),
+            launch_counts: (!launch_counts.is_empty()).then_some(&launch_counts),
+            now: config_snapshot
+                .enable_instant_answers
+                .then(chrono::Local::now),
+        },
+    )
+}
+
+/// Searches file contents with ripgrep when the query carries the content-search prefix.
+///
+/// Starting a new content query cancels the previous ripgrep run. Like [`submit_query`], the
+/// returned results replace the pending actions.
+#[tauri::command]
+pub async fn submit_content_query(
+    query: String,
+    state: State<'_, AppState>,
+) -> AppResult<SearchResponse> {
+    let ticket = next_query_ticket(&state);
+    if !debounce_query(&state, ticket).await {
+        return Ok(publish_results(
+            &state,
+            ticket,
+            Vec::new(),
+            HashMap::new(),
+            false,
+        ));
+    }
+    let config = state
+        .config
+        .lock()
+        .map(|cfg| cfg.clone())
+        .map_err(|_| "无法获取配置".to_string())?;
+    if !config.content_search.enabled {
+        return Err(AppError::InvalidSetting("内容搜索未启用".into()));
     }
+    let Some(pattern) = config
+        .content_search
+        .strip_prefix(&query)
+        .map(str::to_string)
+    else {
+        return Ok(publish_results(
+            &state,
+            ticket,
+            Vec::new(),
+            HashMap::new(),
+            false,
+        ));
+    };
 
-    // 仅在允许的模式下追加 Web 搜索结果
-    if query_mode.allows_web_search() {
-        let search_id = format!("search-{counter}");
-        let search_url = format!(
-            "https://google.com/search?q={}",
-            urlencoding::encode(trimmed)
-        );
-        pending_actions.insert(search_id.clone(), PendingAction::Search(search_url.clone()));
-        results.push(SearchResult {
-            id: search_id,
-            title: format!("在 Google 上搜索: {trimmed}"),
-            subtitle: String::from("Google 搜索"),
-            icon: String::new(),
-            score: i64::MIN,
-            action_id: "search".to_string(),
-        });
+    let generation = Arc::clone(&state.content_search_generation);
+    let expected_generation = generation.fetch_add(1, Ordering::AcqRel) + 1;
+    let content_config = config.content_search.clone();
+    let matches = tauri::async_runtime::spawn_blocking(move || {
+        content_search::run_ripgrep(&content_config, &pattern, &generation, expected_generation)
+    })
+    .await
+    .map_err(|err| err.to_string())??;
+
+    let (results, pending_actions) = search::content_results(&matches, config.locale);
+    Ok(publish_results(
+        &state,
+        ticket,
+        results,
+        pending_actions,
+        false,
+    ))
+}
+
+/// Searches file and folder names when the launcher is in file mode.
+///
+/// The scan runs on a blocking thread and is abandoned once a newer query starts. Like
+/// [`submit_query`], the returned results replace the pending actions.
+#[tauri::command]
+pub async fn submit_file_query(
+    query: String,
+    state: State<'_, AppState>,
+) -> AppResult<SearchResponse> {
+    let ticket = next_query_ticket(&state);
+    if !debounce_query(&state, ticket).await {
+        return Ok(publish_results(
+            &state,
+            ticket,
+            Vec::new(),
+            HashMap::new(),
+            false,
+        ));
+    }
+    let config = state
+        .config
+        .lock()
+        .map(|cfg| cfg.clone())
+        .map_err(|_| "无法获取配置".to_string())?;
+    if !config.enable_file_results {
+        return Err(AppError::InvalidSetting("文件搜索未启用".into()));
+    }
+    if let Ok(mut guard) = state.last_query_mode.lock() {
+        *guard = Some(QueryMode::File);
     }
 
-    if let Ok(mut guard) = state.pending_actions.lock() {
-        guard.clear();
-        guard.extend(pending_actions);
-    } else {
-        log::warn!("无法记录搜索结果缓存，可能导致执行失败");
+    let roots = file_search::search_roots(&config.file_search_roots);
+    let (min_limit, max_limit) = config.advanced.result_limit_bounds();
+    let max_results = config.max_results.clamp(min_limit, max_limit) as usize;
+    let query_ticket = Arc::clone(&state.query_ticket);
+    let pattern = query.trim().to_string();
+    let matches = tauri::async_runtime::spawn_blocking(move || {
+        file_search::search_files(&roots, &pattern, max_results, &query_ticket, ticket)
+    })
+    .await
+    .map_err(|err| err.to_string())??;
+
+    let (results, pending_actions) = search::file_results(&matches, config.locale);
+    Ok(publish_results(
+        &state,
+        ticket,
+        results,
+        pending_actions,
+        false,
+    ))
+}
+
+fn clipboard_query_results(
+    query: &str,
+    state: &AppState,
+) -> (Vec<SearchResult>, HashMap<String, PendingAction>) {
+    let Ok(config) = state.config.lock().map(|cfg| cfg.clone()) else {
+        return (Vec::new(), HashMap::new());
+    };
+    if !config.enable_clipboard_history {
+        return (Vec::new(), HashMap::new());
     }
+    let (min_limit, max_limit) = config.advanced.result_limit_bounds();
+    let max_results = config.max_results.clamp(min_limit, max_limit) as usize;
+    let entries: Vec<_> = state
+        .clipboard_history
+        .lock()
+        .map(|history| history.iter().cloned().collect())
+        .unwrap_or_default();
+    search::clipboard_results(&entries, query.trim(), max_results, config.locale)
+}
 
-    results
+fn run_query_results(
+    query: &str,
+    state: &AppState,
+) -> (Vec<SearchResult>, HashMap<String, PendingAction>) {
+    let Ok(config) = state.config.lock().map(|cfg| cfg.clone()) else {
+        return (Vec::new(), HashMap::new());
+    };
+    let history = state
+        .run_history
+        .lock()
+        .map(|entries| entries.clone())
+        .unwrap_or_default();
+    let (min_limit, max_limit) = config.advanced.result_limit_bounds();
+    let max_results = config.max_results.clamp(min_limit, max_limit) as usize;
+    search::run_results(query, &history, max_results, config.locale)
 }
 
+/// Runs the pending action of a result.
+///
+/// For a Win32 app that is already running, `enter_on_running_app` decides between launching
+/// another instance, focusing the existing window, or returning
+/// [`ExecuteOutcome::NeedsConfirmation`]; `running_choice` overrides the setting once the user
+/// has answered that prompt.
+///
+/// Opening a bookmark folder with more links than `bookmark_folder_confirm_threshold` returns
+/// [`ExecuteOutcome::NeedsApproval`] unless `approved` is set.
+///
+/// Repeating the same id within `launch_cooldown_ms` is treated as a bounced key press and
+/// reports [`ExecuteOutcome::Done`] without running anything.
 #[tauri::command]
 pub async fn execute_action(
     id: String,
+    keep_open: Option<bool>,
+    running_choice: Option<EnterOnRunningApp>,
+    approved: Option<bool>,
     app_handle: AppHandle,
     state: State<'_, AppState>,
-) -> Result<(), String> {
-    let action = {
-        let guard = state
-            .pending_actions
-            .lock()
-            .map_err(|_| "无法访问待执行队列".to_string())?;
-        guard
-            .get(&id)
-            .cloned()
-            .ok_or_else(|| "结果已失效，请重新搜索".to_string())?
-    };
+) -> AppResult<ExecuteOutcome> {
+    run_result(
+        &app_handle,
+        &state,
+        &id,
+        keep_open.unwrap_or(false),
+        running_choice,
+        approved.unwrap_or(false),
+    )
+}
 
-    match action {
-        PendingAction::Application(app) => match app.app_type {
-            AppType::Win32 => launch_win32_app(&app)?,
-            AppType::Uwp => launch_uwp_app(&app.path)?,
-        },
-        PendingAction::Bookmark(entry) => open_url(&app_handle, &entry.url)?,
-        PendingAction::Url(url) | PendingAction::Search(url) => {
-            open_url(&app_handle, &url)?;
+/// The shared core of [`execute_action`] and [`execute_quick`].
+fn run_result(
+    app_handle: &AppHandle,
+    state: &AppState,
+    id: &str,
+    keep_open: bool,
+    running_choice: Option<EnterOnRunningApp>,
+    approved: bool,
+) -> AppResult<ExecuteOutcome> {
+    let action = resolve_pending_action(state, id)?;
+    if !approved {
+        if let Some(prompt) = bulk_open_prompt(state, &action) {
+            return Ok(ExecuteOutcome::NeedsApproval { prompt });
         }
     }
-
-    if let Some(window) = app_handle.get_webview_window("main") {
-        let _ = window.hide();
+    let cooldown = launch_cooldown(state);
+    // 提前检查一次，避免重复按键弹出两次确认
+    if is_repeated_execution(state, id, cooldown) {
+        log::debug!("忽略冷却时间内的重复执行: {id}");
+        return Ok(ExecuteOutcome::Done);
     }
 
-    let _ = app_handle.emit(HIDE_WINDOW_EVENT, ());
+    let mut focus_existing = false;
+    if let PendingAction::Application(app) = &action {
+        let (setting, locale) = state
+            .config
+            .lock()
+            .map(|cfg| (cfg.enter_on_running_app, cfg.locale))
+            .unwrap_or_default();
+        let choice = running_choice.unwrap_or(setting);
+        match running_app_step(app, choice, processes::is_running) {
+            RunningAppStep::Launch => {}
+            RunningAppStep::Focus => focus_existing = true,
+            RunningAppStep::Ask => {
+                return Ok(ExecuteOutcome::NeedsConfirmation {
+                    prompt: tr_with(locale, "prompt.focus_running", &app.name),
+                });
+            }
+        }
+    }
+    // 启动前占用冷却时间，两次并发调用只有一次能通过
+    if !claim_execution(state, id, cooldown, Instant::now()) {
+        log::debug!("忽略冷却时间内的重复执行: {id}");
+        return Ok(ExecuteOutcome::Done);
+    }
+    // 没有可切换的窗口（例如只在托盘中运行）时退回到启动新实例
+    let focused = focus_existing
+        && match &action {
+            PendingAction::Application(app) => {
+                processes::focus_running_window(processes::app_executable(app))
+            }
+            _ => false,
+        };
+    if !focused {
+        // 失败时不隐藏窗口，并清除本次的执行记录，用户可以直接重试
+        run_pending_action(app_handle, action).map_err(|err| {
+            forget_execution(state, id);
+            report_action_failure(app_handle, Some(id), err)
+        })?;
+    }
 
-    Ok(())
+    finish_action(keep_open, || hide_after_action(app_handle));
+    Ok(ExecuteOutcome::Done)
 }
 
-#[tauri::command]
-pub async fn trigger_reindex(state: State<'_, AppState>) -> Result<(), String> {
-    let app_index = Arc::clone(&state.app_index);
-    let bookmark_index = Arc::clone(&state.bookmark_index);
+/// Hides the window through `hide` after a successful run unless `keep_open` is set.
+fn finish_action(keep_open: bool, hide: impl FnOnce()) {
+    // 保持窗口打开时不隐藏，待执行队列也保持不变，以便连续启动同一批结果
+    if !keep_open {
+        hide();
+    }
+}
 
-    tauri::async_runtime::spawn(async move {
-        let apps = indexer::build_index().await;
-        if let Ok(mut guard) = app_index.lock() {
-            *guard = apps;
-        }
-        log::info!("应用索引刷新完成");
-    });
+/// What Enter does on an app under the [`EnterOnRunningApp`] `choice`.
+#[derive(Debug, PartialEq, Eq)]
+enum RunningAppStep {
+    Launch,
+    Focus,
+    Ask,
+}
 
-    tauri::async_runtime::spawn_blocking(move || {
-        let bookmarks = bookmarks::load_chrome_bookmarks();
-        if let Ok(mut guard) = bookmark_index.lock() {
-            *guard = bookmarks;
-        }
-        log::info!("Chrome 收藏夹索引刷新完成");
-    });
+/// Picks the [`RunningAppStep`] for `app`. `is_running` is only asked for Win32 apps when
+/// `choice` could do something other than launch, since listing processes isn't free.
+fn running_app_step(
+    app: &ApplicationInfo,
+    choice: EnterOnRunningApp,
+    is_running: impl FnOnce(&str) -> bool,
+) -> RunningAppStep {
+    if app.app_type != AppType::Win32
+        || choice == EnterOnRunningApp::LaunchNew
+        || !is_running(processes::app_executable(app))
+    {
+        return RunningAppStep::Launch;
+    }
+    match choice {
+        EnterOnRunningApp::Ask => RunningAppStep::Ask,
+        _ => RunningAppStep::Focus,
+    }
+}
 
-    Ok(())
+/// The confirmation prompt for an action that opens more links than the configured
+/// threshold, or `None` when it can run right away.
+fn bulk_open_prompt(state: &AppState, action: &PendingAction) -> Option<String> {
+    let PendingAction::OpenBookmarkFolder(urls) = action else {
+        return None;
+    };
+    let (threshold, locale) = state
+        .config
+        .lock()
+        .map(|cfg| (cfg.bookmark_folder_confirm_threshold, cfg.locale))
+        .unwrap_or_default();
+    (urls.len() > threshold as usize).then(|| {
+        tr_with(
+            locale,
+            "prompt.open_bookmark_folder",
+            &urls.len().to_string(),
+        )
+    })
 }
 
-#[tauri::command]
-pub fn get_settings(state: State<'_, AppState>) -> AppConfig {
+fn launch_cooldown(state: &AppState) -> Duration {
     state
         .config
         .lock()
-        .map(|cfg| cfg.clone())
+        .map(|cfg| Duration::from_millis(cfg.launch_cooldown_ms))
         .unwrap_or_default()
 }
 
+/// Records `id` as run at `now`, or returns `false` when it already ran less than `cooldown`
+/// before. Checking and recording under one lock keeps two bounced invocations from both
+/// launching.
+fn claim_execution(state: &AppState, id: &str, cooldown: Duration, now: Instant) -> bool {
+    let Ok(mut guard) = state.last_execution.lock() else {
+        return true;
+    };
+    let repeated = guard.as_ref().is_some_and(|(last_id, at)| {
+        last_id == id && now.saturating_duration_since(*at) < cooldown
+    });
+    if repeated {
+        return false;
+    }
+    *guard = Some((id.to_string(), now));
+    true
+}
+
+/// Drops the record of `id`'s last run after it failed, so a retry isn't swallowed by
+/// the cooldown.
+fn forget_execution(state: &AppState, id: &str) {
+    if let Ok(mut guard) = state.last_execution.lock() {
+        if guard.as_ref().is_some_and(|(last_id, _)| last_id == id) {
+            *guard = None;
+        }
+    }
+}
+
+/// Whether `id` was already run less than `cooldown` ago.
+fn is_repeated_execution(state: &AppState, id: &str, cooldown: Duration) -> bool {
+    if cooldown.is_zero() {
+        return false;
+    }
+    state
+        .last_execution
+        .lock()
+        .ok()
+        .and_then(|guard| guard.clone())
+        .is_some_and(|(last_id, at)| last_id == id && at.elapsed() < cooldown)
+}
+
+/// Runs the pending actions of several results in order, e.g. a multi-selection of bookmarks.
+///
+/// Each id gets its own outcome at the same index, so one stale or failing entry doesn't stop
+/// the rest. Ids go through the same checks as [`execute_action`], see [`bulk_action`]. The
+/// window is hidden once at the end, and only if at least one action succeeded.
 #[tauri::command]
-pub fn update_settings(
-    updates: SettingsUpdatePayload,
+pub async fn execute_actions(
+    ids: Vec<String>,
+    keep_open: Option<bool>,
     app_handle: AppHandle,
     state: State<'_, AppState>,
-) -> Result<AppConfig, String> {
-    let mut guard = state
-        .config
-        .lock()
-        .map_err(|_| "无法获取配置".to_string())?;
-
-    if let Some(hotkey) = updates.global_hotkey {
-        let normalized = hotkey.trim();
-        if normalized.is_empty() {
-            return Err("快捷键不能为空".into());
-        }
-        if normalized != guard.global_hotkey {
-            bind_hotkey(&app_handle, &state, normalized, "main")?;
-            guard.global_hotkey = normalized.to_string();
-        }
+) -> AppResult<Vec<AppResult<()>>> {
+    let cooldown = launch_cooldown(&state);
+    let outcomes: Vec<AppResult<()>> = ids
+        .iter()
+        .map(|id| {
+            let Some(action) = bulk_action(&state, id, cooldown)? else {
+                return Ok(());
+            };
+            run_pending_action(&app_handle, action).map_err(|err| {
+                forget_execution(&state, id);
+                report_action_failure(&app_handle, Some(id.as_str()), err)
+            })
+        })
+        .collect();
+
+    if !keep_open.unwrap_or(false) && outcomes.iter().any(Result::is_ok) {
+        hide_after_action(&app_handle);
     }
 
-    if updates.query_delay_ms.is_some() {
-        guard.query_delay_ms = normalize_query_delay(updates.query_delay_ms, guard.query_delay_ms);
+    Ok(outcomes)
+}
+
+/// The action `execute_actions` should run for `id`, with the cooldown already claimed, or
+/// `None` when it repeats the last run within `cooldown` and is skipped like in
+/// [`execute_action`]. There is no approval step in a batch, so folders above
+/// `bookmark_folder_confirm_threshold` are rejected.
+fn bulk_action(state: &AppState, id: &str, cooldown: Duration) -> AppResult<Option<PendingAction>> {
+    let action = resolve_pending_action(state, id)?;
+    if bulk_open_prompt(state, &action).is_some() {
+        return Err(AppError::NotApplicable(
+            "链接较多，请选中后按回车确认打开".into(),
+        ));
     }
+    if !claim_execution(state, id, cooldown, Instant::now()) {
+        log::debug!("忽略冷却时间内的重复执行: {id}");
+        return Ok(None);
+    }
+    Ok(Some(action))
+}
+
+/// Runs a result with administrator rights (Ctrl+Shift+Enter) through the `runas` verb.
+///
+/// Only Win32 apps, raw targets and custom commands can be elevated; every other result is
+/// rejected with [`AppError::NotApplicable`] instead of silently running unelevated.
+#[tauri::command]
+pub async fn execute_action_elevated(
+    id: String,
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+) -> AppResult<()> {
+    let action = resolve_pending_action(&state, &id)?;
+    run_elevated(&app_handle, action).map_err(|err| match err {
+        // 用户在 UAC 提示中取消不算执行失败
+        AppError::ElevationCancelled => err,
+        err => report_action_failure(&app_handle, Some(id.as_str()), err),
+    })?;
+    hide_after_action(&app_handle);
+    Ok(())
+}
+
+fn run_elevated(app_handle: &AppHandle, action: PendingAction) -> AppResult<()> {
+    let config = app_handle
+        .try_state::<AppState>()
+        .and_then(|state| state.config.lock().ok().map(|cfg| cfg.clone()))
+        .unwrap_or_default();
+    let launch = elevated_launch(action, &config)?;
+    shell_execute_elevated(
+        &launch.target,
+        launch.arguments.as_deref(),
+        launch.working_dir.as_deref(),
+    )
+}
+
+/// What the `runas` verb starts for an elevated action.
+#[derive(Debug)]
+struct ElevatedLaunch {
+    target: String,
+    arguments: Option<String>,
+    working_dir: Option<String>,
+}
 
-    if updates.max_results.is_some() {
-        guard.max_results = normalize_max_results(updates.max_results, guard.max_results);
+/// The launch behind `action`, or [`AppError::NotApplicable`] for results that can't be
+/// elevated, such as bookmarks, URLs and Steam or UWP apps.
+fn elevated_launch(action: PendingAction, config: &AppConfig) -> AppResult<ElevatedLaunch> {
+    match action {
+        PendingAction::Application(app)
+            if app.app_type == AppType::Win32
+                && !matches!(app.origin, Some(AppOrigin::Steam { .. })) =>
+        {
+            Ok(ElevatedLaunch {
+                working_dir: launch_working_dir(&app, config),
+                arguments: app.launch_args,
+                target: app.path,
+            })
+        }
+        PendingAction::RawOpen(target) => Ok(ElevatedLaunch {
+            target,
+            arguments: None,
+            working_dir: None,
+        }),
+        PendingAction::Command { target, arguments } => Ok(ElevatedLaunch {
+            target,
+            arguments: Some(arguments),
+            working_dir: None,
+        }),
+        _ => Err(AppError::NotApplicable(
+            "该结果不支持以管理员身份运行".into(),
+        )),
     }
+}
 
-    if let Some(value) = updates.enable_app_results {
-        guard.enable_app_results = value;
+/// Whether `target` is an existing file, or a bare program name such as `code` or `wt` that
+/// Windows finds on `PATH`.
+fn elevation_target_exists(target: &str) -> bool {
+    let path = Path::new(target);
+    if path.exists() {
+        return true;
     }
+    if path.is_absolute() || path.components().count() > 1 {
+        return false;
+    }
+    let dirs: Vec<PathBuf> = env::var_os("PATH")
+        .map(|value| env::split_paths(&value).collect())
+        .unwrap_or_default();
+    let extensions = env::var("PATHEXT").unwrap_or_else(|_| ".COM;.EXE;.BAT;.CMD".to_string());
+    let extensions: Vec<&str> = extensions
+        .split(';')
+        .filter(|ext| !ext.is_empty())
+        .collect();
+    find_in_dirs(target, &dirs, &extensions).is_some()
+}
+
+/// The first `dir/name` or `dir/name.ext` that is a file, in the order of `dirs`.
+fn find_in_dirs(name: &str, dirs: &[PathBuf], extensions: &[&str]) -> Option<PathBuf> {
+    dirs.iter().find_map(|dir| {
+        std::iter::once(dir.join(name))
+            .chain(
+                extensions
+                    .iter()
+                    .map(|ext| dir.join(format!("{name}{ext}"))),
+            )
+            .find(|candidate| candidate.is_file())
+    })
+}
+
+/// Starts `target` with the `runas` verb, which shows the UAC prompt.
+fn shell_execute_elevated(
+    target: &str,
+    arguments: Option<&str>,
+    working_directory: Option<&str>,
+) -> AppResult<()> {
+    // 用户在 UAC 提示中选择“否”时 ShellExecute 返回 SE_ERR_ACCESSDENIED
+    const SE_ERR_ACCESSDENIED: isize = 5;
+
+    if !elevation_target_exists(target) {
+        return Err(AppError::LaunchFailed("目标程序不存在或已被移动".into()));
+    }
+    let target_os = OsString::from(target);
+    let argument_os = arguments
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(OsString::from);
+    let working_dir_os = working_directory.map(OsString::from);
+    match shell_execute_status(
+        Some(OsStr::new("runas")),
+        target_os.as_os_str(),
+        argument_os.as_deref(),
+        working_dir_os.as_deref(),
+    ) {
+        code if code > 32 => Ok(()),
+        SE_ERR_ACCESSDENIED => Err(AppError::ElevationCancelled),
+        code => Err(AppError::LaunchFailed(format!(
+            "无法以管理员身份启动 (ShellExecute 错误码 {code})"
+        ))),
+    }
+}
+
+/// Runs the result bound to quick key `n` (Alt+1..9) of the last query, with the same checks
+/// and outcomes as [`execute_action`].
+///
+/// `generation` is the value from the [`SearchResponse`] the key was rendered for; a mismatch
+/// means newer results replaced them and the request is rejected instead of running the
+/// wrong entry.
+#[tauri::command]
+pub async fn execute_quick(
+    n: u8,
+    generation: u64,
+    keep_open: Option<bool>,
+    running_choice: Option<EnterOnRunningApp>,
+    approved: Option<bool>,
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+) -> AppResult<ExecuteOutcome> {
+    let id = {
+        let guard = state
+            .quick_keys
+            .lock()
+            .map_err(|_| "无法访问快捷键列表".to_string())?;
+        quick_key_id(&guard, n, generation)?
+    };
+    run_result(
+        &app_handle,
+        &state,
+        &id,
+        keep_open.unwrap_or(false),
+        running_choice,
+        approved.unwrap_or(false),
+    )
+}
+
+/// The result id behind quick key `n` of the results numbered in `generation`.
+fn quick_key_id(quick_keys: &QuickKeys, n: u8, generation: u64) -> AppResult<String> {
+    if quick_keys.generation != generation {
+        return Err(AppError::ActionExpired);
+    }
+    usize::from(n)
+        .checked_sub(1)
+        .and_then(|index| quick_keys.ids.get(index))
+        .cloned()
+        .ok_or(AppError::ActionExpired)
+}
+
+/// Runs the configured fallback for a query that produced no results (Enter on an empty list).
+/// Returns `false` when `empty_enter_action` is `nothing` and nothing was run.
+#[tauri::command]
+pub async fn execute_fallback(
+    query: String,
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+) -> AppResult<bool> {
+    let action = {
+        let config = state
+            .config
+            .lock()
+            .map_err(|_| "无法获取配置".to_string())?;
+        search::fallback_action(&query, &config)
+    };
+    let Some(action) = action else {
+        return Ok(false);
+    };
+
+    run_pending_action(&app_handle, action)
+        .map_err(|err| report_action_failure(&app_handle, None, err))?;
+    hide_after_action(&app_handle);
+    Ok(true)
+}
+
+/// Emits [`ACTION_FAILED_EVENT`] for `err` and hands it back to be returned by the command.
+fn report_action_failure(app_handle: &AppHandle, id: Option<&str>, err: AppError) -> AppError {
+    log::warn!("failed to run {}: {err}", id.unwrap_or("fallback action"));
+    let payload = ActionFailedPayload { id, error: &err };
+    if let Err(emit_err) = app_handle.emit(ACTION_FAILED_EVENT, payload) {
+        log::warn!("failed to emit action failed event: {emit_err}");
+    }
+    err
+}
+
+fn resolve_pending_action(state: &AppState, id: &str) -> AppResult<PendingAction> {
+    let guard = state
+        .pending_actions
+        .lock()
+        .map_err(|_| "无法访问待执行队列".to_string())?;
+    guard.get(id).cloned().ok_or(AppError::ActionExpired)
+}
+
+fn run_pending_action(app_handle: &AppHandle, action: PendingAction) -> AppResult<()> {
+    let state = app_handle.try_state::<AppState>();
+    let event = state.as_ref().and_then(|state| {
+        let mode = state
+            .last_query_mode
+            .lock()
+            .ok()
+            .and_then(|guard| *guard)
+            .unwrap_or(QueryMode::All);
+        UsageEvent::from_action(&action, mode, diagnostics::unix_now() as i64)
+    });
+    dispatch_pending_action(app_handle, action)?;
+
+    if let (Some(state), Some(event)) = (state, event) {
+        record_usage(app_handle, &state, event);
+    }
+    Ok(())
+}
+
+/// Appends a launch to the local usage log. Failing to persist it never fails the launch.
+fn record_usage(app_handle: &AppHandle, state: &AppState, event: UsageEvent) {
+    let Ok(mut events) = state.usage_log.lock() else {
+        return;
+    };
+    usage::push_event(&mut events, event);
+    if let Ok(mut counts) = state.launch_counts.lock() {
+        *counts = usage::launch_counts(&events);
+    }
+    if let Err(err) = usage::save(app_handle, &events) {
+        log::warn!("failed to save usage log: {err}");
+    }
+}
+
+fn dispatch_pending_action(app_handle: &AppHandle, action: PendingAction) -> AppResult<()> {
+    let result = match action {
+        // Steam 游戏是 steam:// 链接，交给 Steam 客户端处理
+        PendingAction::Application(app) if matches!(app.origin, Some(AppOrigin::Steam { .. })) => {
+            open_url(app_handle, &app.path)
+        }
+        PendingAction::Application(app) => match app.app_type {
+            AppType::Win32 => launch_configured_win32_app(app_handle, &app),
+            AppType::Uwp => return launch_uwp_app(&app.path).map_err(AppError::Uwp),
+            AppType::Desktop => launch_desktop_entry(&app.path),
+            AppType::MacApp => launch_mac_app(&app.path),
+        },
+        PendingAction::Bookmark(entry) => open_url(app_handle, &entry.url),
+        PendingAction::Url(url) | PendingAction::Search(url) => open_url(app_handle, &url),
+        PendingAction::RawOpen(target) => shell_execute_raw(&target, None, None),
+        PendingAction::OpenWith(target) => open_with_dialog(&target),
+        PendingAction::PinToTaskbar(target) => pin_to_taskbar(&target),
+        PendingAction::RestartApp(app) => {
+            // 未运行时直接启动即可
+            processes::terminate_processes_by_path(processes::app_executable(&app)).and_then(
+                |terminated| {
+                    log::info!("重启 {}：已结束 {terminated} 个进程", app.name);
+                    launch_configured_win32_app(app_handle, &app)
+                },
+            )
+        }
+        PendingAction::CopyText(text) => return Ok(copy_text_to_clipboard(&text)?),
+        PendingAction::CopyImage(png) => return Ok(copy_png_to_clipboard(&png)?),
+        PendingAction::OpenPath(path) => open_path(app_handle, &path),
+        PendingAction::OpenBookmarkFolder(urls) => open_all_urls(app_handle, &urls),
+        PendingAction::Command { target, arguments } => {
+            shell_execute_raw(&target, Some(&arguments), None)
+        }
+        PendingAction::RunCommand(command) => {
+            run_command(&command).inspect(|()| record_run_command(app_handle, &command))
+        }
+    };
+
+    result.map_err(AppError::LaunchFailed)
+}
+
+#[cfg(target_os = "linux")]
+fn launch_desktop_entry(exec: &str) -> Result<(), String> {
+    crate::desktop_entries::launch(exec)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn launch_desktop_entry(_exec: &str) -> Result<(), String> {
+    Err("桌面启动项只能在 Linux 上启动".into())
+}
+
+#[cfg(target_os = "macos")]
+fn launch_mac_app(bundle_path: &str) -> Result<(), String> {
+    crate::mac_apps::launch(bundle_path)
+}
+
+#[cfg(not(target_os = "macos"))]
+fn launch_mac_app(_bundle_path: &str) -> Result<(), String> {
+    Err("macOS 应用只能在 macOS 上启动".into())
+}
+
+/// Starts a command line the way the Run dialog does: the first token goes through the shell
+/// with the rest as its arguments, so GUI programs, console programs (in a new console),
+/// documents and URLs all work. Lines only `cmd.exe` understands, such as `dir` or pipes, are
+/// handed to `cmd.exe /K` so their output stays visible.
+fn run_command(command: &str) -> Result<(), String> {
+    let command = command.trim();
+    if command.is_empty() {
+        return Err("命令不能为空".into());
+    }
+    let working_dir = env::var("USERPROFILE").ok();
+
+    // 管道与重定向只有 cmd.exe 能处理
+    if !command.contains(['|', '<', '>', '&']) {
+        if let Some((program, arguments)) = split_windows_arguments(command)
+            .ok()
+            .as_deref()
+            .and_then(<[String]>::split_first)
+        {
+            let program = expand_env_vars(program).unwrap_or_else(|| program.clone());
+            let arguments = join_windows_arguments(arguments);
+            match shell_execute_raw(&program, Some(&arguments), working_dir.as_deref()) {
+                Ok(()) => {
+                    log::info!("已运行 {program}");
+                    return Ok(());
+                }
+                Err(err) => log::debug!("无法直接运行 {program}: {err}，改用 cmd.exe"),
+            }
+        }
+    }
+
+    shell_execute_raw(
+        "cmd.exe",
+        Some(&format!("/K {command}")),
+        working_dir.as_deref(),
+    )
+    .map_err(|err| format!("无法运行命令 {command}: {err}"))
+}
+
+/// Moves `command` to the front of the run history and saves `run_history.json`.
+fn record_run_command(app_handle: &AppHandle, command: &str) {
+    let Some(state) = app_handle.try_state::<AppState>() else {
+        return;
+    };
+    let Ok(mut entries) = state.run_history.lock() else {
+        return;
+    };
+    run_history::push_command(&mut entries, command.trim());
+    if let Err(err) = run_history::save(app_handle, &entries) {
+        log::warn!("failed to save run history: {err}");
+    }
+}
+
+fn hide_after_action(app_handle: &AppHandle) {
+    if let Some(window) = app_handle.get_webview_window("main") {
+        let _ = window.hide();
+    }
+
+    emit_hide_window(app_handle, HideReason::Action);
+}
+
+#[tauri::command]
+pub async fn trigger_reindex(app_handle: AppHandle, state: State<'_, AppState>) -> AppResult<()> {
+    start_full_reindex(&app_handle, &state);
+    Ok(())
+}
+
+/// Starts the app and bookmark reindexes, plus the history one when history results are
+/// enabled. Indexes that are already being rebuilt are skipped.
+pub(crate) fn start_full_reindex(app_handle: &AppHandle, state: &AppState) {
+    start_app_reindex(app_handle, state);
+    start_bookmark_reindex(state);
+    let history_limit = state
+        .config
+        .lock()
+        .ok()
+        .filter(|cfg| cfg.enable_history_results)
+        .map(|cfg| cfg.max_history_entries);
+    if let Some(limit) = history_limit {
+        start_history_reindex(state, limit);
+    }
+}
+
+/// Rebuilds only the application index. Returns `false` when an app reindex is already running.
+#[tauri::command]
+pub async fn reindex_apps(app_handle: AppHandle, state: State<'_, AppState>) -> AppResult<bool> {
+    Ok(start_app_reindex(&app_handle, &state))
+}
+
+/// Rebuilds only the bookmark index. Returns `false` when a bookmark reindex is already running.
+#[tauri::command]
+pub async fn reindex_bookmarks(state: State<'_, AppState>) -> AppResult<bool> {
+    Ok(start_bookmark_reindex(&state))
+}
+
+pub(crate) fn start_app_reindex(app_handle: &AppHandle, state: &AppState) -> bool {
+    if state.app_reindex_in_progress.swap(true, Ordering::AcqRel) {
+        log::debug!("应用索引正在刷新，跳过本次请求");
+        return false;
+    }
+
+    let (options, notify_new_apps) = state
+        .config
+        .lock()
+        .map(|cfg| {
+            let options = IndexOptions {
+                extract_icons: cfg.extract_icons,
+                defer_icons: cfg.prewarm_icons,
+                icon_size: cfg.icon_size,
+                display_names: cfg.display_names.clone(),
+                launch_args: cfg.launch_args.clone(),
+                exclude_exe_patterns: cfg.exclude_exe_patterns.clone(),
+                steam_games: cfg.enable_steam_games,
+            };
+            (options, cfg.notify_new_apps)
+        })
+        .unwrap_or((
+            IndexOptions {
+                extract_icons: true,
+                ..Default::default()
+            },
+            false,
+        ));
+    let app_handle = app_handle.clone();
+    let app_index = Arc::clone(&state.app_index);
+    let in_progress = Arc::clone(&state.app_reindex_in_progress);
+    let last_reindex = Arc::clone(&state.last_app_reindex);
+    let prewarm = options.extract_icons && options.defer_icons;
+    let icon_size = options.icon_size;
+    tauri::async_runtime::spawn(async move {
+        let apps = indexer::build_index(options).await;
+        let diff = match app_index.lock() {
+            Ok(mut guard) => {
+                let previous = std::mem::replace(&mut *guard, apps);
+                // 既没有缓存也没有上次构建时无从比较，不做提示
+                (notify_new_apps && !previous.is_empty())
+                    .then(|| indexer::diff_index(&previous, &guard))
+            }
+            Err(_) => None,
+        };
+        last_reindex.store(diagnostics::unix_now(), Ordering::Release);
+        in_progress.store(false, Ordering::Release);
+        log::info!("应用索引刷新完成");
+
+        if let Some(diff) = diff.filter(|diff| !diff.added.is_empty()) {
+            log::info!(
+                "发现 {} 个新应用，移除 {} 个应用",
+                diff.added.len(),
+                diff.removed.len()
+            );
+            if let Err(err) = app_handle.emit(NEW_APPS_DETECTED_EVENT, &diff) {
+                log::warn!("failed to emit new apps event: {err}");
+            }
+        }
+
+        if prewarm {
+            let spawned = std::thread::Builder::new()
+                .name("icon-prewarm".into())
+                .spawn(move || {
+                    // 新一轮重建开始后停止，由新的重建重新预热
+                    let filled = indexer::prewarm_icons(&app_index, icon_size, || {
+                        in_progress.load(Ordering::Acquire)
+                    });
+                    log::info!("已预热 {filled} 个应用图标");
+                    // 图标补全后再写缓存，被新的重建打断时交给新的重建写入
+                    if !in_progress.load(Ordering::Acquire) {
+                        save_app_cache(&app_index);
+                    }
+                });
+            if let Err(err) = spawned {
+                log::warn!("failed to start icon prewarm: {err}");
+            }
+        } else {
+            save_app_cache(&app_index);
+        }
+    });
+
+    true
+}
+
+/// Saves the current app index so the next start can search before its first reindex.
+fn save_app_cache(app_index: &Mutex<Vec<ApplicationInfo>>) {
+    let apps = match app_index.lock() {
+        Ok(guard) => guard.clone(),
+        Err(_) => return,
+    };
+    if let Err(err) = app_cache::save(&apps) {
+        log::warn!("failed to save app index cache: {err}");
+    }
+}
+
+pub(crate) fn start_bookmark_reindex(state: &AppState) -> bool {
+    if state
+        .bookmark_reindex_in_progress
+        .swap(true, Ordering::AcqRel)
+    {
+        log::debug!("收藏夹索引正在刷新，跳过本次请求");
+        return false;
+    }
+
+    let (browsers, profiles, dedupe, opt_in) = state
+        .config
+        .lock()
+        .map(|cfg| {
+            (
+                cfg.bookmark_browsers.clone(),
+                cfg.bookmark_profiles.clone(),
+                cfg.dedupe_bookmarks_across_profiles,
+                OptInBrowsers {
+                    edge: cfg.enable_edge_bookmarks,
+                    firefox: cfg.enable_firefox_bookmarks,
+                },
+            )
+        })
+        .unwrap_or_default();
+    let bookmark_index = Arc::clone(&state.bookmark_index);
+    let bookmark_folders = Arc::clone(&state.bookmark_folders);
+    let in_progress = Arc::clone(&state.bookmark_reindex_in_progress);
+    tauri::async_runtime::spawn_blocking(move || {
+        let bookmarks = bookmarks::load_bookmarks(&browsers, &profiles, dedupe, opt_in);
+        let folders = bookmarks::group_folders(&bookmarks);
+        if let Ok(mut guard) = bookmark_index.lock() {
+            *guard = bookmarks;
+        }
+        if let Ok(mut guard) = bookmark_folders.lock() {
+            *guard = folders;
+        }
+        in_progress.store(false, Ordering::Release);
+        log::info!("收藏夹索引刷新完成");
+    });
+
+    true
+}
+
+/// Rebuilds the browser history index from the local SQLite databases, keeping the `limit`
+/// most visited URLs. Returns `false` when a history reindex is already running.
+pub(crate) fn start_history_reindex(state: &AppState, limit: u32) -> bool {
+    if state
+        .history_reindex_in_progress
+        .swap(true, Ordering::AcqRel)
+    {
+        log::debug!("历史记录索引正在刷新，跳过本次请求");
+        return false;
+    }
+
+    let history_index = Arc::clone(&state.history_index);
+    let in_progress = Arc::clone(&state.history_reindex_in_progress);
+    tauri::async_runtime::spawn_blocking(move || {
+        let entries = history::load_browser_history(limit as usize);
+        if let Ok(mut guard) = history_index.lock() {
+            *guard = entries;
+        }
+        in_progress.store(false, Ordering::Release);
+        log::info!("浏览器历史记录索引刷新完成");
+    });
+
+    true
+}
+
+#[tauri::command]
+pub fn get_settings(state: State<'_, AppState>) -> AppConfig {
+    state
+        .config
+        .lock()
+        .map(|cfg| cfg.clone())
+        .unwrap_or_default()
+}
+
+#[tauri::command]
+pub fn get_paths(app_handle: AppHandle, state: State<'_, AppState>) -> AppPaths {
+    app_paths(&app_handle, &state)
+}
+
+pub(crate) fn app_paths(app_handle: &AppHandle, state: &AppState) -> AppPaths {
+    let display = |path: std::path::PathBuf| path.to_string_lossy().into_owned();
+    let config_error = state
+        .config_persistence_error
+        .lock()
+        .ok()
+        .and_then(|guard| guard.clone());
+    AppPaths {
+        config_file: config::config_path(app_handle).map(display),
+        config_persistent: config_error.is_none(),
+        config_error,
+        icon_cache_dir: icon_cache_dir().map(display),
+        favicon_cache_dir: favicons::favicon_cache_dir().map(display),
+        profiles_dir: config::config_dir(app_handle).map(display),
+        active_profile_file: config::profile_pointer_path(app_handle).map(display),
+        usage_file: usage::usage_path(app_handle).map(display),
+        run_history_file: run_history::run_history_path(app_handle).map(display),
+        app_index_cache_file: app_cache::cache_path().map(display),
+    }
+}
+
+/// Writes a diagnostics bundle for bug reports and returns its path.
+#[tauri::command]
+pub fn export_diagnostics(
+    include_bookmark_urls: Option<bool>,
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+) -> AppResult<String> {
+    diagnostics::export_bundle(&app_handle, &state, include_bookmark_urls.unwrap_or(false))
+        .map(|path| path.to_string_lossy().into_owned())
+}
+
+/// Launch statistics for the last `range_days` days (30 by default), computed only from the
+/// local usage log. Nothing leaves the machine.
+#[tauri::command]
+pub fn get_analytics(
+    range_days: Option<u32>,
+    state: State<'_, AppState>,
+) -> AppResult<UsageAnalytics> {
+    let events = state
+        .usage_log
+        .lock()
+        .map_err(|_| "无法访问使用记录".to_string())?;
+    Ok(usage::aggregate(
+        &events,
+        range_days.unwrap_or(30),
+        diagnostics::unix_now() as i64,
+    ))
+}
+
+/// Deletes the local usage log, in memory and on disk.
+#[tauri::command]
+pub fn clear_usage_history(app_handle: AppHandle, state: State<'_, AppState>) -> AppResult<()> {
+    let mut events = state
+        .usage_log
+        .lock()
+        .map_err(|_| "无法访问使用记录".to_string())?;
+    events.clear();
+    if let Ok(mut counts) = state.launch_counts.lock() {
+        counts.clear();
+    }
+    usage::save(&app_handle, &events).map_err(AppError::ConfigIo)
+}
+
+#[tauri::command]
+pub fn clear_run_history(app_handle: AppHandle, state: State<'_, AppState>) -> AppResult<()> {
+    let mut entries = state
+        .run_history
+        .lock()
+        .map_err(|_| "无法访问运行记录".to_string())?;
+    entries.clear();
+    run_history::save(&app_handle, &entries).map_err(AppError::ConfigIo)
+}
+
+/// Runs every health check and reports each one separately; a failing check never stops the
+/// others from running.
+#[tauri::command]
+pub fn health_check(app_handle: AppHandle, state: State<'_, AppState>) -> HealthReport {
+    diagnostics::health_check(&app_handle, &state)
+}
+
+/// Hides a result from queries until [`clear_session_hidden`] or the next restart.
+/// Nothing is written to disk.
+#[tauri::command]
+pub fn hide_result_for_session(id: String, state: State<'_, AppState>) -> AppResult<()> {
+    state
+        .session_hidden
+        .lock()
+        .map_err(|_| "无法访问隐藏列表".to_string())?
+        .insert(id);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn clear_session_hidden(state: State<'_, AppState>) -> AppResult<()> {
+    state
+        .session_hidden
+        .lock()
+        .map_err(|_| "无法访问隐藏列表".to_string())?
+        .clear();
+    Ok(())
+}
+
+/// Shows `name` instead of the indexed name of an app, persisted across reindexing.
+/// `id` may be the app id or its result id (`app-<app id>`).
+#[tauri::command]
+pub fn set_display_name(
+    id: String,
+    name: String,
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+) -> AppResult<()> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(AppError::InvalidSetting("显示名称不能为空".into()));
+    }
+    let app_id = app_id_from(&id);
+    update_display_names(&app_handle, &state, |names| {
+        names.insert(app_id.to_string(), name.to_string());
+    })
+}
+
+/// Removes the display-name override of an app and restores its indexed name in place.
+#[tauri::command]
+pub fn clear_display_name(
+    id: String,
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+) -> AppResult<()> {
+    let app_id = app_id_from(&id);
+    update_display_names(&app_handle, &state, |names| {
+        names.remove(app_id);
+    })
+}
+
+/// Saves arguments passed on every launch of a Win32 app, e.g. a browser profile flag. The
+/// arguments are re-quoted with [`normalize_launch_arguments`] before saving; an empty string
+/// removes them. `id` may be
+/// the app id or its result id.
+#[tauri::command]
+pub fn set_launch_args(
+    id: String,
+    args: String,
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+) -> AppResult<()> {
+    let app_id = app_id_from(&id).to_string();
+    let arguments = normalize_launch_arguments(&args).map_err(AppError::InvalidSetting)?;
+    let is_uwp = state
+        .app_index
+        .lock()
+        .map_err(|_| "无法访问应用索引".to_string())?
+        .iter()
+        .any(|app| app.id == app_id && app.app_type == AppType::Uwp);
+    if is_uwp && !arguments.is_empty() {
+        return Err(AppError::NotApplicable("UWP 应用不支持启动参数".into()));
+    }
+
+    let mut guard = state
+        .config
+        .lock()
+        .map_err(|_| "无法获取配置".to_string())?;
+    if arguments.is_empty() {
+        guard.launch_args.remove(&app_id);
+    } else {
+        guard.launch_args.insert(app_id.clone(), arguments);
+    }
+    guard.save(&app_handle)?;
+    // 直接更新索引中的条目，无需重建
+    if let Ok(mut apps) = state.app_index.lock() {
+        indexer::apply_launch_args(&mut apps, &guard.launch_args);
+    }
+    Ok(())
+}
+
+/// Re-reads a single Win32 entry from its Start Menu shortcut or uninstall key and swaps it
+/// into the index, e.g. after an update moved its executable. Much cheaper than a full reindex.
+/// `id` may be the app id or its result id.
+#[tauri::command]
+pub async fn repair_entry(id: String, state: State<'_, AppState>) -> AppResult<ApplicationInfo> {
+    let app_id = app_id_from(&id).to_string();
+    let (extract_icons, icon_size, display_names, launch_args, filter) = {
+        let guard = state
+            .config
+            .lock()
+            .map_err(|_| "无法获取配置".to_string())?;
+        (
+            guard.extract_icons,
+            guard.icon_size,
+            guard.display_names.clone(),
+            guard.launch_args.clone(),
+            ExeFilter::new(&guard.exclude_exe_patterns),
+        )
+    };
+    let origin = state
+        .app_index
+        .lock()
+        .map_err(|_| "无法访问应用索引".to_string())?
+        .iter()
+        .find(|app| app.id == app_id)
+        .ok_or_else(|| format!("未找到应用 {app_id}"))?
+        .origin
+        .clone()
+        .ok_or_else(|| format!("应用 {app_id} 缺少来源信息，请重建索引"))?;
+
+    let mut repaired = tauri::async_runtime::spawn_blocking(move || {
+        indexer::reload_entry(&origin, extract_icons, icon_size, &filter)
+    })
+    .await
+    .map_err(|err| err.to_string())??;
+    indexer::apply_display_names(std::slice::from_mut(&mut repaired), &display_names);
+    indexer::apply_launch_args(std::slice::from_mut(&mut repaired), &launch_args);
+
+    let mut apps = state
+        .app_index
+        .lock()
+        .map_err(|_| "无法访问应用索引".to_string())?;
+    indexer::replace_entry(&mut apps, &app_id, repaired.clone());
+    log::info!("已修复应用条目 {app_id}");
+    Ok(repaired)
+}
+
+/// Extracts the icon of one app again, bypassing the icon cache, and stores it in the index.
+/// Returns the new base64 PNG. `id` may be the app id or its result id.
+#[tauri::command]
+pub async fn refresh_icon(id: String, state: State<'_, AppState>) -> AppResult<String> {
+    let app_id = app_id_from(&id).to_string();
+    let icon_size = state
+        .config
+        .lock()
+        .map_err(|_| "无法获取配置".to_string())?
+        .icon_size;
+    let (source, index) = icon_source(&state, &app_id)?;
+
+    let icon = tauri::async_runtime::spawn_blocking(move || {
+        invalidate_cached_icon(&source, index, icon_size);
+        extract_icon_from_path(&source, index, icon_size)
+    })
+    .await
+    .map_err(|err| err.to_string())?;
+    store_refreshed_icon(&state, &app_id, icon)
+}
+
+/// File and index the icon of `app_id` is extracted from.
+fn icon_source(state: &AppState, app_id: &str) -> AppResult<(String, i32)> {
+    state
+        .app_index
+        .lock()
+        .map_err(|_| "无法访问应用索引".to_string())?
+        .iter()
+        .find(|app| app.id == app_id)
+        .ok_or_else(|| format!("未找到应用 {app_id}"))?
+        .icon_source
+        .clone()
+        .ok_or_else(|| AppError::NotApplicable("该应用的图标无法单独刷新".into()))
+}
+
+/// Puts a freshly extracted icon into the index; `None` means extraction failed and the old
+/// icon stays.
+fn store_refreshed_icon(state: &AppState, app_id: &str, icon: Option<String>) -> AppResult<String> {
+    let icon = icon.ok_or_else(|| format!("无法提取 {app_id} 的图标"))?;
+    let mut apps = state
+        .app_index
+        .lock()
+        .map_err(|_| "无法访问应用索引".to_string())?;
+    if let Some(app) = apps.iter_mut().find(|app| app.id == app_id) {
+        app.icon_b64 = icon.clone();
+    }
+    Ok(icon)
+}
+
+fn app_id_from(id: &str) -> &str {
+    id.strip_prefix("app-").unwrap_or(id)
+}
+
+/// Saves the changed overrides and renames the indexed entries in place; the index keeps each
+/// entry's indexed name, so no reindex is needed.
+fn update_display_names(
+    app_handle: &AppHandle,
+    state: &AppState,
+    change: impl FnOnce(&mut HashMap<String, String>),
+) -> AppResult<()> {
+    {
+        let mut guard = state
+            .config
+            .lock()
+            .map_err(|_| "无法获取配置".to_string())?;
+        change(&mut guard.display_names);
+        guard.save(app_handle)?;
+        if let Ok(mut apps) = state.app_index.lock() {
+            indexer::apply_display_names(&mut apps, &guard.display_names);
+            indexer::sort_by_name(&mut apps);
+        }
+    }
+    save_app_cache(&state.app_index);
+    Ok(())
+}
+
+/// Hide request from the user (Escape); emitted with [`HideReason::User`].
+#[tauri::command]
+pub fn request_hide(app_handle: AppHandle) {
+    emit_hide_window(&app_handle, HideReason::User);
+}
+
+/// Called by the frontend when the active mode changes so the IME follows the per-mode preference.
+#[tauri::command]
+pub fn sync_input_method(mode: Option<String>, app_handle: AppHandle, state: State<'_, AppState>) {
+    // 用户已在用非拉丁文字输入时不打断其输入法
+    if state.query_non_latin.load(Ordering::Acquire) {
+        return;
+    }
+    if crate::should_force_english_input(&app_handle, QueryMode::from_option(mode)) {
+        crate::windows_utils::switch_to_english_input_method();
+    }
+}
+
+/// Reports every problem in `config` without applying it.
+#[tauri::command]
+pub fn validate_config(config: AppConfig) -> Vec<ConfigIssue> {
+    validation::validate_config(&config)
+}
+
+/// Applies `updates` as a whole: the new settings are built and validated on a copy first, so
+/// a rejected value leaves everything as it was. Hotkeys are rebound and launch-on-startup is
+/// synced before saving, and rolled back when a later step fails.
+#[tauri::command]
+pub fn update_settings(
+    updates: SettingsUpdatePayload,
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+) -> AppResult<AppConfig> {
+    let mut guard = state
+        .config
+        .lock()
+        .map_err(|_| "无法获取配置".to_string())?;
+
+    let sync_launch_on_startup = updates.launch_on_startup.is_some();
+    let candidate = settings_candidate(&guard, updates)?;
+
+    // 校验已全部通过，以下步骤修改系统状态，失败时撤销已完成的部分
+    let hotkey_changed = candidate.global_hotkey != guard.global_hotkey;
+    let mode_hotkeys_changed = candidate.mode_hotkeys != guard.mode_hotkeys;
+    if hotkey_changed {
+        bind_hotkey(&app_handle, &state, &candidate.global_hotkey, "main")?;
+    }
+    if mode_hotkeys_changed {
+        if let Err(err) = hotkey::bind_mode_hotkeys(&app_handle, &state, &candidate.mode_hotkeys) {
+            restore_hotkeys(&app_handle, &state, &guard, hotkey_changed, false);
+            return Err(err);
+        }
+    }
+    if sync_launch_on_startup {
+        if let Err(err) =
+            crate::windows_utils::configure_launch_on_startup(candidate.launch_on_startup)
+        {
+            restore_hotkeys(
+                &app_handle,
+                &state,
+                &guard,
+                hotkey_changed,
+                mode_hotkeys_changed,
+            );
+            return Err(err.into());
+        }
+    }
+    if let Err(err) = candidate.save(&app_handle) {
+        if sync_launch_on_startup {
+            let _ = crate::windows_utils::configure_launch_on_startup(guard.launch_on_startup);
+        }
+        restore_hotkeys(
+            &app_handle,
+            &state,
+            &guard,
+            hotkey_changed,
+            mode_hotkeys_changed,
+        );
+        return Err(err);
+    }
+
+    let app_sources_changed = candidate.extract_icons != guard.extract_icons
+        || candidate.icon_size != guard.icon_size
+        || candidate.exclude_exe_patterns != guard.exclude_exe_patterns
+        || candidate.enable_steam_games != guard.enable_steam_games;
+    let bookmark_sources_changed = candidate.bookmark_browsers != guard.bookmark_browsers
+        || candidate.enable_edge_bookmarks != guard.enable_edge_bookmarks
+        || candidate.enable_firefox_bookmarks != guard.enable_firefox_bookmarks
+        || candidate.bookmark_profiles != guard.bookmark_profiles
+        || candidate.dedupe_bookmarks_across_profiles != guard.dedupe_bookmarks_across_profiles;
+    let history_toggled = candidate.enable_history_results != guard.enable_history_results;
+    let locale_changed = candidate.locale != guard.locale;
+    *guard = candidate;
+    let snapshot = guard.clone();
+    // 索引重建会读取配置，需在释放锁之后再触发
+    drop(guard);
+
+    if locale_changed {
+        // 托盘菜单刷新失败不影响已保存的设置
+        if let Err(err) = tray::refresh_tray_menu(&app_handle, snapshot.locale) {
+            log::warn!("failed to rebuild tray menu: {err}");
+        }
+    }
+    if history_toggled {
+        if snapshot.enable_history_results {
+            start_history_reindex(&state, snapshot.max_history_entries);
+        } else if let Ok(mut history_index) = state.history_index.lock() {
+            // 关闭后立即丢弃已读取的历史记录，不在内存中保留
+            history_index.clear();
+        }
+    }
+    if app_sources_changed {
+        start_app_reindex(&app_handle, &state);
+    }
+    if bookmark_sources_changed {
+        start_bookmark_reindex(&state);
+    }
+    let _ = app_handle.emit(SETTINGS_UPDATED_EVENT, snapshot.clone());
+    Ok(snapshot)
+}
+
+/// The settings `updates` turn `current` into, normalized and checked as a whole with
+/// [`validation::ensure_valid`]. Nothing is applied.
+fn settings_candidate(current: &AppConfig, updates: SettingsUpdatePayload) -> AppResult<AppConfig> {
+    let mut config = current.clone();
+
+    if let Some(hotkey) = updates.global_hotkey {
+        let normalized = hotkey.trim();
+        hotkey::validate_hotkey(normalized).map_err(AppError::HotkeyInvalid)?;
+        config.global_hotkey = normalized.to_string();
+    }
+
+    if let Some(bindings) = updates.mode_hotkeys {
+        config.mode_hotkeys = normalize_mode_hotkeys(bindings);
+    }
+
+    if let Some(value) = updates.double_tap_modifier {
+        let value = value.trim();
+        config.double_tap_modifier = (!value.is_empty()).then(|| value.to_string());
+    }
+
+    if let Some(advanced) = updates.advanced {
+        config.advanced = advanced.sanitized();
+    }
+
+    // 边界可能刚被修改，因此总是按当前边界重新约束这两个值
+    config.query_delay_ms = normalize_query_delay(
+        updates.query_delay_ms,
+        config.query_delay_ms,
+        &config.advanced,
+    );
+    config.max_results =
+        normalize_max_results(updates.max_results, config.max_results, &config.advanced);
+
+    if let Some(value) = updates.max_app_results {
+        config.max_app_results = value;
+    }
+
+    if let Some(value) = updates.max_bookmark_results {
+        config.max_bookmark_results = value;
+    }
+
+    if let Some(value) = updates.enable_app_results {
+        config.enable_app_results = value;
+    }
+
+    if let Some(value) = updates.extract_icons {
+        config.extract_icons = value;
+    }
+
+    if let Some(value) = updates.icon_size {
+        config.icon_size = value;
+    }
+
+    if let Some(patterns) = updates.exclude_exe_patterns {
+        config.exclude_exe_patterns = patterns
+            .iter()
+            .map(|pattern| pattern.trim().to_string())
+            .filter(|pattern| !pattern.is_empty())
+            .collect();
+    }
+
+    if let Some(value) = updates.enable_steam_games {
+        config.enable_steam_games = value;
+    }
+
+    if let Some(value) = updates.enable_bookmark_results {
+        config.enable_bookmark_results = value;
+    }
+
+    if let Some(value) = updates.enable_file_results {
+        config.enable_file_results = value;
+    }
+
+    if let Some(roots) = updates.file_search_roots {
+        config.file_search_roots = roots
+            .into_iter()
+            .map(|root| root.trim().to_string())
+            .filter(|root| !root.is_empty())
+            .collect();
+    }
+
+    if let Some(value) = updates.enable_clipboard_history {
+        config.enable_clipboard_history = value;
+    }
+
+    if let Some(value) = updates.clipboard_history_size {
+        config.clipboard_history_size = value.clamp(
+            clipboard_history::MIN_HISTORY_SIZE,
+            clipboard_history::MAX_HISTORY_SIZE,
+        );
+    }
+
+    if let Some(value) = updates.max_history_entries {
+        config.max_history_entries = value.max(1);
+    }
+
+    if let Some(value) = updates.max_history_results {
+        config.max_history_results = value;
+    }
+
+    if let Some(value) = updates.max_visible_results {
+        config.max_visible_results = value;
+    }
+
+    if let Some(value) = updates.enable_history_results {
+        config.enable_history_results = value;
+    }
+
+    if let Some(value) = updates.launch_on_startup {
+        config.launch_on_startup = value;
+    }
+
+    if let Some(value) = updates.force_english_input {
+        config.force_english_input = value;
+    }
+
+    if let Some(value) = updates.force_english_first_show_only {
+        config.force_english_first_show_only = value;
+    }
+
+    if let Some(value) = updates.force_english_by_mode {
+        config.force_english_by_mode = value;
+    }
+
+    if let Some(value) = updates.debug_mode {
+        config.debug_mode = value;
+    }
+
+    if let Some(value) = updates.window_opacity {
+        config.window_opacity = clamp_window_opacity(value);
+    }
+
+    if let Some(value) = updates.fallback_on_empty_results {
+        config.fallback_on_empty_results = value;
+    }
+
+    if let Some(value) = updates.empty_enter_action {
+        config.empty_enter_action = value;
+    }
+
+    if let Some(template) = updates.search_engine_template {
+        config.search_engine_template = template.trim().to_string();
+    }
+
+    if let Some(name) = updates.search_engine_name {
+        config.search_engine_name = name.trim().to_string();
+    }
+
+    if let Some(bangs) = updates.search_bangs {
+        config.search_bangs = bangs
+            .into_iter()
+            .map(|(bang, template)| {
+                (
+                    bang.trim().trim_start_matches('!').to_lowercase(),
+                    template.trim().to_string(),
+                )
+            })
+            .collect();
+    }
+
+    if let Some(value) = updates.enable_instant_answers {
+        config.enable_instant_answers = value;
+    }
+
+    if let Some(value) = updates.clear_query_on_hide {
+        config.clear_query_on_hide = value;
+    }
+
+    if let Some(value) = updates.escape_behavior {
+        config.escape_behavior = value;
+    }
+
+    if let Some(value) = updates.locale {
+        config.locale = value;
+    }
+
+    if let Some(value) = updates.working_dir_mode {
+        config.working_dir_mode = value;
+    }
+
+    if let Some(value) = updates.launch_cooldown_ms {
+        config.launch_cooldown_ms = value;
+    }
+
+    if let Some(value) = updates.bookmark_folder_confirm_threshold {
+        config.bookmark_folder_confirm_threshold = value;
+    }
+
+    if let Some(value) = updates.enter_on_running_app {
+        config.enter_on_running_app = value;
+    }
+
+    if let Some(value) = updates.typed_launch_args {
+        config.typed_launch_args = value;
+    }
+
+    if let Some(value) = updates.custom_working_dir {
+        config.custom_working_dir = value.trim().to_string();
+    }
+
+    if let Some(mut value) = updates.content_search {
+        value.timeout_ms = value.timeout_ms.clamp(100, 30_000);
+        value.max_results = value.max_results.clamp(1, 200);
+        config.content_search = value;
+    }
+
+    if let Some(value) = updates.custom_commands {
+        config.custom_commands = value
+            .into_iter()
+            .filter(|command| {
+                !command.keyword.trim().is_empty() && !command.target.trim().is_empty()
+            })
+            .collect();
+    }
+
+    if let Some(value) = updates.nav_wrap {
+        config.nav_wrap = value;
+    }
+
+    if let Some(value) = updates.page_jump_size {
+        config.page_jump_size = value.max(1);
+    }
+
+    if let Some(value) = updates.watch_bookmarks {
+        config.watch_bookmarks = value;
+    }
+
+    if let Some(value) = updates.reindex_interval_minutes {
+        config.reindex_interval_minutes = value;
+    }
+
+    if let Some(value) = updates.bookmark_browsers {
+        config.bookmark_browsers = value
+            .into_iter()
+            .map(|browser| browser.trim().to_string())
+            .filter(|browser| !browser.is_empty())
+            .collect();
+    }
+
+    if let Some(value) = updates.enable_edge_bookmarks {
+        config.enable_edge_bookmarks = value;
+    }
+
+    if let Some(value) = updates.enable_firefox_bookmarks {
+        config.enable_firefox_bookmarks = value;
+    }
+
+    if let Some(value) = updates.bookmark_profiles {
+        config.bookmark_profiles = value
+            .into_iter()
+            .map(|profile| profile.trim().to_string())
+            .filter(|profile| !profile.is_empty())
+            .collect();
+    }
+
+    if let Some(value) = updates.dedupe_bookmarks_across_profiles {
+        config.dedupe_bookmarks_across_profiles = value;
+    }
+
+    if let Some(value) = updates.favicon_refresh_enabled {
+        config.favicon_refresh_enabled = value;
+    }
+
+    if let Some(value) = updates.favicon_max_age_hours {
+        config.favicon_max_age_hours = value.max(MIN_FAVICON_MAX_AGE_HOURS);
+    }
+
+    if let Some(value) = updates.relative_score_cutoff {
+        config.relative_score_cutoff = clamp_relative_score_cutoff(value);
+    }
+
+    if let Some(value) = updates.prefix_boost {
+        config.prefix_boost = value.max(0);
+    }
+
+    if let Some(value) = updates.usage_boost {
+        config.usage_boost = clamp_usage_boost(value);
+    }
+
+    if let Some(value) = updates.keyword_penalty {
+        config.keyword_penalty = value.max(0);
+    }
+
+    if let Some(value) = updates.path_penalty {
+        config.path_penalty = value.max(0);
+    }
+
+    if let Some(value) = updates.url_penalty {
+        config.url_penalty = value.max(0);
+    }
+
+    if let Some(value) = updates.min_score_threshold {
+        config.min_score_threshold = value.max(0);
+    }
+
+    if let Some(value) = updates.match_algorithm {
+        config.match_algorithm = value;
+    }
+
+    if let Some(value) = updates.ignore_case {
+        config.ignore_case = value;
+    }
+
+    if let Some(value) = updates.ignore_accents {
+        config.ignore_accents = value;
+    }
+
+    if let Some(value) = updates.regex_queries {
+        config.regex_queries = value;
+    }
+
+    if let Some(value) = updates.exclude_unavailable_drives {
+        config.exclude_unavailable_drives = value;
+    }
+
+    if let Some(value) = updates.bookmark_recency_tiebreak {
+        config.bookmark_recency_tiebreak = value;
+    }
+
+    if let Some(value) = updates.prewarm_icons {
+        config.prewarm_icons = value;
+    }
+
+    if let Some(value) = updates.notify_new_apps {
+        config.notify_new_apps = value;
+    }
+
+    // 格式不对的前缀原样保留，由下面的整体校验报告；冲突也按全部新值检查，
+    // 这样在同一次更新中交换两个前缀也能通过
+    let normalized = |prefix: String| normalize_prefix(&prefix).unwrap_or(prefix);
+    if let Some(prefix) = updates.prefix_app {
+        config.prefix_app = normalized(prefix);
+    }
+    if let Some(prefix) = updates.prefix_bookmark {
+        config.prefix_bookmark = normalized(prefix);
+    }
+    if let Some(prefix) = updates.prefix_search {
+        config.prefix_search = normalized(prefix);
+    }
+    if let Some(prefix) = updates.prefix_file {
+        config.prefix_file = normalized(prefix);
+    }
+    if let Some(prefix) = updates.prefix_clipboard {
+        config.prefix_clipboard = normalized(prefix);
+    }
+    if let Some(prefix) = updates.prefix_run {
+        config.prefix_run = normalized(prefix);
+    }
+
+    validation::ensure_valid(&config).map_err(AppError::InvalidSetting)?;
+    Ok(config)
+}
+
+/// Re-registers the hotkeys of `previous` after applying new settings failed part-way.
+fn restore_hotkeys(
+    app_handle: &AppHandle,
+    state: &AppState,
+    previous: &AppConfig,
+    hotkey_changed: bool,
+    mode_hotkeys_changed: bool,
+) {
+    if hotkey_changed {
+        let _ = bind_hotkey(app_handle, state, &previous.global_hotkey, "main");
+    }
+    if mode_hotkeys_changed {
+        let _ = hotkey::bind_mode_hotkeys(app_handle, state, &previous.mode_hotkeys);
+    }
+}
+
+#[tauri::command]
+pub fn list_profiles(app_handle: AppHandle) -> AppResult<ConfigProfiles> {
+    config::list_profiles(&app_handle)
+}
+
+/// Creates a profile named `name` starting from a copy of the current settings. The active
+/// profile doesn't change.
+#[tauri::command]
+pub fn create_profile(
+    name: String,
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+) -> AppResult<()> {
+    let name = validated_profile_name(&name)?;
+    let current = state
+        .config
+        .lock()
+        .map_err(|_| "无法获取配置".to_string())?
+        .clone();
+    config::create_profile(&app_handle, name, &current)
+}
+
+/// Makes `name` the active profile: loads its settings, rebinds the hotkey, re-syncs
+/// launch-on-startup and the tray, and rebuilds the indexes its settings affect.
+#[tauri::command]
+pub fn switch_profile(
+    name: String,
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+) -> AppResult<AppConfig> {
+    let name = validated_profile_name(&name)?;
+    let mut loaded = config::load_profile(&app_handle, name)?;
+    loaded.mode_hotkeys = normalize_mode_hotkeys(loaded.mode_hotkeys);
+
+    let mut guard = state
+        .config
+        .lock()
+        .map_err(|_| "无法获取配置".to_string())?;
+    let previous_hotkey = guard.global_hotkey.clone();
+    let previous_mode_hotkeys = guard.mode_hotkeys.clone();
+    let hotkey_changed = loaded.global_hotkey.trim() != previous_hotkey;
+    let mode_hotkeys_changed = loaded.mode_hotkeys != previous_mode_hotkeys;
+    if hotkey_changed {
+        bind_hotkey(&app_handle, &state, &loaded.global_hotkey, "main")?;
+    }
+    if mode_hotkeys_changed {
+        if let Err(err) = hotkey::bind_mode_hotkeys(&app_handle, &state, &loaded.mode_hotkeys) {
+            if hotkey_changed {
+                let _ = bind_hotkey(&app_handle, &state, &previous_hotkey, "main");
+            }
+            return Err(err);
+        }
+    }
+    if let Err(err) = config::set_active_profile(&app_handle, name) {
+        // 切换未能持久化，恢复原快捷键，保持与仍在使用的配置一致
+        if mode_hotkeys_changed {
+            let _ = hotkey::bind_mode_hotkeys(&app_handle, &state, &previous_mode_hotkeys);
+        }
+        if hotkey_changed {
+            let _ = bind_hotkey(&app_handle, &state, &previous_hotkey, "main");
+        }
+        return Err(err);
+    }
+    *guard = loaded;
+    let snapshot = guard.clone();
+    drop(guard);
+
+    if let Err(err) = crate::windows_utils::configure_launch_on_startup(snapshot.launch_on_startup)
+    {
+        log::warn!("failed to sync launch-on-startup setting: {err}");
+    }
+    if let Err(err) = tray::refresh_tray_menu(&app_handle, snapshot.locale) {
+        log::warn!("failed to rebuild tray menu: {err}");
+    }
+    start_app_reindex(&app_handle, &state);
+    start_bookmark_reindex(&state);
+    log::info!("已切换到配置方案 {name}");
+    let _ = app_handle.emit(SETTINGS_UPDATED_EVENT, snapshot.clone());
+    Ok(snapshot)
+}
+
+/// Replaces every setting of the active profile with the defaults: rebinds the default
+/// hotkey, re-syncs launch-on-startup and the tray, saves, and rebuilds the indexes.
+#[tauri::command]
+pub fn reset_settings(app_handle: AppHandle, state: State<'_, AppState>) -> AppResult<AppConfig> {
+    let snapshot = replace_config(&app_handle, &state, AppConfig::default())?;
+    log::info!("设置已恢复为默认值");
+    Ok(snapshot)
+}
+
+/// Writes the current settings as pretty JSON to `path`, e.g. for a backup.
+#[tauri::command]
+pub fn export_settings(path: String, state: State<'_, AppState>) -> AppResult<()> {
+    let config = state
+        .config
+        .lock()
+        .map_err(|_| "无法获取配置".to_string())?
+        .clone();
+    let data =
+        serde_json::to_string_pretty(&config).map_err(|err| AppError::Internal(err.to_string()))?;
+    std::fs::write(path.trim(), data)
+        .map_err(|err| AppError::ConfigIo(format!("无法写入 {}: {err}", path.trim())))
+}
+
+/// Replaces the active settings with those in the JSON file at `path`, applied like
+/// [`reset_settings`]. The file is rejected without changing anything when it can't be
+/// parsed or [`validation::validate_config`] reports errors, e.g. an invalid hotkey.
+#[tauri::command]
+pub fn import_settings(
+    path: String,
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+) -> AppResult<AppConfig> {
+    let path = path.trim();
+    let content = std::fs::read_to_string(path)
+        .map_err(|err| AppError::ConfigIo(format!("无法读取 {path}: {err}")))?;
+    let imported: AppConfig = serde_json::from_str(&content)
+        .map_err(|err| AppError::InvalidSetting(format!("设置文件格式无效: {err}")))?;
+    let imported = prepare_config(imported)?;
+
+    let snapshot = replace_config(&app_handle, &state, imported)?;
+    log::info!("已从 {path} 导入设置");
+    Ok(snapshot)
+}
 
-    if let Some(value) = updates.enable_bookmark_results {
-        guard.enable_bookmark_results = value;
+/// Normalizes a config read as a whole, from an import or a hand-edited settings file, the way
+/// [`update_settings`] normalizes single values, and rejects it when
+/// [`validation::ensure_valid`] finds errors.
+pub(crate) fn prepare_config(mut config: AppConfig) -> AppResult<AppConfig> {
+    config.global_hotkey = config.global_hotkey.trim().to_string();
+    config.mode_hotkeys = normalize_mode_hotkeys(config.mode_hotkeys);
+    config.advanced = config.advanced.sanitized();
+    config.query_delay_ms = normalize_query_delay(None, config.query_delay_ms, &config.advanced);
+    config.max_results = normalize_max_results(None, config.max_results, &config.advanced);
+    config.window_opacity = clamp_window_opacity(config.window_opacity);
+    config.relative_score_cutoff = clamp_relative_score_cutoff(config.relative_score_cutoff);
+    config.usage_boost = clamp_usage_boost(config.usage_boost);
+    for prefix in [
+        &mut config.prefix_app,
+        &mut config.prefix_bookmark,
+        &mut config.prefix_search,
+        &mut config.prefix_file,
+        &mut config.prefix_clipboard,
+        &mut config.prefix_run,
+    ] {
+        if let Some(normalized) = normalize_prefix(prefix) {
+            *prefix = normalized;
+        }
     }
 
-    if let Some(value) = updates.launch_on_startup {
-        crate::windows_utils::configure_launch_on_startup(value)?;
-        guard.launch_on_startup = value;
-    }
+    validation::ensure_valid(&config).map_err(AppError::InvalidSetting)?;
+    Ok(config)
+}
 
-    if let Some(value) = updates.force_english_input {
-        guard.force_english_input = value;
-    }
+/// Makes `config` the active settings as a whole: rebinds the hotkey when it changed, saves,
+/// re-syncs launch-on-startup and the tray, rebuilds the indexes and notifies the windows.
+/// Nothing changes when the hotkey can't be bound or the file can't be saved.
+fn replace_config(
+    app_handle: &AppHandle,
+    state: &AppState,
+    mut config: AppConfig,
+) -> AppResult<AppConfig> {
+    config.global_hotkey = config.global_hotkey.trim().to_string();
+    config.mode_hotkeys = normalize_mode_hotkeys(config.mode_hotkeys);
 
-    if let Some(value) = updates.debug_mode {
-        guard.debug_mode = value;
+    let mut guard = state
+        .config
+        .lock()
+        .map_err(|_| "无法获取配置".to_string())?;
+    let previous_hotkey = guard.global_hotkey.clone();
+    let previous_mode_hotkeys = guard.mode_hotkeys.clone();
+    let hotkey_changed = config.global_hotkey != previous_hotkey;
+    let mode_hotkeys_changed = config.mode_hotkeys != previous_mode_hotkeys;
+    if hotkey_changed {
+        bind_hotkey(app_handle, state, &config.global_hotkey, "main")?;
     }
-
-    if let Some(value) = updates.window_opacity {
-        guard.window_opacity = clamp_window_opacity(value);
+    if mode_hotkeys_changed {
+        if let Err(err) = hotkey::bind_mode_hotkeys(app_handle, state, &config.mode_hotkeys) {
+            if hotkey_changed {
+                let _ = bind_hotkey(app_handle, state, &previous_hotkey, "main");
+            }
+            return Err(err);
+        }
     }
-
-    // 同步模式前缀设置（如果前端传入了非空值）
-    if let Some(prefix) = updates.prefix_app {
-        guard.prefix_app = normalize_prefix(&prefix)
-            .ok_or_else(|| "应用模式前缀需为单个字母，可选跟随空格或冒号".to_string())?;
+    if let Err(err) = config.save(app_handle) {
+        // 未能保存时恢复原快捷键，保持与仍在使用的配置一致
+        if mode_hotkeys_changed {
+            let _ = hotkey::bind_mode_hotkeys(app_handle, state, &previous_mode_hotkeys);
+        }
+        if hotkey_changed {
+            let _ = bind_hotkey(app_handle, state, &previous_hotkey, "main");
+        }
+        return Err(err);
     }
+    *guard = config;
+    let snapshot = guard.clone();
+    drop(guard);
 
-    if let Some(prefix) = updates.prefix_bookmark {
-        guard.prefix_bookmark = normalize_prefix(&prefix)
-            .ok_or_else(|| "书签模式前缀需为单个字母，可选跟随空格或冒号".to_string())?;
+    if let Err(err) = crate::windows_utils::configure_launch_on_startup(snapshot.launch_on_startup)
+    {
+        log::warn!("failed to sync launch-on-startup setting: {err}");
     }
-
-    if let Some(prefix) = updates.prefix_search {
-        guard.prefix_search = normalize_prefix(&prefix)
-            .ok_or_else(|| "搜索模式前缀需为单个字母，可选跟随空格或冒号".to_string())?;
+    if let Err(err) = tray::refresh_tray_menu(app_handle, snapshot.locale) {
+        log::warn!("failed to rebuild tray menu: {err}");
     }
-
-    guard.save(&app_handle)?;
-    let snapshot = guard.clone();
+    start_app_reindex(app_handle, state);
+    start_bookmark_reindex(state);
     let _ = app_handle.emit(SETTINGS_UPDATED_EVENT, snapshot.clone());
     Ok(snapshot)
 }
 
+fn validated_profile_name(name: &str) -> AppResult<&str> {
+    let name = name.trim();
+    if config::is_valid_profile_name(name) {
+        Ok(name)
+    } else {
+        Err(AppError::InvalidSetting(
+            "配置方案名称只能包含字母、数字、- 和 _，且不超过 32 个字符".into(),
+        ))
+    }
+}
+
 #[tauri::command]
 pub fn update_hotkey(
     hotkey: String,
     query_delay_ms: Option<u64>,
     app_handle: AppHandle,
     state: State<'_, AppState>,
-) -> Result<AppConfig, String> {
+) -> AppResult<AppConfig> {
     update_settings(
         SettingsUpdatePayload {
             global_hotkey: Some(hotkey),
             query_delay_ms,
-            max_results: None,
-            enable_app_results: None,
-            enable_bookmark_results: None,
-            prefix_app: None,
-            prefix_bookmark: None,
-            prefix_search: None,
-            launch_on_startup: None,
-            force_english_input: None,
-            debug_mode: None,
-            window_opacity: None,
+            ..Default::default()
         },
         app_handle,
         state,
     )
 }
 
-fn normalize_query_delay(candidate: Option<u64>, current: u64) -> u64 {
-    let value = candidate.unwrap_or(current);
-    value.clamp(MIN_QUERY_DELAY_MS, MAX_QUERY_DELAY_MS)
+/// Temporarily unregisters the global hotkey, e.g. while gaming. With `minutes` it is
+/// re-enabled automatically after that time.
+#[tauri::command]
+pub fn disable_hotkey(
+    minutes: Option<u64>,
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+) -> AppResult<()> {
+    let duration = minutes
+        .filter(|value| *value > 0)
+        .map(|value| Duration::from_secs(value.saturating_mul(60)));
+    hotkey::suspend_hotkey(&app_handle, &state, duration)
 }
 
-fn normalize_max_results(candidate: Option<u32>, current: u32) -> u32 {
-    let value = candidate.unwrap_or(current);
-    value.clamp(MIN_RESULT_LIMIT, MAX_RESULT_LIMIT)
+#[tauri::command]
+pub fn enable_hotkey(app_handle: AppHandle, state: State<'_, AppState>) -> AppResult<()> {
+    hotkey::resume_hotkey(&app_handle, &state)
 }
 
-fn clamp_window_opacity(value: f32) -> f32 {
-    value.clamp(MIN_WINDOW_OPACITY, MAX_WINDOW_OPACITY)
+#[tauri::command]
+pub fn get_registered_shortcuts(state: State<'_, AppState>) -> RegisteredShortcuts {
+    hotkey::registered_shortcuts(&state)
 }
 
-fn normalize_prefix(value: &str) -> Option<String> {
-    let trimmed_start = value.trim_start();
-    if trimmed_start.is_empty() {
-        return None;
-    }
-
-    let mut chars = trimmed_start.chars();
-    let Some(first) = chars.next() else {
-        return None;
-    };
+/// Notifies the frontend to hide, carrying the `clear_query_on_hide` preference so every hide path behaves the same.
+pub(crate) fn emit_hide_window(app_handle: &AppHandle, reason: HideReason) {
+    let clear_query = app_handle
+        .try_state::<AppState>()
+        .and_then(|state| state.config.lock().ok().map(|cfg| cfg.clear_query_on_hide))
+        .unwrap_or(true);
+    let _ = app_handle.emit(
+        HIDE_WINDOW_EVENT,
+        HideWindowPayload {
+            clear_query,
+            reason,
+        },
+    );
+}
 
-    if !first.is_ascii_alphabetic() {
-        return None;
+fn normalize_query_delay(candidate: Option<u64>, current: u64, bounds: &AdvancedConfig) -> u64 {
+    let value = candidate.unwrap_or(current);
+    // 0 表示即时模式：不设最小延迟，重叠的查询由 query_ticket 合并
+    if value == 0 {
+        return 0;
     }
+    let (min, max) = bounds.query_delay_bounds();
+    value.clamp(min, max)
+}
 
-    let mut normalized = String::new();
-    normalized.push(first.to_ascii_uppercase());
+/// Trims both fields of every binding and drops the ones left without a hotkey.
+fn normalize_mode_hotkeys(bindings: Vec<ModeHotkey>) -> Vec<ModeHotkey> {
+    bindings
+        .into_iter()
+        .map(|binding| ModeHotkey {
+            hotkey: binding.hotkey.trim().to_string(),
+            mode: binding.mode.trim().to_ascii_lowercase(),
+        })
+        .filter(|binding| !binding.hotkey.is_empty())
+        .collect()
+}
 
-    let remainder: String = chars.collect();
-    let mut remainder_chars = remainder.chars().filter(|c| !c.is_control());
-    if let Some(next) = remainder_chars.next() {
-        match next {
-            ' ' | ':' => normalized.push(next),
-            _ => return None,
-        }
+fn normalize_max_results(candidate: Option<u32>, current: u32, bounds: &AdvancedConfig) -> u32 {
+    let value = candidate.unwrap_or(current);
+    let (min, max) = bounds.result_limit_bounds();
+    value.clamp(min, max)
+}
 
-        if remainder_chars.any(|c| !c.is_whitespace()) {
-            return None;
-        }
+fn clamp_window_opacity(value: f32) -> f32 {
+    value.clamp(MIN_WINDOW_OPACITY, MAX_WINDOW_OPACITY)
+}
+
+fn clamp_usage_boost(value: f32) -> f32 {
+    if value.is_finite() {
+        value.max(0.0)
+    } else {
+        0.0
     }
+}
 
-    Some(normalized)
+fn clamp_relative_score_cutoff(value: f32) -> f32 {
+    if value.is_finite() {
+        value.clamp(0.0, 1.0)
+    } else {
+        0.0
+    }
 }
 
 fn open_url(app_handle: &AppHandle, target: &str) -> Result<(), String> {
@@ -482,15 +2656,87 @@ fn open_url(app_handle: &AppHandle, target: &str) -> Result<(), String> {
         .map_err(|err| err.to_string())
 }
 
-fn launch_win32_app(app: &ApplicationInfo) -> Result<(), String> {
+fn open_path(app_handle: &AppHandle, path: &str) -> Result<(), String> {
+    app_handle
+        .opener()
+        .open_path(path.to_string(), Option::<&str>::None)
+        .map_err(|err| err.to_string())
+}
+
+/// Opens every URL even if some fail, then reports how many failed.
+fn open_all_urls(app_handle: &AppHandle, urls: &[String]) -> Result<(), String> {
+    let failed = urls
+        .iter()
+        .filter(|url| {
+            open_url(app_handle, url)
+                .inspect_err(|err| log::warn!("failed to open {url}: {err}"))
+                .is_err()
+        })
+        .count();
+    if failed == 0 {
+        Ok(())
+    } else {
+        Err(format!("{failed}/{} 个链接打开失败", urls.len()))
+    }
+}
+
+fn launch_configured_win32_app(
+    app_handle: &AppHandle,
+    app: &ApplicationInfo,
+) -> Result<(), String> {
+    let working_dir = app_handle
+        .try_state::<AppState>()
+        .and_then(|state| state.config.lock().ok().map(|cfg| cfg.clone()))
+        .and_then(|cfg| launch_working_dir(app, &cfg));
+    launch_win32_app(app, working_dir.as_deref())
+}
+
+/// Picks the working directory for a Win32 launch according to `working_dir_mode`.
+fn launch_working_dir(app: &ApplicationInfo, config: &AppConfig) -> Option<String> {
+    let exe_parent = || {
+        app.working_directory
+            .clone()
+            .filter(|dir| !dir.trim().is_empty())
+            .or_else(|| {
+                Path::new(&app.path)
+                    .parent()
+                    .filter(|dir| !dir.as_os_str().is_empty())
+                    .map(|dir| dir.to_string_lossy().into_owned())
+            })
+    };
+
+    match config.working_dir_mode {
+        WorkingDirMode::ExeParent => exe_parent(),
+        WorkingDirMode::Inherit => None,
+        WorkingDirMode::Custom => {
+            let custom = config.custom_working_dir.trim();
+            if !custom.is_empty() && Path::new(custom).is_dir() {
+                Some(custom.to_string())
+            } else {
+                log::warn!("自定义工作目录无效，改用程序所在目录: {custom}");
+                exe_parent()
+            }
+        }
+    }
+}
+
+fn launch_win32_app(app: &ApplicationInfo, working_dir: Option<&str>) -> Result<(), String> {
     let primary = Path::new(&app.path);
-    match shell_execute_path(primary) {
+    match shell_execute_path(primary, app.launch_args.as_deref(), working_dir) {
         Ok(_) => Ok(()),
         Err(primary_err) => {
             if let Some(source) = &app.source_path {
+                // 快捷方式自带的参数在前，保存的启动参数追加在后
+                let arguments = [app.arguments.as_deref(), app.launch_args.as_deref()]
+                    .into_iter()
+                    .flatten()
+                    .map(str::trim)
+                    .filter(|value| !value.is_empty())
+                    .collect::<Vec<_>>()
+                    .join(" ");
                 launch_from_source(
                     source,
-                    app.arguments.as_deref(),
+                    Some(arguments.as_str()),
                     app.working_directory.as_deref(),
                 )
                 .or(Err(primary_err))
@@ -501,55 +2747,72 @@ fn launch_win32_app(app: &ApplicationInfo) -> Result<(), String> {
     }
 }
 
-fn shell_execute_path(path: &Path) -> Result<(), String> {
+fn shell_execute_path(
+    path: &Path,
+    arguments: Option<&str>,
+    working_dir: Option<&str>,
+) -> Result<(), String> {
     if !path.exists() {
         return Err("目标程序不存在或已被移动".into());
     }
 
-    shell_execute_internal(path.as_os_str(), None, None)
+    let argument_os = arguments
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(OsString::from);
+    let working_dir_os = working_dir.map(OsString::from);
+    shell_execute_internal(
+        None,
+        path.as_os_str(),
+        argument_os.as_deref(),
+        working_dir_os.as_deref(),
+    )
 }
 
+/// Starts a Store app through `IApplicationActivationManager`. When activation fails, e.g.
+/// because COM was set up in an unexpected apartment on this thread, the app is started via
+/// `explorer.exe shell:AppsFolder\<AppUserModelId>` instead.
 fn launch_uwp_app(app_id: &str) -> Result<(), String> {
+    let activation_err = match activate_uwp_app(app_id) {
+        Ok(()) => {
+            log::info!("已通过 ActivateApplication 启动 {app_id}");
+            return Ok(());
+        }
+        Err(err) => err,
+    };
+    let activation_detail = format!(
+        "ActivateApplication 失败 (HRESULT 0x{:08X}): {}",
+        activation_err.code().0 as u32,
+        activation_err.message()
+    );
+    log::warn!("{activation_detail}，改用资源管理器启动 {app_id}");
+
+    let apps_folder_target = format!("shell:AppsFolder\\{app_id}");
+    match shell_execute_raw("explorer.exe", Some(&apps_folder_target), None) {
+        Ok(()) => {
+            log::info!("已通过 shell:AppsFolder 启动 {app_id}");
+            Ok(())
+        }
+        Err(fallback_err) => Err(format!(
+            "{activation_detail}；通过资源管理器启动也失败: {fallback_err}"
+        )),
+    }
+}
+
+fn activate_uwp_app(app_id: &str) -> windows::core::Result<()> {
     unsafe {
-        let _guard = ComGuard::new().map_err(|err| err.to_string())?;
+        let _guard = ComGuard::new()?;
 
         let manager: IApplicationActivationManager =
-            CoCreateInstance(&ApplicationActivationManager, None, CLSCTX_LOCAL_SERVER)
-                .map_err(|err| err.to_string())?;
+            CoCreateInstance(&ApplicationActivationManager, None, CLSCTX_LOCAL_SERVER)?;
 
         let app_id = HSTRING::from(app_id);
-        let _process_id = manager
-            .ActivateApplication(&app_id, PCWSTR::null(), ACTIVATEOPTIONS::default())
-            .map_err(|err| err.to_string())?;
+        let _process_id =
+            manager.ActivateApplication(&app_id, PCWSTR::null(), ACTIVATEOPTIONS::default())?;
         Ok(())
     }
 }
 
-fn is_url_like(input: &str) -> bool {
-    input.starts_with("http://")
-        || input.starts_with("https://")
-        || input.contains('.') && input.split_whitespace().count() == 1
-}
-
-fn match_application(matcher: &SkimMatcherV2, app: &ApplicationInfo, query: &str) -> Option<i64> {
-    let mut best = matcher.fuzzy_match(&app.name, query);
-
-    for keyword in &app.keywords {
-        if keyword.is_empty() {
-            continue;
-        }
-
-        if let Some(score) = matcher.fuzzy_match(keyword, query) {
-            let score = score - 5; // prefer primary name by adding small penalty to keyword matches
-            if best.is_none_or(|current| score > current) {
-                best = Some(score);
-            }
-        }
-    }
-
-    best
-}
-
 fn launch_from_source(
     source: &str,
     arguments: Option<&str>,
@@ -583,6 +2846,7 @@ fn shell_execute_raw(
         .map(OsString::from);
 
     shell_execute_internal(
+        None,
         target_os.as_os_str(),
         argument_os.as_deref(),
         working_dir_os.as_deref(),
@@ -591,14 +2855,82 @@ fn shell_execute_raw(
 
 fn shell_execute_uri(uri: &str) -> Result<(), String> {
     let uri_os = OsString::from(uri);
-    shell_execute_internal(uri_os.as_os_str(), None, None)
+    shell_execute_internal(None, uri_os.as_os_str(), None, None)
+}
+
+/// Shows the Windows "Open with" dialog for `target`.
+///
+/// Falls back to `OpenAs_RunDLL` when the `openas` verb is rejected, e.g. because no
+/// handler is registered for the file type, so the user still gets the OS dialog.
+fn open_with_dialog(target: &str) -> Result<(), String> {
+    let target_os = OsString::from(target);
+    if shell_execute_internal(
+        Some(OsStr::new("openas")),
+        target_os.as_os_str(),
+        None,
+        None,
+    )
+    .is_ok()
+    {
+        return Ok(());
+    }
+
+    let arguments = OsString::from(format!("shell32.dll,OpenAs_RunDLL {target}"));
+    shell_execute_internal(
+        None,
+        OsStr::new("rundll32.exe"),
+        Some(arguments.as_os_str()),
+        None,
+    )
+}
+
+/// Pins `target` to the taskbar through the `taskbarpin` shell verb.
+///
+/// Windows 10 and later no longer expose this verb to third-party programs, in which case
+/// ShellExecute reports a missing association and a clear "unsupported" error is returned.
+fn pin_to_taskbar(target: &str) -> Result<(), String> {
+    const SE_ERR_NOASSOC: isize = 31;
+    const SE_ERR_ACCESSDENIED: isize = 5;
+
+    if !Path::new(target).exists() {
+        return Err("目标程序不存在或已被移动".into());
+    }
+
+    let target_os = OsString::from(target);
+    match shell_execute_status(
+        Some(OsStr::new("taskbarpin")),
+        target_os.as_os_str(),
+        None,
+        None,
+    ) {
+        code if code > 32 => Ok(()),
+        SE_ERR_NOASSOC | SE_ERR_ACCESSDENIED => Err("当前 Windows 版本不支持固定到任务栏".into()),
+        code => Err(format!("固定到任务栏失败 (ShellExecute 错误码 {code})")),
+    }
 }
 
 fn shell_execute_internal(
+    verb: Option<&OsStr>,
     target: &OsStr,
     arguments: Option<&OsStr>,
     working_directory: Option<&OsStr>,
 ) -> Result<(), String> {
+    let code = shell_execute_status(verb, target, arguments, working_directory);
+    if code <= 32 {
+        Err(format!("无法启动程序 (ShellExecute 错误码 {code})"))
+    } else {
+        Ok(())
+    }
+}
+
+/// Calls `ShellExecuteW` and returns its raw status; values `<= 32` are errors.
+fn shell_execute_status(
+    verb: Option<&OsStr>,
+    target: &OsStr,
+    arguments: Option<&OsStr>,
+    working_directory: Option<&OsStr>,
+) -> isize {
+    let verb_buffer = verb.map(os_str_to_wide);
     let file_buffer = os_str_to_wide(target);
     let arg_buffer = arguments.map(os_str_to_wide);
     let dir_buffer = working_directory.map(os_str_to_wide);
@@ -611,11 +2943,15 @@ fn shell_execute_internal(
         .as_ref()
         .map(|value| PCWSTR(value.as_ptr()))
         .unwrap_or(PCWSTR::null());
+    let verb_ptr = verb_buffer
+        .as_ref()
+        .map(|value| PCWSTR(value.as_ptr()))
+        .unwrap_or(PCWSTR::null());
 
     let result = unsafe {
         ShellExecuteW(
             HWND(ptr::null_mut()),
-            PCWSTR::null(),
+            verb_ptr,
             PCWSTR(file_buffer.as_ptr()),
             arg_ptr,
             dir_ptr,
@@ -623,49 +2959,636 @@ fn shell_execute_internal(
         )
     };
 
-    if result.0 as isize <= 32 {
-        Err(format!(
-            "无法启动程序 (ShellExecute 错误码 {})",
-            result.0 as isize
-        ))
-    } else {
-        Ok(())
-    }
+    result.0 as isize
 }
 
-fn match_bookmark(matcher: &SkimMatcherV2, bookmark: &BookmarkEntry, query: &str) -> Option<i64> {
-    let mut best = matcher.fuzzy_match(&bookmark.title, query);
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prepared_config_is_normalized() {
+        let config = prepare_config(AppConfig {
+            global_hotkey: " Alt+Space ".to_string(),
+            prefix_bookmark: "b:".to_string(),
+            window_opacity: 5.0,
+            ..AppConfig::default()
+        })
+        .unwrap();
+        assert_eq!(config.global_hotkey, "Alt+Space");
+        assert_eq!(config.prefix_bookmark, "B:");
+        assert_eq!(config.window_opacity, MAX_WINDOW_OPACITY);
+    }
 
-    if let Some(path) = &bookmark.folder_path {
-        if let Some(score) = matcher.fuzzy_match(path, query) {
-            let score = score - 5;
-            if best.is_none_or(|current| score > current) {
-                best = Some(score);
-            }
+    #[test]
+    fn prepared_config_with_errors_is_rejected() {
+        let config = AppConfig {
+            prefix_file: "r".to_string(),
+            ..AppConfig::default()
+        };
+        assert!(prepare_config(config).is_err());
+    }
+
+    fn state_with_actions(actions: Vec<(&str, PendingAction)>) -> AppState {
+        let state = AppState::new();
+        state.pending_actions.lock().unwrap().extend(
+            actions
+                .into_iter()
+                .map(|(id, action)| (id.to_string(), action)),
+        );
+        state
+    }
+
+    #[test]
+    fn running_app_step_follows_the_choice() {
+        let code = app("code", "Code");
+        let running = |_: &str| true;
+        let stopped = |_: &str| false;
+
+        assert_eq!(
+            running_app_step(&code, EnterOnRunningApp::FocusExisting, running),
+            RunningAppStep::Focus
+        );
+        assert_eq!(
+            running_app_step(&code, EnterOnRunningApp::Ask, running),
+            RunningAppStep::Ask
+        );
+        for choice in [EnterOnRunningApp::FocusExisting, EnterOnRunningApp::Ask] {
+            assert_eq!(
+                running_app_step(&code, choice, stopped),
+                RunningAppStep::Launch
+            );
         }
     }
 
-    if let Some(score) = matcher
-        .fuzzy_match(&bookmark.url, query)
-        .map(|value| value - 8)
-    {
-        if best.is_none_or(|current| score > current) {
-            best = Some(score);
+    #[test]
+    fn launch_new_and_store_apps_skip_the_process_check() {
+        let unexpected = |path: &str| panic!("process list read for {path}");
+        assert_eq!(
+            running_app_step(
+                &app("code", "Code"),
+                EnterOnRunningApp::LaunchNew,
+                unexpected
+            ),
+            RunningAppStep::Launch
+        );
+        let mut store = app("calc", "Calculator");
+        store.app_type = AppType::Uwp;
+        for choice in [EnterOnRunningApp::FocusExisting, EnterOnRunningApp::Ask] {
+            assert_eq!(
+                running_app_step(&store, choice, unexpected),
+                RunningAppStep::Launch
+            );
         }
     }
 
-    for keyword in &bookmark.keywords {
-        if keyword.is_empty() {
-            continue;
+    fn state_with_apps(apps: Vec<ApplicationInfo>) -> AppState {
+        let state = AppState::new();
+        *state.app_index.lock().unwrap() = apps;
+        state
+    }
+
+    #[test]
+    fn refresh_icon_reads_the_icon_source_of_the_app() {
+        let mut code = app("code", "Code");
+        code.icon_source = Some((r"C:\Apps\code.exe".to_string(), 2));
+        let state = state_with_apps(vec![code, app("uwp", "Store App")]);
+
+        assert_eq!(
+            icon_source(&state, "code").unwrap(),
+            (r"C:\Apps\code.exe".to_string(), 2)
+        );
+        assert!(matches!(
+            icon_source(&state, "uwp"),
+            Err(AppError::NotApplicable(_))
+        ));
+        assert!(matches!(
+            icon_source(&state, "missing"),
+            Err(AppError::Internal(_))
+        ));
+    }
+
+    #[test]
+    fn refreshed_icon_replaces_only_that_app_icon() {
+        let mut code = app("code", "Code");
+        code.icon_b64 = "old".to_string();
+        let mut paint = app("paint", "Paint");
+        paint.icon_b64 = "paint".to_string();
+        let state = state_with_apps(vec![code, paint]);
+
+        assert_eq!(
+            store_refreshed_icon(&state, "code", Some("new".to_string())).unwrap(),
+            "new"
+        );
+        let icons: Vec<String> = state
+            .app_index
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|app| app.icon_b64.clone())
+            .collect();
+        assert_eq!(icons, ["new", "paint"]);
+    }
+
+    #[test]
+    fn failed_extraction_keeps_the_old_icon() {
+        let mut code = app("code", "Code");
+        code.icon_b64 = "old".to_string();
+        let state = state_with_apps(vec![code]);
+
+        assert!(store_refreshed_icon(&state, "code", None).is_err());
+        assert_eq!(state.app_index.lock().unwrap()[0].icon_b64, "old");
+    }
+
+    fn saved_bookmark() -> bookmarks::BookmarkEntry {
+        bookmarks::BookmarkEntry {
+            id: "chrome:1".to_string(),
+            title: "Docs".to_string(),
+            url: "https://docs.example".to_string(),
+            folder_path: None,
+            browser: "Chrome".to_string(),
+            profile: "默认".to_string(),
+            keywords: Vec::new(),
+            icon_b64: String::new(),
+            date_added: None,
         }
+    }
 
-        if let Some(score) = matcher.fuzzy_match(keyword, query) {
-            let score = score - 8;
-            if best.is_none_or(|current| score > current) {
-                best = Some(score);
-            }
+    #[test]
+    fn bookmark_reindex_leaves_the_app_index_alone() {
+        let state = state_with_apps(vec![app("code", "Code")]);
+        // 不加载任何浏览器，重建结果为空列表
+        state.config.lock().unwrap().bookmark_browsers = vec!["none".to_string()];
+        *state.bookmark_index.lock().unwrap() = vec![saved_bookmark()];
+        state.app_reindex_in_progress.store(true, Ordering::Release);
+
+        assert!(start_bookmark_reindex(&state));
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while state.bookmark_reindex_in_progress.load(Ordering::Acquire) {
+            assert!(Instant::now() < deadline, "bookmark reindex did not finish");
+            std::thread::sleep(Duration::from_millis(5));
+        }
+
+        assert!(state.bookmark_index.lock().unwrap().is_empty());
+        let apps = state.app_index.lock().unwrap();
+        assert_eq!(apps.len(), 1);
+        assert_eq!(apps[0].id, "code");
+        assert!(state.app_reindex_in_progress.load(Ordering::Acquire));
+    }
+
+    #[test]
+    fn running_bookmark_reindex_skips_a_second_request() {
+        let state = state_with_apps(vec![app("code", "Code")]);
+        *state.bookmark_index.lock().unwrap() = vec![saved_bookmark()];
+        state
+            .bookmark_reindex_in_progress
+            .store(true, Ordering::Release);
+
+        assert!(!start_bookmark_reindex(&state));
+        assert_eq!(state.bookmark_index.lock().unwrap().len(), 1);
+        assert!(!state.app_reindex_in_progress.load(Ordering::Acquire));
+    }
+
+    #[test]
+    fn responses_are_marked_stale_while_any_index_rebuilds() {
+        let state = state_with_apps(vec![app("code", "Code")]);
+        state.config.lock().unwrap().query_delay_ms = 0;
+        let query = |state: &AppState| {
+            tauri::async_runtime::block_on(run_query("code".to_string(), None, state, false))
+                .unwrap()
+        };
+
+        let fresh = query(&state);
+        assert!(!fresh.stale_index);
+        assert!(!fresh.results.is_empty());
+
+        for flag in [
+            &state.app_reindex_in_progress,
+            &state.bookmark_reindex_in_progress,
+            &state.history_reindex_in_progress,
+        ] {
+            flag.store(true, Ordering::Release);
+            let response = query(&state);
+            assert!(response.stale_index);
+            // 重建期间仍返回旧索引中的结果
+            assert!(!response.results.is_empty());
+            flag.store(false, Ordering::Release);
+        }
+        assert!(!query(&state).stale_index);
+    }
+
+    #[test]
+    fn keep_open_skips_the_hide_path() {
+        let mut hidden = false;
+        finish_action(true, || hidden = true);
+        assert!(!hidden);
+
+        finish_action(false, || hidden = true);
+        assert!(hidden);
+    }
+
+    #[test]
+    fn pending_actions_survive_for_the_next_launch() {
+        let state = state_with_actions(vec![(
+            "url-a",
+            PendingAction::Url("https://example.com".to_string()),
+        )]);
+
+        for _ in 0..2 {
+            assert!(matches!(
+                resolve_pending_action(&state, "url-a"),
+                Ok(PendingAction::Url(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn bookmark_folders_above_the_threshold_need_confirmation() {
+        let state = AppState::new();
+        state
+            .config
+            .lock()
+            .unwrap()
+            .bookmark_folder_confirm_threshold = 3;
+        let folder = |count: usize| {
+            PendingAction::OpenBookmarkFolder(vec!["https://example.com".to_string(); count])
+        };
+
+        assert!(bulk_open_prompt(&state, &folder(2)).is_none());
+        assert!(bulk_open_prompt(&state, &folder(3)).is_none());
+        let prompt = bulk_open_prompt(&state, &folder(4)).expect("4 links exceed 3");
+        assert!(prompt.contains('4'), "{prompt}");
+        assert!(bulk_open_prompt(
+            &state,
+            &PendingAction::Url("https://example.com".to_string())
+        )
+        .is_none());
+    }
+
+    #[test]
+    fn bulk_action_checks_each_id_on_its_own() {
+        let folder = vec!["https://example.com".to_string(); 50];
+        let state = state_with_actions(vec![
+            (
+                "url-a",
+                PendingAction::Url("https://example.com".to_string()),
+            ),
+            ("folder", PendingAction::OpenBookmarkFolder(folder)),
+        ]);
+        let cooldown = Duration::from_secs(60);
+
+        assert!(matches!(
+            bulk_action(&state, "url-a", cooldown),
+            Ok(Some(PendingAction::Url(_)))
+        ));
+        assert!(matches!(
+            bulk_action(&state, "stale", cooldown),
+            Err(AppError::ActionExpired)
+        ));
+        assert!(matches!(
+            bulk_action(&state, "folder", cooldown),
+            Err(AppError::NotApplicable(_))
+        ));
+    }
+
+    #[test]
+    fn bulk_action_skips_an_id_repeated_within_the_cooldown() {
+        let state = state_with_actions(vec![(
+            "url-a",
+            PendingAction::Url("https://example.com".to_string()),
+        )]);
+        assert!(claim_execution(
+            &state,
+            "url-a",
+            Duration::from_secs(60),
+            Instant::now()
+        ));
+
+        assert!(matches!(
+            bulk_action(&state, "url-a", Duration::from_secs(60)),
+            Ok(None)
+        ));
+        assert!(matches!(
+            bulk_action(&state, "url-a", Duration::ZERO),
+            Ok(Some(_))
+        ));
+    }
+
+    #[test]
+    fn failed_run_clears_only_its_own_cooldown() {
+        let state = state_with_actions(vec![(
+            "url-a",
+            PendingAction::Url("https://example.com".to_string()),
+        )]);
+        let cooldown = Duration::from_secs(60);
+        assert!(claim_execution(&state, "url-a", cooldown, Instant::now()));
+
+        forget_execution(&state, "url-b");
+        assert!(is_repeated_execution(&state, "url-a", cooldown));
+
+        forget_execution(&state, "url-a");
+        assert!(!is_repeated_execution(&state, "url-a", cooldown));
+    }
+
+    #[test]
+    fn only_one_of_two_rapid_executions_launches() {
+        let state = AppState::new();
+        let cooldown = Duration::from_millis(500);
+        let start = Instant::now();
+
+        assert!(claim_execution(&state, "app-a", cooldown, start));
+        assert!(!claim_execution(
+            &state,
+            "app-a",
+            cooldown,
+            start + Duration::from_millis(30)
+        ));
+        // 其他结果不受冷却时间影响
+        assert!(claim_execution(
+            &state,
+            "app-b",
+            cooldown,
+            start + Duration::from_millis(40)
+        ));
+    }
+
+    #[test]
+    fn slower_repeat_launches_again() {
+        let state = AppState::new();
+        let cooldown = Duration::from_millis(500);
+        let start = Instant::now();
+
+        assert!(claim_execution(&state, "app-a", cooldown, start));
+        assert!(claim_execution(&state, "app-a", cooldown, start + cooldown));
+        assert!(claim_execution(
+            &state,
+            "app-a",
+            Duration::ZERO,
+            start + cooldown
+        ));
+    }
+
+    #[test]
+    fn concurrent_claims_let_exactly_one_through() {
+        let state = AppState::new();
+        let cooldown = Duration::from_secs(60);
+        let now = Instant::now();
+        let launches = std::thread::scope(|scope| {
+            let handles: Vec<_> = (0..8)
+                .map(|_| scope.spawn(|| claim_execution(&state, "app-a", cooldown, now)))
+                .collect();
+            handles
+                .into_iter()
+                .map(|handle| handle.join().unwrap())
+                .filter(|launched| *launched)
+                .count()
+        });
+        assert_eq!(launches, 1);
+    }
+
+    fn numbered_keys(generation: u64) -> QuickKeys {
+        QuickKeys {
+            generation,
+            ids: vec!["app-a".to_string(), "url-b".to_string()],
+        }
+    }
+
+    #[test]
+    fn quick_key_maps_back_to_the_numbered_id() {
+        let quick_keys = numbered_keys(7);
+        assert_eq!(quick_key_id(&quick_keys, 1, 7).unwrap(), "app-a");
+        assert_eq!(quick_key_id(&quick_keys, 2, 7).unwrap(), "url-b");
+    }
+
+    #[test]
+    fn quick_key_out_of_range_is_expired() {
+        let quick_keys = numbered_keys(7);
+        assert!(matches!(
+            quick_key_id(&quick_keys, 0, 7),
+            Err(AppError::ActionExpired)
+        ));
+        assert!(matches!(
+            quick_key_id(&quick_keys, 3, 7),
+            Err(AppError::ActionExpired)
+        ));
+    }
+
+    #[test]
+    fn quick_key_from_older_results_is_expired() {
+        let quick_keys = numbered_keys(7);
+        assert!(matches!(
+            quick_key_id(&quick_keys, 1, 6),
+            Err(AppError::ActionExpired)
+        ));
+    }
+
+    fn app(id: &str, name: &str) -> ApplicationInfo {
+        ApplicationInfo {
+            id: id.to_string(),
+            name: name.to_string(),
+            path: format!(r"C:\Apps\{id}.exe"),
+            source_path: None,
+            app_type: AppType::Win32,
+            icon_b64: String::new(),
+            description: None,
+            keywords: Vec::new(),
+            working_directory: None,
+            arguments: None,
+            origin: None,
+            icon_source: None,
+            launch_args: None,
+            indexed_name: None,
+        }
+    }
+
+    #[test]
+    fn relevance_overrides_change_the_preview_but_not_the_config() {
+        let state = AppState::new();
+        *state.app_index.lock().unwrap() =
+            vec![app("code", "Code"), app("vscode", "Visual Studio Code")];
+        let overrides = RelevanceOverrides {
+            max_visible_results: Some(1),
+            prefix_boost: Some(0),
+            ..RelevanceOverrides::default()
+        };
+
+        let (stored, _) = collect_results("code", None, &state, None);
+        let (preview, _) = collect_results("code", None, &state, Some(&overrides));
+        assert!(stored.len() > 1);
+        assert_eq!(preview.len(), 1);
+
+        let config = state.config.lock().unwrap();
+        assert_eq!(config.max_visible_results, 0);
+        assert_eq!(config.prefix_boost, AppConfig::default().prefix_boost);
+    }
+
+    #[test]
+    fn zero_query_delay_is_kept_instead_of_clamped_up() {
+        let bounds = AdvancedConfig::default();
+        assert_eq!(normalize_query_delay(Some(0), 120, &bounds), 0);
+        assert_eq!(normalize_query_delay(None, 0, &bounds), 0);
+        assert_eq!(
+            normalize_query_delay(Some(1), 120, &bounds),
+            bounds.query_delay_bounds().0
+        );
+    }
+
+    #[test]
+    fn limits_clamp_to_the_configured_advanced_bounds() {
+        let bounds = AdvancedConfig {
+            min_query_delay_ms: 10,
+            max_query_delay_ms: 5000,
+            min_result_limit: 3,
+            max_result_limit: 200,
+        };
+        // 超出默认范围（50..=2000、10..=60）但在自定义范围内的值保持不变
+        assert_eq!(normalize_query_delay(Some(20), 120, &bounds), 20);
+        assert_eq!(normalize_query_delay(Some(4000), 120, &bounds), 4000);
+        assert_eq!(normalize_query_delay(Some(9000), 120, &bounds), 5000);
+        assert_eq!(normalize_max_results(Some(5), 20, &bounds), 5);
+        assert_eq!(normalize_max_results(Some(150), 20, &bounds), 150);
+        assert_eq!(normalize_max_results(Some(1), 20, &bounds), 3);
+        assert_eq!(normalize_max_results(None, 400, &bounds), 200);
+    }
+
+    fn working_dir_config(mode: WorkingDirMode, custom: &str) -> AppConfig {
+        AppConfig {
+            working_dir_mode: mode,
+            custom_working_dir: custom.to_string(),
+            ..AppConfig::default()
+        }
+    }
+
+    #[test]
+    fn exe_parent_mode_prefers_the_shortcut_directory() {
+        let config = working_dir_config(WorkingDirMode::ExeParent, "");
+        let mut code = app("code", "Code");
+        assert_eq!(
+            launch_working_dir(&code, &config).as_deref(),
+            Some(r"C:\Apps")
+        );
+
+        code.working_directory = Some(r"D:\Projects".to_string());
+        assert_eq!(
+            launch_working_dir(&code, &config).as_deref(),
+            Some(r"D:\Projects")
+        );
+    }
+
+    #[test]
+    fn inherit_mode_sets_no_working_directory() {
+        let config = working_dir_config(WorkingDirMode::Inherit, "");
+        assert_eq!(launch_working_dir(&app("code", "Code"), &config), None);
+    }
+
+    #[test]
+    fn custom_mode_uses_an_existing_directory() {
+        let dir = std::env::temp_dir();
+        let dir = dir.to_string_lossy();
+        let config = working_dir_config(WorkingDirMode::Custom, &dir);
+        assert_eq!(
+            launch_working_dir(&app("code", "Code"), &config).as_deref(),
+            Some(&*dir)
+        );
+    }
+
+    #[test]
+    fn invalid_custom_directory_falls_back_to_the_exe_parent() {
+        let missing = std::env::temp_dir().join("launcher-missing-working-dir");
+        let code = app("code", "Code");
+        for custom in [&*missing.to_string_lossy(), "", "   "] {
+            let config = working_dir_config(WorkingDirMode::Custom, custom);
+            assert_eq!(
+                launch_working_dir(&code, &config).as_deref(),
+                Some(r"C:\Apps"),
+                "{custom}"
+            );
+        }
+    }
+
+    #[test]
+    fn win32_apps_raw_targets_and_commands_can_be_elevated() {
+        let config = AppConfig::default();
+        let mut code = app("code", "Code");
+        code.launch_args = Some("--new-window".to_string());
+        let launch = elevated_launch(PendingAction::Application(code), &config).unwrap();
+        assert_eq!(launch.target, r"C:\Apps\code.exe");
+        assert_eq!(launch.arguments.as_deref(), Some("--new-window"));
+        assert_eq!(launch.working_dir.as_deref(), Some(r"C:\Apps"));
+
+        let command = PendingAction::Command {
+            target: "wt".to_string(),
+            arguments: "-d .".to_string(),
+        };
+        assert_eq!(elevated_launch(command, &config).unwrap().target, "wt");
+        let raw = PendingAction::RawOpen(r"C:\Tools\tool.exe".to_string());
+        assert!(elevated_launch(raw, &config).is_ok());
+    }
+
+    #[test]
+    fn bookmarks_urls_and_store_apps_are_not_elevated() {
+        let config = AppConfig::default();
+        let mut store_app = app("calc", "Calculator");
+        store_app.app_type = AppType::Uwp;
+        for action in [
+            PendingAction::Url("https://example.com".to_string()),
+            PendingAction::Search("https://example.com/?q=x".to_string()),
+            PendingAction::Application(store_app),
+        ] {
+            assert!(matches!(
+                elevated_launch(action, &config),
+                Err(AppError::NotApplicable(_))
+            ));
         }
     }
 
-    best
+    #[test]
+    fn bare_program_names_are_found_in_path_directories() {
+        let dir = std::env::temp_dir().join("launcher-elevation-path-test");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("tool.exe"), b"").unwrap();
+        let dirs = [PathBuf::from("missing-dir"), dir.clone()];
+
+        assert_eq!(
+            find_in_dirs("tool", &dirs, &[".com", ".exe"]),
+            Some(dir.join("tool.exe"))
+        );
+        assert_eq!(
+            find_in_dirs("tool.exe", &dirs, &[]),
+            Some(dir.join("tool.exe"))
+        );
+        assert_eq!(find_in_dirs("other", &dirs, &[".EXE"]), None);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn colliding_prefix_is_rejected() {
+        let updates = SettingsUpdatePayload {
+            prefix_search: Some("b".to_string()),
+            ..SettingsUpdatePayload::default()
+        };
+        assert!(settings_candidate(&AppConfig::default(), updates).is_err());
+    }
+
+    #[test]
+    fn prefixes_can_be_swapped_in_one_update() {
+        let updates = SettingsUpdatePayload {
+            prefix_app: Some("b".to_string()),
+            prefix_bookmark: Some("r".to_string()),
+            ..SettingsUpdatePayload::default()
+        };
+        let config = settings_candidate(&AppConfig::default(), updates).unwrap();
+        assert_eq!(config.prefix_app, "B");
+        assert_eq!(config.prefix_bookmark, "R");
+    }
+
+    #[test]
+    fn valid_hotkey_does_not_carry_an_invalid_later_field() {
+        let updates = SettingsUpdatePayload {
+            global_hotkey: Some("Ctrl+Shift+P".to_string()),
+            icon_size: Some(40),
+            ..SettingsUpdatePayload::default()
+        };
+        assert!(settings_candidate(&AppConfig::default(), updates).is_err());
+    }
 }
@@ -1,53 +1,576 @@
-use std::{fs, path::PathBuf};
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+};
 
 use serde::{Deserialize, Serialize};
 use tauri::{AppHandle, Manager};
 
+use crate::{
+    custom_commands::CustomCommand,
+    error::{AppError, AppResult},
+    i18n::Locale,
+    search::QueryMode,
+    windows_utils::SYSTEM_ICON_SIZE,
+};
+
 const CONFIG_FILE: &str = "settings.json";
+/// Holds the name of the active profile; missing means [`DEFAULT_PROFILE`].
+const PROFILE_POINTER_FILE: &str = "active_profile";
+/// The profile stored in the plain `settings.json`, so configs from before profiles keep working.
+pub const DEFAULT_PROFILE: &str = "default";
+const MAX_PROFILE_NAME_LEN: usize = 32;
+/// Replaced with the URL-encoded query in `search_engine_template`.
+pub const SEARCH_QUERY_PLACEHOLDER: &str = "{query}";
+pub const MIN_QUERY_DELAY_MS: u64 = 50;
+pub const MAX_QUERY_DELAY_MS: u64 = 2000;
+pub const MIN_RESULT_LIMIT: u32 = 10;
+pub const MAX_RESULT_LIMIT: u32 = 60;
+pub const MIN_WINDOW_OPACITY: f32 = 0.6;
+pub const MAX_WINDOW_OPACITY: f32 = 1.0;
+/// Icon edge lengths that can be picked for `icon_size`.
+pub const ICON_SIZES: [u32; 4] = [SYSTEM_ICON_SIZE, 48, 64, 256];
+/// Hard ceilings for the user-configurable bounds in [`AdvancedConfig`].
+const HARD_MAX_QUERY_DELAY_MS: u64 = 10_000;
+const HARD_MAX_RESULT_LIMIT: u32 = 500;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AppConfig {
     pub global_hotkey: String,
+    /// Extra hotkeys that open the launcher straight in a mode; `global_hotkey` keeps toggling
+    /// it in the default mode.
+    #[serde(default)]
+    pub mode_hotkeys: Vec<ModeHotkey>,
+    /// Modifier (`Ctrl`, `Shift`, `Alt` or `Super`) that toggles the launcher when tapped twice
+    /// in quick succession, next to `global_hotkey`; `None` turns it off.
+    #[serde(default)]
+    pub double_tap_modifier: Option<String>,
+    /// Debounce applied by `submit_query` before ranking, clamped to the advanced bounds; 0 ranks
+    /// every keystroke.
     #[serde(default = "default_query_delay")]
     // ensure backward compatibility when loading old config files
     pub query_delay_ms: u64,
     #[serde(default = "default_max_results")]
     pub max_results: u32,
+    /// Per-provider caps applied before the global `max_results`; 0 means unlimited.
+    #[serde(default = "default_max_app_results")]
+    pub max_app_results: u32,
+    #[serde(default = "default_max_bookmark_results")]
+    pub max_bookmark_results: u32,
     #[serde(default = "default_enable_app_results")]
     pub enable_app_results: bool,
+    /// Loads app icons while indexing. Turning it off leaves icons empty but makes reindexing
+    /// much faster on slow machines.
+    #[serde(default = "default_extract_icons")]
+    pub extract_icons: bool,
+    /// Edge length in pixels of extracted app icons, one of [`ICON_SIZES`]. Sizes above the
+    /// system 32 px keep icons sharp on high-DPI displays.
+    #[serde(default = "default_icon_size")]
+    pub icon_size: u32,
+    /// Executable file names skipped by every scan that picks executables, e.g. `unins*`.
+    /// See [`crate::indexer::ExeFilter`] for the pattern syntax.
+    #[serde(default = "default_exclude_exe_patterns")]
+    pub exclude_exe_patterns: Vec<String>,
+    /// Index installed Steam games as apps that launch through the Steam client.
+    #[serde(default)]
+    pub enable_steam_games: bool,
     #[serde(default = "default_enable_bookmark_results")]
     pub enable_bookmark_results: bool,
+    /// Browser history is privacy-sensitive, so this provider is opt-in.
+    #[serde(default = "default_enable_history_results")]
+    pub enable_history_results: bool,
+    /// Searches file and folder names under `file_search_roots` in file mode. Opt-in because
+    /// the scan touches the disk on every query.
+    #[serde(default)]
+    pub enable_file_results: bool,
+    /// Folders scanned in file mode; empty means the user's Desktop, Documents and Downloads.
+    #[serde(default)]
+    pub file_search_roots: Vec<String>,
+    /// Records copied text for clipboard mode. Opt-in since the clipboard may hold secrets.
+    #[serde(default)]
+    pub enable_clipboard_history: bool,
+    /// Entries kept in the clipboard history, oldest dropped first.
+    #[serde(default = "default_clipboard_history_size")]
+    pub clipboard_history_size: u32,
+    /// How many of the most visited URLs are kept in the history index.
+    #[serde(default = "default_max_history_entries")]
+    pub max_history_entries: u32,
+    #[serde(default = "default_max_history_results")]
+    pub max_history_results: u32,
+    /// Most results returned per query, web search included, for screens too small for
+    /// `max_results`; 0 means no extra cap.
+    #[serde(default)]
+    pub max_visible_results: u32,
     #[serde(default = "default_prefix_app")]
     pub prefix_app: String,
     #[serde(default = "default_prefix_bookmark")]
     pub prefix_bookmark: String,
     #[serde(default = "default_prefix_search")]
     pub prefix_search: String,
+    #[serde(default = "default_prefix_file")]
+    pub prefix_file: String,
+    #[serde(default = "default_prefix_clipboard")]
+    pub prefix_clipboard: String,
+    #[serde(default = "default_prefix_run")]
+    pub prefix_run: String,
+    /// Run history saved by older versions; read once to seed `run_history.json`, never written.
+    #[serde(rename = "run_history", default, skip_serializing)]
+    pub legacy_run_history: Vec<String>,
     #[serde(default = "default_launch_on_startup")]
     pub launch_on_startup: bool,
     #[serde(default = "default_force_english_input")]
     pub force_english_input: bool,
+    /// Only force English on the first show after a hotkey press, not when the window is
+    /// shown again from the tray or a second instance.
+    #[serde(default = "default_force_english_first_show_only")]
+    pub force_english_first_show_only: bool,
     #[serde(default = "default_debug_mode")]
     pub debug_mode: bool,
     #[serde(default = "default_window_opacity")]
     pub window_opacity: f32,
+    #[serde(default = "default_fallback_on_empty_results")]
+    pub fallback_on_empty_results: bool,
+    #[serde(default)]
+    pub empty_enter_action: EmptyEnterAction,
+    /// URL of the web search; must contain [`SEARCH_QUERY_PLACEHOLDER`].
+    #[serde(default = "default_search_engine_template")]
+    pub search_engine_template: String,
+    /// Shown in the web search result, e.g. "Search DuckDuckGo for: ...".
+    #[serde(default = "default_search_engine_name")]
+    pub search_engine_name: String,
+    /// Search URL templates by bang, without the `!` and lowercase: `!gh rust` searches
+    /// `rust` with the `gh` template. Each template must contain [`SEARCH_QUERY_PLACEHOLDER`].
+    #[serde(default = "default_search_bangs")]
+    pub search_bangs: HashMap<String, String>,
+    /// Answers keywords like `time` or `date` locally with a copyable result.
+    #[serde(default = "default_enable_instant_answers")]
+    pub enable_instant_answers: bool,
+    /// Whether hiding the launcher clears the query. When disabled the whole input,
+    /// including any mode prefix, is kept, so the active mode is restored on reopen as well.
+    #[serde(default = "default_clear_query_on_hide")]
+    pub clear_query_on_hide: bool,
+    #[serde(default)]
+    pub escape_behavior: EscapeBehavior,
+    #[serde(default)]
+    pub locale: Locale,
+    #[serde(default)]
+    pub working_dir_mode: WorkingDirMode,
+    /// Directory used when `working_dir_mode` is `custom`.
+    #[serde(default)]
+    pub custom_working_dir: String,
+    /// Repeating `execute_action` for the same result within this many milliseconds is
+    /// ignored, so a bounced Enter doesn't launch twice; 0 disables it.
+    #[serde(default = "default_launch_cooldown_ms")]
+    pub launch_cooldown_ms: u64,
+    /// Opening a bookmark folder with more links than this asks for confirmation first.
+    #[serde(default = "default_bookmark_folder_confirm_threshold")]
+    pub bookmark_folder_confirm_threshold: u32,
+    #[serde(default)]
+    pub enter_on_running_app: EnterOnRunningApp,
+    /// How arguments typed after an app name combine with its saved `launch_args`.
+    #[serde(default)]
+    pub typed_launch_args: TypedLaunchArgs,
+    /// Whether arrow navigation past either end of the result list wraps around.
+    #[serde(default = "default_nav_wrap")]
+    pub nav_wrap: bool,
+    /// Rows moved by PageUp/PageDown in the result list.
+    #[serde(default = "default_page_jump_size")]
+    pub page_jump_size: u32,
+    #[serde(default = "default_watch_bookmarks")]
+    pub watch_bookmarks: bool,
+    /// Minutes between background rebuilds of the app, bookmark and history indexes; 0 turns
+    /// the periodic reindex off.
+    #[serde(default)]
+    pub reindex_interval_minutes: u64,
+    /// Browsers to load bookmarks from, most preferred first; empty loads all supported ones.
+    /// When a bookmark exists in several browsers the most preferred copy is kept.
+    #[serde(default)]
+    pub bookmark_browsers: Vec<String>,
+    /// Also index Microsoft Edge bookmarks; off by default like Firefox.
+    #[serde(default)]
+    pub enable_edge_bookmarks: bool,
+    /// Also index Firefox bookmarks from `places.sqlite`; off so Chrome-only setups don't scan
+    /// for Firefox profiles.
+    #[serde(default)]
+    pub enable_firefox_bookmarks: bool,
+    /// Chrome or Firefox profiles (folder or display name) to load bookmarks from; empty loads
+    /// all.
+    #[serde(default)]
+    pub bookmark_profiles: Vec<String>,
+    /// Keep a URL bookmarked in several profiles only once.
+    #[serde(default = "default_dedupe_bookmarks_across_profiles")]
+    pub dedupe_bookmarks_across_profiles: bool,
+    /// Periodically re-fetch missing or stale bookmark favicons in the background. Off by
+    /// default because every bookmarked host name is sent to Google's favicon service
+    /// (`www.google.com/s2/favicons`), which reveals the sites you have bookmarked.
+    #[serde(default = "default_favicon_refresh_enabled")]
+    pub favicon_refresh_enabled: bool,
+    /// Cached favicons older than this are re-fetched while the refresher is enabled.
+    #[serde(default = "default_favicon_max_age_hours")]
+    pub favicon_max_age_hours: u64,
+    /// Results scoring below this fraction of the top score are dropped; 0 disables the cutoff.
+    #[serde(default = "default_relative_score_cutoff")]
+    pub relative_score_cutoff: f32,
+    /// Score bonus for app and bookmark results whose name or a keyword starts with the
+    /// query, so `ch` prefers `Chrome` over a scattered match; 0 disables it.
+    #[serde(default = "default_prefix_boost")]
+    pub prefix_boost: i64,
+    /// Subtracted from app matches on a keyword (pinyin, file name) rather than the name.
+    #[serde(default = "default_keyword_penalty")]
+    pub keyword_penalty: i64,
+    /// Subtracted from bookmark matches on the folder path.
+    #[serde(default = "default_path_penalty")]
+    pub path_penalty: i64,
+    /// Subtracted from bookmark and history matches on the URL or derived keywords.
+    #[serde(default = "default_url_penalty")]
+    pub url_penalty: i64,
+    /// App, bookmark and history matches scoring below this before bonuses are dropped;
+    /// 0 keeps every fuzzy match.
+    #[serde(default)]
+    pub min_score_threshold: i64,
+    /// Weight of the launch-frequency bonus for apps and bookmarks; 0 ranks by match alone.
+    #[serde(default = "default_usage_boost")]
+    pub usage_boost: f32,
+    #[serde(default)]
+    pub match_algorithm: MatchAlgorithm,
+    /// Skim matching ignores case entirely; off, it is case-sensitive only for queries that
+    /// contain an uppercase letter.
+    #[serde(default = "default_ignore_case")]
+    pub ignore_case: bool,
+    /// Fold diacritics on the query and candidates before matching, so `cafe` finds `Café`.
+    #[serde(default)]
+    pub ignore_accents: bool,
+    /// Treat a query wrapped in slashes (`/pattern/`) as a regular expression matched against
+    /// names and keywords instead of using `match_algorithm`.
+    #[serde(default)]
+    pub regex_queries: bool,
+    /// Drop app results whose target is on a drive that isn't mounted, instead of only
+    /// marking them.
+    #[serde(default)]
+    pub exclude_unavailable_drives: bool,
+    /// Among bookmark results with the same score, rank the more recently added one first.
+    #[serde(default = "default_bookmark_recency_tiebreak")]
+    pub bookmark_recency_tiebreak: bool,
+    /// Publish the app index before Win32 icons are extracted and fill them in from a
+    /// throttled background task afterwards, so a reindex shows results sooner.
+    #[serde(default = "default_prewarm_icons")]
+    pub prewarm_icons: bool,
+    /// After a reindex, tell the launcher which apps became searchable via
+    /// `new_apps_detected`. The first build after startup is compared with the cached index of
+    /// the previous run, and isn't reported when there is no cache.
+    #[serde(default)]
+    pub notify_new_apps: bool,
+    #[serde(default)]
+    pub advanced: AdvancedConfig,
+    #[serde(default)]
+    pub content_search: ContentSearchConfig,
+    #[serde(default)]
+    pub custom_commands: Vec<CustomCommand>,
+    #[serde(default)]
+    pub force_english_by_mode: ForceEnglishByMode,
+    /// Display names replacing the indexed name of an app, keyed by app id. Managed through
+    /// `set_display_name`/`clear_display_name` rather than `update_settings`.
+    #[serde(default)]
+    pub display_names: HashMap<String, String>,
+    /// Arguments passed on every launch of a Win32 app, keyed by app id and stored quoted.
+    /// Managed through `set_launch_args` rather than `update_settings`.
+    #[serde(default)]
+    pub launch_args: HashMap<String, String>,
+}
+
+/// A global hotkey that shows the launcher in `mode`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModeHotkey {
+    pub hotkey: String,
+    /// A mode name understood by [`QueryMode::from_option`], e.g. `bookmark`.
+    pub mode: String,
+}
+
+/// Per-mode overrides for `force_english_input`; `None` falls back to the global flag.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ForceEnglishByMode {
+    #[serde(default)]
+    pub all: Option<bool>,
+    #[serde(default)]
+    pub app: Option<bool>,
+    #[serde(default)]
+    pub bookmark: Option<bool>,
+    #[serde(default)]
+    pub search: Option<bool>,
+    #[serde(default)]
+    pub file: Option<bool>,
+    #[serde(default)]
+    pub clipboard: Option<bool>,
+    #[serde(default)]
+    pub run: Option<bool>,
+}
+
+/// How app, bookmark and history results are matched against the query.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MatchAlgorithm {
+    /// Skim fuzzy matching: the query characters only need to appear in order.
+    #[default]
+    Skim,
+    /// The query must appear as one contiguous, case-insensitive substring; earlier matches
+    /// score higher. Stricter and cheaper on large indexes.
+    Substring,
+}
+
+/// What pressing Escape in the launcher does.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EscapeBehavior {
+    /// Escape always hides the window.
+    #[default]
+    AlwaysHide,
+    /// The first Escape clears a non-empty query, the next one hides.
+    ClearThenHide,
+}
+
+/// What Enter does when the query produced no results.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EmptyEnterAction {
+    /// Open URL-like input, otherwise search the web.
+    #[default]
+    UrlOrSearch,
+    /// Always search the web for the raw input.
+    WebSearch,
+    /// Do nothing.
+    Nothing,
+}
+
+/// Working directory given to Win32 apps when they are launched.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WorkingDirMode {
+    /// The shortcut's "Start in" directory, or the executable's parent directory.
+    #[default]
+    ExeParent,
+    /// Don't set one; the app inherits the launcher's working directory.
+    Inherit,
+    /// Always use `custom_working_dir`.
+    Custom,
+}
+
+/// What Enter does on a Win32 app that already has a running process.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EnterOnRunningApp {
+    /// Start another instance.
+    #[default]
+    LaunchNew,
+    /// Bring the existing window to the front, launching only when it has no window.
+    FocusExisting,
+    /// Let the user choose each time.
+    Ask,
+}
+
+/// How arguments typed after an app name combine with the app's saved launch arguments.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TypedLaunchArgs {
+    /// Pass the saved arguments first, then the typed ones.
+    #[default]
+    Append,
+    /// Pass only the typed arguments.
+    Replace,
+}
+
+/// Power-user overrides for the bounds that `query_delay_ms` and `max_results` are clamped to.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AdvancedConfig {
+    #[serde(default = "default_min_query_delay")]
+    pub min_query_delay_ms: u64,
+    #[serde(default = "default_max_query_delay")]
+    pub max_query_delay_ms: u64,
+    #[serde(default = "default_min_result_limit")]
+    pub min_result_limit: u32,
+    #[serde(default = "default_max_result_limit")]
+    pub max_result_limit: u32,
+}
+
+/// File content search backed by an external `rg` (ripgrep) binary.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContentSearchConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Text the query must start with to search file contents, e.g. `f ` for `f todo`.
+    #[serde(default = "default_content_search_prefix")]
+    pub prefix: String,
+    #[serde(default = "default_rg_path")]
+    pub rg_path: String,
+    #[serde(default)]
+    pub roots: Vec<String>,
+    /// ripgrep `--glob` filters, e.g. `*.md` or `!node_modules`.
+    #[serde(default)]
+    pub globs: Vec<String>,
+    #[serde(default = "default_content_search_timeout")]
+    pub timeout_ms: u64,
+    #[serde(default = "default_content_search_max_results")]
+    pub max_results: u32,
+}
+
+impl Default for ContentSearchConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            prefix: default_content_search_prefix(),
+            rg_path: default_rg_path(),
+            roots: Vec::new(),
+            globs: Vec::new(),
+            timeout_ms: default_content_search_timeout(),
+            max_results: default_content_search_max_results(),
+        }
+    }
+}
+
+impl ContentSearchConfig {
+    /// Returns the search pattern when `query` carries the content-search prefix.
+    pub fn strip_prefix<'a>(&self, query: &'a str) -> Option<&'a str> {
+        let prefix = self.prefix.trim();
+        if prefix.is_empty() {
+            return None;
+        }
+        let query = query.trim_start();
+        let head = query.get(..prefix.len())?;
+        if !head.eq_ignore_ascii_case(prefix) {
+            return None;
+        }
+        let rest = &query[prefix.len()..];
+        // 前缀后必须跟空白，避免 "foo" 被当作 "f" + "oo"
+        if !rest.starts_with(char::is_whitespace) {
+            return None;
+        }
+        let pattern = rest.trim();
+        (!pattern.is_empty()).then_some(pattern)
+    }
+}
+
+impl Default for AdvancedConfig {
+    fn default() -> Self {
+        Self {
+            min_query_delay_ms: default_min_query_delay(),
+            max_query_delay_ms: default_max_query_delay(),
+            min_result_limit: default_min_result_limit(),
+            max_result_limit: default_max_result_limit(),
+        }
+    }
+}
+
+impl AdvancedConfig {
+    /// Returns `(min, max)` for the query delay, kept within the hard ceiling and ordered.
+    pub fn query_delay_bounds(&self) -> (u64, u64) {
+        let min = self.min_query_delay_ms.min(HARD_MAX_QUERY_DELAY_MS);
+        let max = self.max_query_delay_ms.clamp(min, HARD_MAX_QUERY_DELAY_MS);
+        (min, max)
+    }
+
+    /// Returns `(min, max)` for the result limit, kept within `1..=HARD_MAX_RESULT_LIMIT` and ordered.
+    pub fn result_limit_bounds(&self) -> (u32, u32) {
+        let min = self.min_result_limit.clamp(1, HARD_MAX_RESULT_LIMIT);
+        let max = self.max_result_limit.clamp(min, HARD_MAX_RESULT_LIMIT);
+        (min, max)
+    }
+
+    /// Rewrites the bounds in their sanitized form.
+    pub fn sanitized(&self) -> Self {
+        let (min_query_delay_ms, max_query_delay_ms) = self.query_delay_bounds();
+        let (min_result_limit, max_result_limit) = self.result_limit_bounds();
+        Self {
+            min_query_delay_ms,
+            max_query_delay_ms,
+            min_result_limit,
+            max_result_limit,
+        }
+    }
 }
 
 impl Default for AppConfig {
     fn default() -> Self {
         Self {
             global_hotkey: "Alt+Space".to_string(),
+            mode_hotkeys: Vec::new(),
+            double_tap_modifier: None,
             query_delay_ms: default_query_delay(),
             max_results: default_max_results(),
+            max_app_results: default_max_app_results(),
+            max_bookmark_results: default_max_bookmark_results(),
             enable_app_results: default_enable_app_results(),
+            extract_icons: default_extract_icons(),
+            icon_size: default_icon_size(),
+            exclude_exe_patterns: default_exclude_exe_patterns(),
+            enable_steam_games: false,
             enable_bookmark_results: default_enable_bookmark_results(),
+            enable_history_results: default_enable_history_results(),
+            enable_file_results: false,
+            file_search_roots: Vec::new(),
+            enable_clipboard_history: false,
+            clipboard_history_size: default_clipboard_history_size(),
+            max_history_entries: default_max_history_entries(),
+            max_history_results: default_max_history_results(),
+            max_visible_results: 0,
             prefix_app: default_prefix_app(),
             prefix_bookmark: default_prefix_bookmark(),
             prefix_search: default_prefix_search(),
+            prefix_file: default_prefix_file(),
+            prefix_clipboard: default_prefix_clipboard(),
+            prefix_run: default_prefix_run(),
+            legacy_run_history: Vec::new(),
             launch_on_startup: default_launch_on_startup(),
             force_english_input: default_force_english_input(),
+            force_english_first_show_only: default_force_english_first_show_only(),
             debug_mode: default_debug_mode(),
             window_opacity: default_window_opacity(),
+            fallback_on_empty_results: default_fallback_on_empty_results(),
+            empty_enter_action: EmptyEnterAction::default(),
+            search_engine_template: default_search_engine_template(),
+            search_engine_name: default_search_engine_name(),
+            search_bangs: default_search_bangs(),
+            enable_instant_answers: default_enable_instant_answers(),
+            clear_query_on_hide: default_clear_query_on_hide(),
+            escape_behavior: EscapeBehavior::default(),
+            locale: Locale::default(),
+            working_dir_mode: WorkingDirMode::default(),
+            custom_working_dir: String::new(),
+            launch_cooldown_ms: default_launch_cooldown_ms(),
+            bookmark_folder_confirm_threshold: default_bookmark_folder_confirm_threshold(),
+            enter_on_running_app: EnterOnRunningApp::default(),
+            typed_launch_args: TypedLaunchArgs::default(),
+            nav_wrap: default_nav_wrap(),
+            page_jump_size: default_page_jump_size(),
+            watch_bookmarks: default_watch_bookmarks(),
+            reindex_interval_minutes: 0,
+            bookmark_browsers: Vec::new(),
+            enable_edge_bookmarks: false,
+            enable_firefox_bookmarks: false,
+            bookmark_profiles: Vec::new(),
+            dedupe_bookmarks_across_profiles: default_dedupe_bookmarks_across_profiles(),
+            favicon_refresh_enabled: default_favicon_refresh_enabled(),
+            favicon_max_age_hours: default_favicon_max_age_hours(),
+            relative_score_cutoff: default_relative_score_cutoff(),
+            prefix_boost: default_prefix_boost(),
+            keyword_penalty: default_keyword_penalty(),
+            path_penalty: default_path_penalty(),
+            url_penalty: default_url_penalty(),
+            min_score_threshold: 0,
+            usage_boost: default_usage_boost(),
+            match_algorithm: MatchAlgorithm::default(),
+            ignore_case: default_ignore_case(),
+            ignore_accents: false,
+            regex_queries: false,
+            exclude_unavailable_drives: false,
+            bookmark_recency_tiebreak: default_bookmark_recency_tiebreak(),
+            prewarm_icons: default_prewarm_icons(),
+            notify_new_apps: false,
+            advanced: AdvancedConfig::default(),
+            content_search: ContentSearchConfig::default(),
+            custom_commands: Vec::new(),
+            force_english_by_mode: ForceEnglishByMode::default(),
+            display_names: HashMap::new(),
+            launch_args: HashMap::new(),
         }
     }
 }
@@ -60,14 +583,42 @@ const fn default_max_results() -> u32 {
     40
 }
 
+const fn default_max_app_results() -> u32 {
+    30
+}
+
+const fn default_max_bookmark_results() -> u32 {
+    20
+}
+
 const fn default_enable_app_results() -> bool {
     true
 }
 
+const fn default_extract_icons() -> bool {
+    true
+}
+
+const fn default_icon_size() -> u32 {
+    SYSTEM_ICON_SIZE
+}
+
 const fn default_enable_bookmark_results() -> bool {
     true
 }
 
+const fn default_enable_history_results() -> bool {
+    false
+}
+
+const fn default_max_history_entries() -> u32 {
+    500
+}
+
+const fn default_max_history_results() -> u32 {
+    10
+}
+
 fn default_prefix_app() -> String {
     "R".to_string()
 }
@@ -80,6 +631,22 @@ fn default_prefix_search() -> String {
     "S".to_string()
 }
 
+fn default_prefix_file() -> String {
+    "F".to_string()
+}
+
+fn default_prefix_clipboard() -> String {
+    "V".to_string()
+}
+
+fn default_prefix_run() -> String {
+    "C".to_string()
+}
+
+const fn default_clipboard_history_size() -> u32 {
+    50
+}
+
 const fn default_launch_on_startup() -> bool {
     false
 }
@@ -88,6 +655,10 @@ const fn default_force_english_input() -> bool {
     true
 }
 
+const fn default_force_english_first_show_only() -> bool {
+    true
+}
+
 const fn default_debug_mode() -> bool {
     false
 }
@@ -96,38 +667,518 @@ const fn default_window_opacity() -> f32 {
     0.95
 }
 
+const fn default_fallback_on_empty_results() -> bool {
+    true
+}
+
+const fn default_enable_instant_answers() -> bool {
+    true
+}
+
+const fn default_clear_query_on_hide() -> bool {
+    true
+}
+
+const fn default_nav_wrap() -> bool {
+    false
+}
+
+const fn default_page_jump_size() -> u32 {
+    5
+}
+
+const fn default_watch_bookmarks() -> bool {
+    true
+}
+
+const fn default_dedupe_bookmarks_across_profiles() -> bool {
+    false
+}
+
+const fn default_favicon_refresh_enabled() -> bool {
+    false
+}
+
+const fn default_favicon_max_age_hours() -> u64 {
+    24 * 7
+}
+
+const fn default_relative_score_cutoff() -> f32 {
+    0.0
+}
+
+const fn default_prefix_boost() -> i64 {
+    50
+}
+
+const fn default_ignore_case() -> bool {
+    true
+}
+
+const fn default_keyword_penalty() -> i64 {
+    5
+}
+
+const fn default_path_penalty() -> i64 {
+    5
+}
+
+const fn default_url_penalty() -> i64 {
+    8
+}
+
+const fn default_usage_boost() -> f32 {
+    10.0
+}
+
+const fn default_bookmark_recency_tiebreak() -> bool {
+    true
+}
+
+const fn default_prewarm_icons() -> bool {
+    false
+}
+
+const fn default_launch_cooldown_ms() -> u64 {
+    500
+}
+
+const fn default_bookmark_folder_confirm_threshold() -> u32 {
+    10
+}
+
+fn default_search_engine_template() -> String {
+    "https://google.com/search?q={query}".to_string()
+}
+
+fn default_search_engine_name() -> String {
+    "Google".to_string()
+}
+
+fn default_search_bangs() -> HashMap<String, String> {
+    [
+        ("g", "https://google.com/search?q={query}"),
+        ("gh", "https://github.com/search?q={query}"),
+        ("so", "https://stackoverflow.com/search?q={query}"),
+    ]
+    .into_iter()
+    .map(|(bang, template)| (bang.to_string(), template.to_string()))
+    .collect()
+}
+
+fn default_exclude_exe_patterns() -> Vec<String> {
+    ["unins*", "*setup*", "*crashpad*", "*helper*"]
+        .into_iter()
+        .map(String::from)
+        .collect()
+}
+
+fn default_content_search_prefix() -> String {
+    "f".to_string()
+}
+
+fn default_rg_path() -> String {
+    "rg".to_string()
+}
+
+const fn default_content_search_timeout() -> u64 {
+    3000
+}
+
+const fn default_content_search_max_results() -> u32 {
+    30
+}
+
+const fn default_min_query_delay() -> u64 {
+    MIN_QUERY_DELAY_MS
+}
+
+const fn default_max_query_delay() -> u64 {
+    MAX_QUERY_DELAY_MS
+}
+
+const fn default_min_result_limit() -> u32 {
+    MIN_RESULT_LIMIT
+}
+
+const fn default_max_result_limit() -> u32 {
+    MAX_RESULT_LIMIT
+}
+
 impl AppConfig {
+    /// Whether the English input method should be forced while `mode` is active.
+    pub fn should_force_english(&self, mode: QueryMode) -> bool {
+        let overrides = &self.force_english_by_mode;
+        let specific = match mode {
+            QueryMode::All => overrides.all,
+            QueryMode::Application => overrides.app,
+            QueryMode::Bookmark => overrides.bookmark,
+            QueryMode::Search => overrides.search,
+            QueryMode::File => overrides.file,
+            QueryMode::Clipboard => overrides.clipboard,
+            QueryMode::Run => overrides.run,
+        };
+        specific.unwrap_or(self.force_english_input)
+    }
+
     pub fn load(handle: &AppHandle) -> Self {
         let Some(path) = config_path(handle) else {
             return Self::default();
         };
+        Self::load_from(&path)
+    }
 
+    /// Reads the settings file at `path`, falling back to defaults when it is missing or invalid.
+    pub fn load_from(path: &Path) -> Self {
         if let Some(parent) = path.parent() {
             let _ = fs::create_dir_all(parent);
         }
 
-        match fs::read_to_string(&path) {
+        match fs::read_to_string(path) {
             Ok(content) => serde_json::from_str(&content).unwrap_or_default(),
             Err(_) => Self::default(),
         }
     }
 
-    pub fn save(&self, handle: &AppHandle) -> Result<(), String> {
+    pub fn save(&self, handle: &AppHandle) -> AppResult<()> {
         let Some(path) = config_path(handle) else {
-            return Err("无法确定配置目录".into());
+            return Err(AppError::ConfigIo("无法确定配置目录".into()));
         };
         if let Some(parent) = path.parent() {
-            fs::create_dir_all(parent).map_err(|err| err.to_string())?;
+            fs::create_dir_all(parent).map_err(|err| AppError::ConfigIo(err.to_string()))?;
+        }
+        let data = serde_json::to_string_pretty(self)
+            .map_err(|err| AppError::Internal(err.to_string()))?;
+        fs::write(path, data).map_err(|err| AppError::ConfigIo(err.to_string()))
+    }
+}
+
+/// Normalizes a mode prefix to a single uppercase letter, optionally followed by a space or colon.
+pub(crate) fn normalize_prefix(value: &str) -> Option<String> {
+    let trimmed_start = value.trim_start();
+    if trimmed_start.is_empty() {
+        return None;
+    }
+
+    let mut chars = trimmed_start.chars();
+    let Some(first) = chars.next() else {
+        return None;
+    };
+
+    if !first.is_ascii_alphabetic() {
+        return None;
+    }
+
+    let mut normalized = String::new();
+    normalized.push(first.to_ascii_uppercase());
+
+    let remainder: String = chars.collect();
+    let mut remainder_chars = remainder.chars().filter(|c| !c.is_control());
+    if let Some(next) = remainder_chars.next() {
+        match next {
+            ' ' | ':' => normalized.push(next),
+            _ => return None,
+        }
+
+        if remainder_chars.any(|c| !c.is_whitespace()) {
+            return None;
         }
-        let data = serde_json::to_string_pretty(self).map_err(|err| err.to_string())?;
-        fs::write(path, data).map_err(|err| err.to_string())
+    }
+
+    Some(normalized)
+}
+
+/// Checks that the settings file can actually be written, by resolving the config directory
+/// and writing a probe file into it. An error means changes will only live until exit.
+pub(crate) fn check_config_persistence(handle: &AppHandle) -> AppResult<PathBuf> {
+    check_persistence_in(config_dir(handle))
+}
+
+/// [`check_config_persistence`] for the config directory `dir`; `None` means it couldn't be
+/// resolved.
+fn check_persistence_in(dir: Option<PathBuf>) -> AppResult<PathBuf> {
+    let dir = dir.ok_or_else(|| AppError::ConfigIo("无法确定配置目录".into()))?;
+    let path = active_profile_path(&dir);
+    fs::create_dir_all(&dir)
+        .map_err(|err| AppError::ConfigIo(format!("无法创建配置目录 {}: {err}", dir.display())))?;
+
+    let probe = dir.join(".write-test");
+    fs::write(&probe, b"")
+        .map_err(|err| AppError::ConfigIo(format!("配置目录 {} 不可写: {err}", dir.display())))?;
+    let _ = fs::remove_file(&probe);
+    Ok(path)
+}
+
+/// Settings file of the active profile.
+pub(crate) fn config_path(handle: &AppHandle) -> Option<PathBuf> {
+    Some(active_profile_path(&config_dir(handle)?))
+}
+
+fn active_profile_path(dir: &Path) -> PathBuf {
+    profile_path(dir, &read_active_profile(dir))
+}
+
+/// Directory holding `settings.json`, the other profiles' settings files and the active
+/// profile pointer.
+pub(crate) fn config_dir(handle: &AppHandle) -> Option<PathBuf> {
+    handle.path().app_config_dir().ok()
+}
+
+/// File naming the active profile.
+pub(crate) fn profile_pointer_path(handle: &AppHandle) -> Option<PathBuf> {
+    Some(config_dir(handle)?.join(PROFILE_POINTER_FILE))
+}
+
+fn require_config_dir(handle: &AppHandle) -> AppResult<PathBuf> {
+    config_dir(handle).ok_or_else(|| AppError::ConfigIo("无法确定配置目录".into()))
+}
+
+/// The named config profiles and which one is in use.
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfigProfiles {
+    pub active: String,
+    /// Always includes [`DEFAULT_PROFILE`], sorted with it first.
+    pub profiles: Vec<String>,
+}
+
+/// Profile names are used in file names, so only ASCII letters, digits, `-` and `_` are allowed.
+pub fn is_valid_profile_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.len() <= MAX_PROFILE_NAME_LEN
+        && name
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || ch == '-' || ch == '_')
+}
+
+fn profile_path(dir: &Path, profile: &str) -> PathBuf {
+    if profile == DEFAULT_PROFILE {
+        dir.join(CONFIG_FILE)
+    } else {
+        dir.join(format!("settings.{profile}.json"))
     }
 }
 
-fn config_path(handle: &AppHandle) -> Option<PathBuf> {
-    handle
-        .path()
-        .app_config_dir()
+fn read_active_profile(dir: &Path) -> String {
+    fs::read_to_string(dir.join(PROFILE_POINTER_FILE))
         .ok()
-        .map(|dir| dir.join(CONFIG_FILE))
+        .map(|content| content.trim().to_string())
+        .filter(|name| is_valid_profile_name(name))
+        .unwrap_or_else(|| DEFAULT_PROFILE.to_string())
+}
+
+pub(crate) fn list_profiles(handle: &AppHandle) -> AppResult<ConfigProfiles> {
+    Ok(profiles_in(&require_config_dir(handle)?))
+}
+
+fn profiles_in(dir: &Path) -> ConfigProfiles {
+    let mut profiles: Vec<String> = fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter_map(|entry| {
+                    let file_name = entry.file_name().into_string().ok()?;
+                    let name = file_name.strip_prefix("settings.")?.strip_suffix(".json")?;
+                    (is_valid_profile_name(name) && name != DEFAULT_PROFILE)
+                        .then(|| name.to_string())
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    profiles.sort();
+    profiles.insert(0, DEFAULT_PROFILE.to_string());
+    ConfigProfiles {
+        active: read_active_profile(dir),
+        profiles,
+    }
+}
+
+/// Writes `config` as the settings of a new profile. Fails if the profile already exists.
+pub(crate) fn create_profile(handle: &AppHandle, name: &str, config: &AppConfig) -> AppResult<()> {
+    create_profile_in(&require_config_dir(handle)?, name, config)
+}
+
+fn create_profile_in(dir: &Path, name: &str, config: &AppConfig) -> AppResult<()> {
+    let path = profile_path(dir, name);
+    if path.exists() {
+        return Err(AppError::InvalidSetting(format!("配置方案 {name} 已存在")));
+    }
+    fs::create_dir_all(dir).map_err(|err| AppError::ConfigIo(err.to_string()))?;
+    let data =
+        serde_json::to_string_pretty(config).map_err(|err| AppError::Internal(err.to_string()))?;
+    fs::write(path, data).map_err(|err| AppError::ConfigIo(err.to_string()))
+}
+
+/// Loads the settings of an existing profile without activating it.
+pub(crate) fn load_profile(handle: &AppHandle, name: &str) -> AppResult<AppConfig> {
+    load_profile_in(&require_config_dir(handle)?, name)
+}
+
+fn load_profile_in(dir: &Path, name: &str) -> AppResult<AppConfig> {
+    let path = profile_path(dir, name);
+    if name != DEFAULT_PROFILE && !path.is_file() {
+        return Err(AppError::InvalidSetting(format!("配置方案 {name} 不存在")));
+    }
+    Ok(AppConfig::load_from(&path))
+}
+
+/// Records `name` as the active profile; later loads and saves use its settings file.
+pub(crate) fn set_active_profile(handle: &AppHandle, name: &str) -> AppResult<()> {
+    set_active_profile_in(&require_config_dir(handle)?, name)
+}
+
+fn set_active_profile_in(dir: &Path, name: &str) -> AppResult<()> {
+    fs::create_dir_all(dir).map_err(|err| AppError::ConfigIo(err.to_string()))?;
+    fs::write(dir.join(PROFILE_POINTER_FILE), name)
+        .map_err(|err| AppError::ConfigIo(err.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_prefix_uppercases_a_single_letter() {
+        assert_eq!(normalize_prefix("b").as_deref(), Some("B"));
+        assert_eq!(normalize_prefix("  r").as_deref(), Some("R"));
+    }
+
+    #[test]
+    fn normalize_prefix_keeps_one_trailing_separator() {
+        assert_eq!(normalize_prefix("b:").as_deref(), Some("B:"));
+        assert_eq!(normalize_prefix("s ").as_deref(), Some("S "));
+    }
+
+    #[test]
+    fn normalize_prefix_rejects_anything_else() {
+        for value in ["", "   ", "1", ">", "bb", "b-", "b :", "b: x"] {
+            assert_eq!(normalize_prefix(value), None, "{value:?}");
+        }
+    }
+
+    #[test]
+    fn inverted_advanced_bounds_are_sanitized() {
+        let advanced = AdvancedConfig {
+            min_query_delay_ms: 800,
+            max_query_delay_ms: 100,
+            min_result_limit: 40,
+            max_result_limit: 5,
+        }
+        .sanitized();
+        assert_eq!(advanced.min_query_delay_ms, 800);
+        assert_eq!(advanced.max_query_delay_ms, 800);
+        assert_eq!(advanced.min_result_limit, 40);
+        assert_eq!(advanced.max_result_limit, 40);
+    }
+
+    #[test]
+    fn advanced_bounds_stay_below_the_hard_ceilings() {
+        let advanced = AdvancedConfig {
+            min_query_delay_ms: 50_000,
+            max_query_delay_ms: 90_000,
+            min_result_limit: 0,
+            max_result_limit: 10_000,
+        }
+        .sanitized();
+        assert_eq!(advanced.min_query_delay_ms, HARD_MAX_QUERY_DELAY_MS);
+        assert_eq!(advanced.max_query_delay_ms, HARD_MAX_QUERY_DELAY_MS);
+        assert_eq!(advanced.min_result_limit, 1);
+        assert_eq!(advanced.max_result_limit, HARD_MAX_RESULT_LIMIT);
+    }
+
+    /// An empty config directory of its own for each test.
+    fn temp_config_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("launcher-profiles-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn switching_profiles_changes_which_settings_file_is_used() {
+        let dir = temp_config_dir("switch");
+        let work = AppConfig {
+            global_hotkey: "Ctrl+Alt+W".to_string(),
+            ..AppConfig::default()
+        };
+        create_profile_in(&dir, "work", &work).unwrap();
+
+        let profiles = profiles_in(&dir);
+        assert_eq!(profiles.active, DEFAULT_PROFILE);
+        assert_eq!(profiles.profiles, [DEFAULT_PROFILE, "work"]);
+        assert_eq!(active_profile_path(&dir), dir.join(CONFIG_FILE));
+
+        set_active_profile_in(&dir, "work").unwrap();
+        assert_eq!(profiles_in(&dir).active, "work");
+        assert_eq!(active_profile_path(&dir), dir.join("settings.work.json"));
+        assert_eq!(
+            load_profile_in(&dir, "work").unwrap().global_hotkey,
+            "Ctrl+Alt+W"
+        );
+        // 默认方案不受其他方案影响
+        assert_eq!(
+            load_profile_in(&dir, DEFAULT_PROFILE)
+                .unwrap()
+                .global_hotkey,
+            AppConfig::default().global_hotkey
+        );
+
+        set_active_profile_in(&dir, DEFAULT_PROFILE).unwrap();
+        assert_eq!(active_profile_path(&dir), dir.join(CONFIG_FILE));
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn missing_or_duplicate_profiles_are_rejected() {
+        let dir = temp_config_dir("reject");
+        assert!(matches!(
+            load_profile_in(&dir, "missing"),
+            Err(AppError::InvalidSetting(_))
+        ));
+        create_profile_in(&dir, "work", &AppConfig::default()).unwrap();
+        assert!(matches!(
+            create_profile_in(&dir, "work", &AppConfig::default()),
+            Err(AppError::InvalidSetting(_))
+        ));
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn invalid_profile_pointer_falls_back_to_the_default() {
+        let dir = temp_config_dir("pointer");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join(PROFILE_POINTER_FILE), "../escape").unwrap();
+        assert_eq!(read_active_profile(&dir), DEFAULT_PROFILE);
+
+        fs::write(dir.join(PROFILE_POINTER_FILE), " work \n").unwrap();
+        assert_eq!(read_active_profile(&dir), "work");
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn unresolvable_config_dir_is_reported() {
+        let err = check_persistence_in(None).unwrap_err();
+        assert!(matches!(err, AppError::ConfigIo(_)), "{err:?}");
+    }
+
+    #[test]
+    fn unwritable_config_dir_is_reported() {
+        // 以普通文件充当父目录，配置目录无法创建
+        let blocker = temp_config_dir("blocked");
+        fs::write(&blocker, b"").unwrap();
+        let result = check_persistence_in(Some(blocker.join("config")));
+        let _ = fs::remove_file(&blocker);
+        assert!(matches!(result, Err(AppError::ConfigIo(_))));
+    }
+
+    #[test]
+    fn writable_config_dir_reports_the_active_settings_file() {
+        let dir = temp_config_dir("writable");
+        assert_eq!(
+            check_persistence_in(Some(dir.clone())).unwrap(),
+            dir.join(CONFIG_FILE)
+        );
+        // 探测文件不会留在配置目录中
+        assert!(!dir.join(".write-test").exists());
+        let _ = fs::remove_dir_all(&dir);
+    }
 }
@@ -0,0 +1,190 @@
+use std::{
+    collections::hash_map::DefaultHasher,
+    fs,
+    hash::{Hash, Hasher},
+    path::PathBuf,
+    sync::mpsc::{self, RecvTimeoutError},
+    thread,
+    time::{Duration, Instant},
+};
+
+use log::{debug, info, warn};
+use notify::{Event, RecommendedWatcher, RecursiveMode, Watcher};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::{
+    commands::{prepare_config, SETTINGS_UPDATED_EVENT},
+    config::{self, AppConfig},
+    hotkey::{bind_hotkey, bind_mode_hotkeys},
+    state::AppState,
+};
+
+/// Editors often save in several steps (truncate, write, rename); wait for the burst to settle.
+const DEBOUNCE: Duration = Duration::from_millis(500);
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+/// How often the config directory is retried when it couldn't be watched yet.
+const ATTACH_RETRY_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Watches the active profile's settings file and applies hand edits without a restart.
+///
+/// The config directory is watched rather than the file, so editors that save by replacing
+/// the file are picked up too. A change updates the shared config, rebinds the global hotkey
+/// when it differs and emits [`SETTINGS_UPDATED_EVENT`]. Writes made by [`AppConfig::save`]
+/// are recognized because the file then matches the in-memory settings, so they never loop
+/// back. Files that fail to parse or to validate are ignored and the current settings stay in
+/// place; the edited file is normalized like an import before it is applied.
+pub fn spawn_config_watcher(app_handle: AppHandle) {
+    let (tx, rx) = mpsc::channel::<notify::Result<Event>>();
+    let mut watcher: RecommendedWatcher = match notify::recommended_watcher(tx) {
+        Ok(watcher) => watcher,
+        Err(err) => {
+            warn!("failed to create config watcher: {err}");
+            return;
+        }
+    };
+
+    let spawned = thread::Builder::new()
+        .name("config-watcher".into())
+        .spawn(move || {
+            let mut attached: Option<PathBuf> = None;
+            let mut last_attach_attempt: Option<Instant> = None;
+            let mut pending_since: Option<Instant> = None;
+            let mut last_applied_hash: Option<u64> = None;
+
+            loop {
+                if attached.is_none()
+                    && last_attach_attempt.is_none_or(|at| at.elapsed() >= ATTACH_RETRY_INTERVAL)
+                {
+                    attached = attach_config_dir(&app_handle, &mut watcher);
+                    last_attach_attempt = Some(Instant::now());
+                }
+
+                match rx.recv_timeout(POLL_INTERVAL) {
+                    Ok(Ok(event)) => {
+                        if touches_config_file(&app_handle, &event) {
+                            pending_since = Some(Instant::now());
+                        }
+                    }
+                    Ok(Err(err)) => debug!("config watcher error: {err}"),
+                    Err(RecvTimeoutError::Timeout) => {}
+                    Err(RecvTimeoutError::Disconnected) => break,
+                }
+
+                let Some(since) = pending_since else {
+                    continue;
+                };
+                if since.elapsed() < DEBOUNCE {
+                    continue;
+                }
+                pending_since = None;
+
+                reload_if_changed(&app_handle, &mut last_applied_hash);
+            }
+        });
+
+    if let Err(err) = spawned {
+        warn!("failed to spawn config watcher: {err}");
+    }
+}
+
+fn attach_config_dir(app_handle: &AppHandle, watcher: &mut RecommendedWatcher) -> Option<PathBuf> {
+    let dir = config::config_path(app_handle)?.parent()?.to_path_buf();
+    if !dir.is_dir() {
+        return None;
+    }
+    match watcher.watch(&dir, RecursiveMode::NonRecursive) {
+        Ok(()) => {
+            debug!("watching {:?} for settings changes", dir);
+            Some(dir)
+        }
+        Err(err) => {
+            warn!("failed to watch {:?}: {err}", dir);
+            None
+        }
+    }
+}
+
+/// Whether `event` concerns the settings file of the profile active right now.
+fn touches_config_file(app_handle: &AppHandle, event: &Event) -> bool {
+    let Some(path) = config::config_path(app_handle) else {
+        return false;
+    };
+    event.paths.iter().any(|changed| changed == &path)
+}
+
+fn reload_if_changed(app_handle: &AppHandle, last_applied_hash: &mut Option<u64>) {
+    let Some(path) = config::config_path(app_handle) else {
+        return;
+    };
+    let Ok(content) = fs::read_to_string(&path) else {
+        return;
+    };
+    let hash = content_hash(&content);
+    if *last_applied_hash == Some(hash) {
+        return;
+    }
+
+    let Some(state) = app_handle.try_state::<AppState>() else {
+        return;
+    };
+    let Ok(mut guard) = state.config.lock() else {
+        return;
+    };
+    // 与内存中的配置序列化结果一致，说明是 save() 自己写入的，无需重新应用
+    let current = serde_json::to_string_pretty(&*guard).unwrap_or_default();
+    if content_hash(&current) == hash {
+        *last_applied_hash = Some(hash);
+        return;
+    }
+
+    let loaded: AppConfig = match serde_json::from_str(&content) {
+        Ok(loaded) => loaded,
+        Err(err) => {
+            // 手动编辑可能尚未完成，保留当前配置，等待下一次修改
+            warn!("ignoring invalid settings file {:?}: {err}", path);
+            return;
+        }
+    };
+    // 同样的内容不再重复校验，文件再次修改后才会重新尝试
+    *last_applied_hash = Some(hash);
+    let mut loaded = match prepare_config(loaded) {
+        Ok(loaded) => loaded,
+        Err(err) => {
+            warn!(
+                "ignoring settings file {:?} that fails validation: {err}",
+                path
+            );
+            return;
+        }
+    };
+
+    let previous_hotkey = guard.global_hotkey.clone();
+    if loaded.global_hotkey != previous_hotkey {
+        if let Err(err) = bind_hotkey(app_handle, &state, &loaded.global_hotkey, "main") {
+            // 新快捷键无法注册时保留原快捷键，使配置与实际注册状态一致
+            warn!(
+                "failed to register global shortcut {} from edited settings: {err}",
+                loaded.global_hotkey
+            );
+            loaded.global_hotkey = previous_hotkey;
+        }
+    }
+    if loaded.mode_hotkeys != guard.mode_hotkeys {
+        if let Err(err) = bind_mode_hotkeys(app_handle, &state, &loaded.mode_hotkeys) {
+            warn!("failed to register mode hotkeys from edited settings: {err}");
+            loaded.mode_hotkeys = guard.mode_hotkeys.clone();
+        }
+    }
+    *guard = loaded;
+    let snapshot = guard.clone();
+    drop(guard);
+
+    info!("settings file changed on disk, reloaded");
+    let _ = app_handle.emit(SETTINGS_UPDATED_EVENT, snapshot);
+}
+
+fn content_hash(content: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    content.hash(&mut hasher);
+    hasher.finish()
+}
@@ -0,0 +1,142 @@
+use std::{
+    io::{BufRead, BufReader},
+    os::windows::process::CommandExt,
+    path::Path,
+    process::{Command, Stdio},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        mpsc, Arc,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::config::ContentSearchConfig;
+
+/// Keeps ripgrep from flashing a console window.
+const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+const POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+#[derive(Debug, Clone)]
+pub struct ContentMatch {
+    pub path: String,
+    pub line_number: u64,
+    pub line: String,
+}
+
+/// Runs ripgrep for `pattern` over the configured roots and returns the first match per file.
+///
+/// The run is killed when it exceeds `timeout_ms` or when `generation` moves past
+/// `expected_generation`, i.e. a newer content query has started.
+pub fn run_ripgrep(
+    config: &ContentSearchConfig,
+    pattern: &str,
+    generation: &Arc<AtomicU64>,
+    expected_generation: u64,
+) -> Result<Vec<ContentMatch>, String> {
+    let roots: Vec<&str> = config
+        .roots
+        .iter()
+        .map(|root| root.trim())
+        .filter(|root| !root.is_empty() && Path::new(root).is_dir())
+        .collect();
+    if roots.is_empty() {
+        return Err("未配置有效的内容搜索目录".into());
+    }
+
+    let mut command = Command::new(config.rg_path.trim());
+    command
+        .args([
+            "--null",
+            "--line-number",
+            "--no-heading",
+            "--color",
+            "never",
+            "--max-count",
+            "1",
+            "--max-columns",
+            "200",
+            "--smart-case",
+            "--fixed-strings",
+        ])
+        .creation_flags(CREATE_NO_WINDOW)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null());
+    for glob in config.globs.iter().map(|glob| glob.trim()) {
+        if !glob.is_empty() {
+            command.arg("--glob").arg(glob);
+        }
+    }
+    command.arg("--").arg(pattern).args(&roots);
+
+    let mut child = command
+        .spawn()
+        .map_err(|err| format!("无法启动 ripgrep ({}): {err}", config.rg_path))?;
+    let Some(stdout) = child.stdout.take() else {
+        let _ = child.kill();
+        return Err("无法读取 ripgrep 输出".into());
+    };
+
+    // 在独立线程中读取输出，主循环负责超时与取消
+    let max_results = config.max_results as usize;
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+            if let Some(found) = parse_match(&line) {
+                if sender.send(found).is_err() {
+                    break;
+                }
+            }
+        }
+    });
+
+    let deadline = Instant::now() + Duration::from_millis(config.timeout_ms);
+    let mut matches = Vec::new();
+    loop {
+        if generation.load(Ordering::Acquire) != expected_generation {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err("内容搜索已被新的查询取代".into());
+        }
+
+        while let Ok(found) = receiver.try_recv() {
+            matches.push(found);
+        }
+        if matches.len() >= max_results {
+            let _ = child.kill();
+            let _ = child.wait();
+            break;
+        }
+
+        match child.try_wait() {
+            Ok(Some(_)) => {
+                // 进程已退出，取走剩余输出
+                matches.extend(receiver.iter());
+                break;
+            }
+            Ok(None) if Instant::now() >= deadline => {
+                let _ = child.kill();
+                let _ = child.wait();
+                log::debug!("ripgrep 超时，返回已找到的 {} 条结果", matches.len());
+                break;
+            }
+            Ok(None) => thread::sleep(POLL_INTERVAL),
+            Err(err) => return Err(err.to_string()),
+        }
+    }
+
+    matches.truncate(max_results);
+    Ok(matches)
+}
+
+/// Parses a `--null` formatted line: `path\0line:text`.
+fn parse_match(line: &str) -> Option<ContentMatch> {
+    let (path, rest) = line.split_once('\0')?;
+    let (line_number, text) = rest.split_once(':')?;
+    Some(ContentMatch {
+        path: path.to_string(),
+        line_number: line_number.parse().ok()?,
+        line: text.trim().to_string(),
+    })
+}
@@ -0,0 +1,365 @@
+use serde::{Deserialize, Serialize};
+
+use crate::config::TypedLaunchArgs;
+
+/// Placeholder replaced by the text typed after the keyword.
+pub const INPUT_PLACEHOLDER: &str = "{input}";
+
+/// A user-defined launcher triggered by typing `keyword` followed by optional input,
+/// e.g. `jira ABC-12` with the target `https://jira/browse/{input}`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CustomCommand {
+    pub keyword: String,
+    pub title: String,
+    #[serde(default)]
+    pub kind: CustomCommandKind,
+    /// URL template (for `url`) or program path (for `command`).
+    pub target: String,
+    /// Argument template passed to the program; only used for `command`.
+    #[serde(default)]
+    pub arguments: String,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CustomCommandKind {
+    #[default]
+    Url,
+    Command,
+}
+
+/// A custom command with the input substituted, ready to preview and run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExpandedCommand {
+    Url(String),
+    Command { target: String, arguments: String },
+}
+
+impl CustomCommand {
+    /// Returns the input after the keyword when `query` invokes this command.
+    /// The keyword must be followed by whitespace or end the query.
+    pub fn match_input<'a>(&self, query: &'a str) -> Option<&'a str> {
+        let keyword = self.keyword.trim();
+        if keyword.is_empty() {
+            return None;
+        }
+        let query = query.trim_start();
+        let head = query.get(..keyword.len())?;
+        if !head.eq_ignore_ascii_case(keyword) {
+            return None;
+        }
+        let rest = &query[keyword.len()..];
+        if !rest.is_empty() && !rest.starts_with(char::is_whitespace) {
+            return None;
+        }
+        Some(rest.trim())
+    }
+
+    /// Substitutes `input` into the templates, encoding it for the target type.
+    pub fn expand(&self, input: &str) -> ExpandedCommand {
+        match self.kind {
+            CustomCommandKind::Url => ExpandedCommand::Url(
+                self.target
+                    .replace(INPUT_PLACEHOLDER, &urlencoding::encode(input)),
+            ),
+            CustomCommandKind::Command => ExpandedCommand::Command {
+                target: self.target.clone(),
+                arguments: self
+                    .arguments
+                    .replace(INPUT_PLACEHOLDER, &quote_windows_argument(input)),
+            },
+        }
+    }
+
+    pub fn needs_input(&self) -> bool {
+        self.target.contains(INPUT_PLACEHOLDER) || self.arguments.contains(INPUT_PLACEHOLDER)
+    }
+}
+
+/// Splits a command line into arguments following the `CommandLineToArgvW` rules, the
+/// inverse of [`quote_windows_argument`]. Unlike Windows, an unterminated quote is an error
+/// rather than running to the end of the line.
+pub fn split_windows_arguments(input: &str) -> Result<Vec<String>, String> {
+    let mut arguments = Vec::new();
+    let mut current = String::new();
+    let mut in_argument = false;
+    let mut in_quotes = false;
+    let mut backslashes = 0usize;
+
+    for ch in input.chars() {
+        match ch {
+            '\\' => {
+                backslashes += 1;
+                in_argument = true;
+            }
+            '"' => {
+                // 偶数个反斜杠加引号：反斜杠减半，引号切换引用状态；奇数个则引号按字面保留
+                current.push_str(&"\\".repeat(backslashes / 2));
+                if backslashes % 2 == 1 {
+                    current.push('"');
+                } else {
+                    in_quotes = !in_quotes;
+                }
+                backslashes = 0;
+                in_argument = true;
+            }
+            ' ' | '\t' | '\n' if !in_quotes => {
+                current.push_str(&"\\".repeat(backslashes));
+                backslashes = 0;
+                if in_argument {
+                    arguments.push(std::mem::take(&mut current));
+                    in_argument = false;
+                }
+            }
+            _ => {
+                current.push_str(&"\\".repeat(backslashes));
+                backslashes = 0;
+                current.push(ch);
+                in_argument = true;
+            }
+        }
+    }
+
+    if in_quotes {
+        return Err("参数中的引号未闭合".into());
+    }
+    current.push_str(&"\\".repeat(backslashes));
+    if in_argument {
+        arguments.push(current);
+    }
+    Ok(arguments)
+}
+
+/// Quotes `value` as a single argument following the `CommandLineToArgvW` rules, so spaces
+/// and quotes in user input can't split it or inject extra arguments.
+pub fn quote_windows_argument(value: &str) -> String {
+    if !value.is_empty() && !value.contains([' ', '\t', '\n', '"']) {
+        return value.to_string();
+    }
+
+    let mut quoted = String::from('"');
+    let mut backslashes = 0usize;
+    for ch in value.chars() {
+        match ch {
+            '\\' => backslashes += 1,
+            '"' => {
+                // 引号前的反斜杠需要加倍，再转义引号本身
+                quoted.push_str(&"\\".repeat(backslashes * 2 + 1));
+                quoted.push('"');
+                backslashes = 0;
+            }
+            _ => {
+                quoted.push_str(&"\\".repeat(backslashes));
+                quoted.push(ch);
+                backslashes = 0;
+            }
+        }
+    }
+    // 结尾的反斜杠会紧挨着闭合引号，同样需要加倍
+    quoted.push_str(&"\\".repeat(backslashes * 2));
+    quoted.push('"');
+    quoted
+}
+
+/// Quotes every argument and joins them into one command line.
+pub fn join_windows_arguments(arguments: &[String]) -> String {
+    arguments
+        .iter()
+        .map(|argument| quote_windows_argument(argument))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Splits and re-quotes the saved launch arguments of an app; an empty string means none.
+/// A literal quote inside an argument is rejected: shortcuts and batch files don't all
+/// unescape it the way `CommandLineToArgvW` does.
+pub fn normalize_launch_arguments(input: &str) -> Result<String, String> {
+    let arguments = split_windows_arguments(input)?;
+    if arguments.iter().any(|argument| argument.contains('"')) {
+        return Err("启动参数中不能包含引号字符".into());
+    }
+    Ok(join_windows_arguments(&arguments))
+}
+
+/// The arguments an app runs with when `typed` was entered after its name and `saved` are
+/// its saved launch arguments.
+pub fn combine_launch_arguments(
+    saved: Option<&str>,
+    typed: &str,
+    policy: TypedLaunchArgs,
+) -> String {
+    match (saved.filter(|saved| !saved.is_empty()), policy) {
+        (Some(saved), TypedLaunchArgs::Append) => format!("{saved} {typed}"),
+        _ => typed.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_keeps_quoted_spaces_in_one_argument() {
+        assert_eq!(
+            split_windows_arguments(r#"--profile "Work Profile"  -x"#).unwrap(),
+            ["--profile", "Work Profile", "-x"]
+        );
+    }
+
+    #[test]
+    fn split_keeps_backslashes_outside_quotes() {
+        assert_eq!(
+            split_windows_arguments(r"C:\tools\app --dir C:\temp\").unwrap(),
+            [r"C:\tools\app", "--dir", r"C:\temp\"]
+        );
+    }
+
+    #[test]
+    fn split_of_blank_input_is_empty() {
+        assert!(split_windows_arguments("").unwrap().is_empty());
+        assert!(split_windows_arguments(" \t\n ").unwrap().is_empty());
+    }
+
+    #[test]
+    fn split_rejects_an_unterminated_quote() {
+        assert!(split_windows_arguments(r#"--name "open"#).is_err());
+    }
+
+    #[test]
+    fn join_quotes_only_what_needs_it() {
+        let arguments = ["--profile".to_string(), "Work Profile".to_string()];
+        assert_eq!(
+            join_windows_arguments(&arguments),
+            r#"--profile "Work Profile""#
+        );
+        assert_eq!(
+            split_windows_arguments(&join_windows_arguments(&arguments)).unwrap(),
+            arguments
+        );
+    }
+
+    #[test]
+    fn launch_arguments_are_re_quoted() {
+        assert_eq!(
+            normalize_launch_arguments(r#"  --profile   "Work Profile" "#).unwrap(),
+            r#"--profile "Work Profile""#
+        );
+        assert_eq!(normalize_launch_arguments("   ").unwrap(), "");
+    }
+
+    #[test]
+    fn launch_arguments_reject_embedded_quotes() {
+        assert!(normalize_launch_arguments(r#"--title a\"b"#).is_err());
+        assert!(normalize_launch_arguments(r#"--title "say \"hi\"""#).is_err());
+    }
+
+    fn command(kind: CustomCommandKind, target: &str, arguments: &str) -> CustomCommand {
+        CustomCommand {
+            keyword: "jira".to_string(),
+            title: "Jira".to_string(),
+            kind,
+            target: target.to_string(),
+            arguments: arguments.to_string(),
+        }
+    }
+
+    #[test]
+    fn keyword_must_be_followed_by_whitespace_or_end_the_query() {
+        let jira = command(CustomCommandKind::Url, "https://jira/browse/{input}", "");
+        assert_eq!(jira.match_input("  JIRA   ABC-12  "), Some("ABC-12"));
+        assert_eq!(jira.match_input("jira"), Some(""));
+        assert_eq!(jira.match_input("jiranotes"), None);
+        assert_eq!(jira.match_input("ji"), None);
+        // 多字节字符不会在关键字边界处切断
+        assert_eq!(jira.match_input("jir界"), None);
+    }
+
+    #[test]
+    fn url_input_is_percent_encoded_at_every_placeholder() {
+        let search = command(
+            CustomCommandKind::Url,
+            "https://example.com/search?q={input}&again={input}",
+            "",
+        );
+        assert_eq!(
+            search.expand("a b&c=d/é"),
+            ExpandedCommand::Url(
+                "https://example.com/search?q=a%20b%26c%3Dd%2F%C3%A9&again=a%20b%26c%3Dd%2F%C3%A9"
+                    .to_string()
+            )
+        );
+        assert_eq!(
+            command(CustomCommandKind::Url, "https://jira/browse/{input}", "").expand(""),
+            ExpandedCommand::Url("https://jira/browse/".to_string())
+        );
+    }
+
+    #[test]
+    fn command_input_is_quoted_as_one_argument() {
+        let grep = command(
+            CustomCommandKind::Command,
+            r"C:\Tools\grep.exe",
+            "-r {input} .",
+        );
+        let expanded = |input: &str| match grep.expand(input) {
+            ExpandedCommand::Command { target, arguments } => {
+                assert_eq!(target, r"C:\Tools\grep.exe");
+                arguments
+            }
+            other => panic!("unexpected {other:?}"),
+        };
+
+        assert_eq!(expanded("needle"), "-r needle .");
+        assert_eq!(expanded("two words"), r#"-r "two words" ."#);
+        assert_eq!(
+            expanded(r#"say "hi" & exit"#),
+            r#"-r "say \"hi\" & exit" ."#
+        );
+        assert_eq!(expanded(""), r#"-r "" ."#);
+        // 引号转义后仍拆分为原来的一个参数
+        assert_eq!(
+            split_windows_arguments(&expanded(r#"a "b" c\"#)).unwrap(),
+            ["-r", r#"a "b" c\"#, "."]
+        );
+    }
+
+    #[test]
+    fn needs_input_looks_at_target_and_arguments() {
+        assert!(command(CustomCommandKind::Url, "https://x/{input}", "").needs_input());
+        assert!(command(CustomCommandKind::Command, "tool.exe", "--q {input}").needs_input());
+        assert!(!command(CustomCommandKind::Url, "https://x/", "").needs_input());
+    }
+
+    #[test]
+    fn typed_arguments_follow_the_saved_ones_by_default() {
+        assert_eq!(
+            combine_launch_arguments(
+                Some(r#"--profile "Work Profile""#),
+                "https://example.com",
+                TypedLaunchArgs::default()
+            ),
+            r#"--profile "Work Profile" https://example.com"#
+        );
+    }
+
+    #[test]
+    fn replace_policy_drops_the_saved_arguments() {
+        assert_eq!(
+            combine_launch_arguments(
+                Some("--incognito"),
+                "https://example.com",
+                TypedLaunchArgs::Replace
+            ),
+            "https://example.com"
+        );
+    }
+
+    #[test]
+    fn typed_arguments_alone_when_nothing_is_saved() {
+        for policy in [TypedLaunchArgs::Append, TypedLaunchArgs::Replace] {
+            for saved in [None, Some("")] {
+                assert_eq!(combine_launch_arguments(saved, "-x", policy), "-x");
+            }
+        }
+    }
+}
@@ -0,0 +1,371 @@
+use std::{
+    collections::HashSet,
+    env, fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use log::debug;
+
+use crate::{
+    ids,
+    models::{AppType, ApplicationInfo},
+    text_utils::extend_keywords_with_pinyin,
+};
+
+/// The parts of a `[Desktop Entry]` group the index uses.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct DesktopEntry {
+    name: String,
+    exec: String,
+    icon: Option<String>,
+    comment: Option<String>,
+    keywords: Vec<String>,
+}
+
+/// Icon theme directories searched for a named PNG icon, largest first that still fits a row.
+const ICON_DIRS: &[&str] = &[
+    "/usr/share/icons/hicolor/48x48/apps",
+    "/usr/share/icons/hicolor/64x64/apps",
+    "/usr/share/icons/hicolor/128x128/apps",
+    "/usr/share/pixmaps",
+];
+
+/// Lists the applications of the freedesktop `.desktop` files under every `applications`
+/// directory of `$XDG_DATA_HOME` and `$XDG_DATA_DIRS`. A file in the user's directory hides a
+/// system file with the same desktop file id, as the spec asks.
+///
+/// With `extract_icons` the `Icon` key is resolved to a PNG in the hicolor theme or pixmaps;
+/// SVG-only icons stay empty and get the frontend's fallback glyph.
+pub fn enumerate_desktop_entries(extract_icons: bool) -> Vec<ApplicationInfo> {
+    let mut seen_ids = HashSet::new();
+    let mut apps = Vec::new();
+    for dir in application_dirs() {
+        let mut files = Vec::new();
+        collect_desktop_files(&dir, &mut files);
+        for path in files {
+            let Some(file_id) = desktop_file_id(&dir, &path) else {
+                continue;
+            };
+            if !seen_ids.insert(file_id.clone()) {
+                continue;
+            }
+            let Some(entry) = fs::read_to_string(&path)
+                .ok()
+                .and_then(|content| parse_desktop_entry(&content))
+            else {
+                continue;
+            };
+            apps.push(entry_to_application(&file_id, &path, entry, extract_icons));
+        }
+    }
+    debug!("indexed {} desktop entries", apps.len());
+    apps
+}
+
+/// Starts the `Exec` line of an entry with its field codes removed.
+pub fn launch(exec: &str) -> Result<(), String> {
+    let arguments = exec_arguments(exec).ok_or_else(|| format!("无法解析启动命令 {exec}"))?;
+    let (program, arguments) = arguments
+        .split_first()
+        .ok_or_else(|| "启动命令为空".to_string())?;
+    Command::new(program)
+        .args(arguments)
+        .spawn()
+        .map(|_| ())
+        .map_err(|err| format!("无法启动 {program}: {err}"))
+}
+
+fn application_dirs() -> Vec<PathBuf> {
+    let data_home = env::var_os("XDG_DATA_HOME")
+        .filter(|value| !value.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share")));
+    let data_dirs = env::var("XDG_DATA_DIRS")
+        .ok()
+        .filter(|value| !value.is_empty())
+        .unwrap_or_else(|| "/usr/local/share:/usr/share".to_string());
+
+    data_home
+        .into_iter()
+        .chain(
+            data_dirs
+                .split(':')
+                .filter(|dir| !dir.is_empty())
+                .map(PathBuf::from),
+        )
+        .map(|dir| dir.join("applications"))
+        .filter(|dir| dir.is_dir())
+        .collect()
+}
+
+fn collect_desktop_files(dir: &Path, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            collect_desktop_files(&path, files);
+        } else if path.extension().is_some_and(|ext| ext == "desktop") {
+            files.push(path);
+        }
+    }
+}
+
+/// The desktop file id: the path below the `applications` directory with `/` replaced by `-`.
+fn desktop_file_id(dir: &Path, path: &Path) -> Option<String> {
+    let relative = path.strip_prefix(dir).ok()?.to_str()?;
+    Some(relative.replace('/', "-"))
+}
+
+fn entry_to_application(
+    file_id: &str,
+    path: &Path,
+    entry: DesktopEntry,
+    extract_icons: bool,
+) -> ApplicationInfo {
+    let mut keywords = entry.keywords;
+    keywords.push(entry.name.clone());
+    if let Some(program) = exec_arguments(&entry.exec)
+        .and_then(|arguments| arguments.into_iter().next())
+        .and_then(|program| {
+            Path::new(&program)
+                .file_name()
+                .and_then(|name| name.to_str())
+                .map(str::to_string)
+        })
+    {
+        keywords.push(program);
+    }
+    extend_keywords_with_pinyin(&mut keywords);
+    keywords.sort();
+    keywords.dedup();
+
+    let icon_b64 = entry
+        .icon
+        .as_deref()
+        .filter(|_| extract_icons)
+        .and_then(load_icon)
+        .unwrap_or_default();
+    ApplicationInfo {
+        id: ids::desktop_id(file_id),
+        name: entry.name,
+        path: entry.exec,
+        source_path: Some(path.to_string_lossy().into_owned()),
+        app_type: AppType::Desktop,
+        icon_b64,
+        description: entry.comment,
+        keywords,
+        working_directory: None,
+        arguments: None,
+        origin: None,
+        icon_source: None,
+        launch_args: None,
+        indexed_name: None,
+    }
+}
+
+/// The `Icon` value as base64 PNG: either an absolute path or a name looked up in [`ICON_DIRS`].
+fn load_icon(icon: &str) -> Option<String> {
+    let path = if Path::new(icon).is_absolute() {
+        PathBuf::from(icon)
+    } else {
+        ICON_DIRS
+            .iter()
+            .map(|dir| Path::new(dir).join(format!("{icon}.png")))
+            .find(|path| path.is_file())?
+    };
+    if !path.extension().is_some_and(|ext| ext == "png") {
+        return None;
+    }
+    fs::read(path).ok().map(|bytes| BASE64.encode(bytes))
+}
+
+/// Reads the `[Desktop Entry]` group. Entries that aren't applications, are hidden from menus
+/// or have no `Exec` line yield `None`. Localized keys such as `Name[de]` are skipped.
+fn parse_desktop_entry(content: &str) -> Option<DesktopEntry> {
+    let mut entry = DesktopEntry::default();
+    let mut in_main_group = false;
+    let mut is_application = false;
+
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if line.starts_with('[') {
+            in_main_group = line == "[Desktop Entry]";
+            continue;
+        }
+        if !in_main_group {
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let value = value.trim();
+        match key.trim() {
+            "Type" => is_application = value == "Application",
+            "Name" => entry.name = unescape_value(value),
+            "Exec" => entry.exec = unescape_value(value),
+            "Icon" => entry.icon = Some(value.to_string()).filter(|icon| !icon.is_empty()),
+            "Comment" => {
+                entry.comment = Some(unescape_value(value)).filter(|text| !text.is_empty())
+            }
+            "Keywords" => {
+                entry.keywords = value
+                    .split(';')
+                    .map(|keyword| unescape_value(keyword.trim()))
+                    .filter(|keyword| !keyword.is_empty())
+                    .collect();
+            }
+            "NoDisplay" | "Hidden" if value == "true" => return None,
+            _ => {}
+        }
+    }
+
+    (is_application && !entry.name.is_empty() && !entry.exec.is_empty()).then_some(entry)
+}
+
+/// Resolves the `\s`, `\n`, `\t`, `\r` and `\\` escapes of a string value.
+fn unescape_value(value: &str) -> String {
+    let mut unescaped = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(ch) = chars.next() {
+        if ch != '\\' {
+            unescaped.push(ch);
+            continue;
+        }
+        match chars.next() {
+            Some('s') => unescaped.push(' '),
+            Some('n') => unescaped.push('\n'),
+            Some('t') => unescaped.push('\t'),
+            Some('r') => unescaped.push('\r'),
+            Some(other) => unescaped.push(other),
+            None => unescaped.push('\\'),
+        }
+    }
+    unescaped
+}
+
+/// Splits an `Exec` value into arguments and drops the field codes (`%f`, `%U`, `%i`, ...)
+/// that only make sense when files are passed in; `%%` stays a literal `%`. `None` when a
+/// quote is left open.
+fn exec_arguments(exec: &str) -> Option<Vec<String>> {
+    let mut arguments = Vec::new();
+    let mut current = String::new();
+    let mut in_argument = false;
+    let mut in_quotes = false;
+    let mut chars = exec.chars();
+
+    while let Some(ch) = chars.next() {
+        match ch {
+            '"' => {
+                in_quotes = !in_quotes;
+                in_argument = true;
+            }
+            '\\' if in_quotes => {
+                // 引号内 \" \` \$ \\ 表示字面字符
+                current.extend(chars.next());
+            }
+            '%' => match chars.next() {
+                Some('%') => {
+                    current.push('%');
+                    in_argument = true;
+                }
+                Some(_) => {}
+                None => return None,
+            },
+            ' ' | '\t' if !in_quotes => {
+                if in_argument && !current.is_empty() {
+                    arguments.push(std::mem::take(&mut current));
+                }
+                current.clear();
+                in_argument = false;
+            }
+            _ => {
+                current.push(ch);
+                in_argument = true;
+            }
+        }
+    }
+
+    if in_quotes {
+        return None;
+    }
+    if in_argument && !current.is_empty() {
+        arguments.push(current);
+    }
+    Some(arguments)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIREFOX: &str = "\
+[Desktop Entry]
+Type=Application
+Name=Firefox
+Name[de]=Firefox-Webbrowser
+Comment=Browse the Web
+Exec=firefox %u
+Icon=firefox
+Keywords=Internet;WWW;Browser;
+
+[Desktop Action new-window]
+Name=New Window
+Exec=firefox --new-window %u
+";
+
+    #[test]
+    fn parses_the_main_group_only() {
+        let entry = parse_desktop_entry(FIREFOX).unwrap();
+        assert_eq!(entry.name, "Firefox");
+        assert_eq!(entry.exec, "firefox %u");
+        assert_eq!(entry.icon.as_deref(), Some("firefox"));
+        assert_eq!(entry.comment.as_deref(), Some("Browse the Web"));
+        assert_eq!(entry.keywords, ["Internet", "WWW", "Browser"]);
+    }
+
+    #[test]
+    fn skips_hidden_entries_and_other_types() {
+        let hidden = FIREFOX.replace("Icon=firefox", "NoDisplay=true");
+        assert!(parse_desktop_entry(&hidden).is_none());
+        let link = FIREFOX.replace("Type=Application", "Type=Link");
+        assert!(parse_desktop_entry(&link).is_none());
+        let no_exec = FIREFOX.replace("Exec=firefox %u\n", "");
+        assert!(parse_desktop_entry(&no_exec).is_none());
+    }
+
+    #[test]
+    fn exec_drops_field_codes() {
+        assert_eq!(exec_arguments("firefox %u").unwrap(), ["firefox"]);
+        assert_eq!(
+            exec_arguments("code --new-window %F").unwrap(),
+            ["code", "--new-window"]
+        );
+        assert_eq!(exec_arguments("printf 100%%").unwrap(), ["printf", "100%"]);
+    }
+
+    #[test]
+    fn exec_keeps_quoted_arguments_together() {
+        assert_eq!(
+            exec_arguments(r#""/opt/My App/app" --title "say \"hi\"" %U"#).unwrap(),
+            ["/opt/My App/app", "--title", r#"say "hi""#]
+        );
+        assert!(exec_arguments(r#"app "open"#).is_none());
+    }
+
+    #[test]
+    fn desktop_file_id_joins_subdirectories_with_dashes() {
+        let dir = Path::new("/usr/share/applications");
+        let path = dir.join("kde/org.kde.dolphin.desktop");
+        assert_eq!(
+            desktop_file_id(dir, &path).as_deref(),
+            Some("kde-org.kde.dolphin.desktop")
+        );
+    }
+}
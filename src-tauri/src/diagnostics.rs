@@ -0,0 +1,306 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::PathBuf,
+    sync::atomic::Ordering,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use serde_json::{json, Map, Value};
+use tauri::{AppHandle, Manager};
+
+use crate::{
+    commands::app_paths,
+    config,
+    favicons::url_host,
+    hotkey,
+    models::{AppType, HealthCheck, HealthReport},
+    state::AppState,
+    windows_utils::{icon_cache_dir, ComGuard},
+};
+
+/// Collects a triage bundle and writes it as JSON into the config directory, returning its path.
+///
+/// Every section is gathered independently: a failing section is recorded as `{"error": ...}`
+/// and the rest of the bundle is still written. Bookmark URLs are reduced to their host unless
+/// `include_bookmark_urls` is set.
+pub fn export_bundle(
+    app_handle: &AppHandle,
+    state: &AppState,
+    include_bookmark_urls: bool,
+) -> Result<PathBuf, String> {
+    let generated_at = unix_now();
+
+    let mut bundle = Map::new();
+    bundle.insert("generated_at".into(), json!(generated_at));
+    bundle.insert(
+        "app_version".into(),
+        json!(app_handle.package_info().version.to_string()),
+    );
+    bundle.insert("config".into(), section(collect_config(state)));
+    bundle.insert("index_stats".into(), section(collect_index_stats(state)));
+    bundle.insert("paths".into(), section(collect_paths(app_handle, state)));
+    bundle.insert("shortcuts".into(), section(collect_shortcuts(state)));
+    bundle.insert(
+        "bookmarks".into(),
+        section(collect_bookmarks(state, include_bookmark_urls)),
+    );
+    // 目前没有写入日志文件，如实记录为缺失，便于之后接入日志插件
+    bundle.insert(
+        "recent_logs".into(),
+        section(Err("未配置日志文件，无法收集最近日志".to_string())),
+    );
+
+    let dir = app_handle
+        .path()
+        .app_config_dir()
+        .map_err(|err| err.to_string())?;
+    fs::create_dir_all(&dir).map_err(|err| err.to_string())?;
+    let path = dir.join(format!("diagnostics-{generated_at}.json"));
+    let data =
+        serde_json::to_string_pretty(&Value::Object(bundle)).map_err(|err| err.to_string())?;
+    fs::write(&path, data).map_err(|err| err.to_string())?;
+    Ok(path)
+}
+
+pub(crate) fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
+
+/// Checks the subsystems the launcher can't work without. Each check runs on its own, so the
+/// report always lists all of them.
+pub fn health_check(app_handle: &AppHandle, state: &AppState) -> HealthReport {
+    report(vec![
+        check("config", check_config(app_handle, state)),
+        check("hotkey", check_hotkey(state)),
+        check("app_index", check_app_index(state)),
+        check("com", check_com()),
+        check("icon_cache", check_icon_cache(icon_cache_dir())),
+    ])
+}
+
+fn report(checks: Vec<HealthCheck>) -> HealthReport {
+    HealthReport {
+        ok: checks.iter().all(|check| check.ok),
+        checks,
+    }
+}
+
+fn check(name: &'static str, result: Result<String, String>) -> HealthCheck {
+    let ok = result.is_ok();
+    let detail = result.unwrap_or_else(|err| err);
+    if !ok {
+        log::warn!("health check {name} failed: {detail}");
+    }
+    HealthCheck { name, ok, detail }
+}
+
+fn check_config(app_handle: &AppHandle, state: &AppState) -> Result<String, String> {
+    state
+        .config
+        .lock()
+        .map_err(|_| "无法获取配置".to_string())?;
+    let path = config::check_config_persistence(app_handle).map_err(|err| err.to_string())?;
+    Ok(format!("配置文件可写: {}", path.display()))
+}
+
+fn check_hotkey(state: &AppState) -> Result<String, String> {
+    let shortcuts = hotkey::registered_shortcuts(state);
+    match shortcuts.hotkey {
+        None => Err("未注册全局快捷键".into()),
+        Some(hotkey) if !shortcuts.enabled => Err(format!("快捷键 {hotkey} 已暂停")),
+        Some(hotkey) => Ok(format!("已注册 {hotkey}")),
+    }
+}
+
+fn check_app_index(state: &AppState) -> Result<String, String> {
+    let count = state
+        .app_index
+        .lock()
+        .map_err(|_| "无法访问应用索引".to_string())?
+        .len();
+    if count > 0 {
+        return Ok(format!("已索引 {count} 个应用"));
+    }
+    if state.app_reindex_in_progress.load(Ordering::Acquire) {
+        return Err("应用索引为空，正在刷新".into());
+    }
+    match state.last_app_reindex.load(Ordering::Acquire) {
+        0 => Err("应用索引为空，尚未完成过索引".into()),
+        at => Err(format!("应用索引为空，上次刷新于 {at}（Unix 时间）")),
+    }
+}
+
+fn check_com() -> Result<String, String> {
+    let _guard = unsafe { ComGuard::new() }.map_err(|err| format!("COM 初始化失败: {err}"))?;
+    Ok("COM 可用".into())
+}
+
+fn check_icon_cache(dir: Option<PathBuf>) -> Result<String, String> {
+    let dir = dir.ok_or_else(|| "无法确定图标缓存目录".to_string())?;
+    fs::create_dir_all(&dir)
+        .map_err(|err| format!("无法创建图标缓存目录 {}: {err}", dir.display()))?;
+    let probe = dir.join(".write-test");
+    fs::write(&probe, b"")
+        .map_err(|err| format!("图标缓存目录 {} 不可写: {err}", dir.display()))?;
+    let _ = fs::remove_file(&probe);
+    Ok(format!("图标缓存目录可写: {}", dir.display()))
+}
+
+fn section(result: Result<Value, String>) -> Value {
+    match result {
+        Ok(value) => value,
+        Err(err) => json!({ "error": err }),
+    }
+}
+
+fn collect_config(state: &AppState) -> Result<Value, String> {
+    let guard = state
+        .config
+        .lock()
+        .map_err(|_| "无法获取配置".to_string())?;
+    serde_json::to_value(&*guard).map_err(|err| err.to_string())
+}
+
+fn collect_index_stats(state: &AppState) -> Result<Value, String> {
+    let (win32_apps, uwp_apps) = {
+        let apps = state
+            .app_index
+            .lock()
+            .map_err(|_| "无法访问应用索引".to_string())?;
+        let win32 = apps
+            .iter()
+            .filter(|app| app.app_type == AppType::Win32)
+            .count();
+        (win32, apps.len() - win32)
+    };
+    let bookmarks = state
+        .bookmark_index
+        .lock()
+        .map_err(|_| "无法访问收藏夹索引".to_string())?
+        .len();
+    let history = state
+        .history_index
+        .lock()
+        .map_err(|_| "无法访问历史记录索引".to_string())?
+        .len();
+
+    Ok(json!({
+        "win32_apps": win32_apps,
+        "uwp_apps": uwp_apps,
+        "bookmarks": bookmarks,
+        "history_entries": history,
+        "app_reindex_in_progress": state.app_reindex_in_progress.load(Ordering::Acquire),
+        "bookmark_reindex_in_progress": state.bookmark_reindex_in_progress.load(Ordering::Acquire),
+        "history_reindex_in_progress": state.history_reindex_in_progress.load(Ordering::Acquire),
+    }))
+}
+
+fn collect_paths(app_handle: &AppHandle, state: &AppState) -> Result<Value, String> {
+    serde_json::to_value(app_paths(app_handle, state)).map_err(|err| err.to_string())
+}
+
+fn collect_shortcuts(state: &AppState) -> Result<Value, String> {
+    let registered = state
+        .registered_hotkey
+        .lock()
+        .map_err(|_| "无法读取已注册的快捷键".to_string())?
+        .clone();
+    Ok(json!({ "registered_hotkey": registered }))
+}
+
+fn collect_bookmarks(state: &AppState, include_urls: bool) -> Result<Value, String> {
+    let guard = state
+        .bookmark_index
+        .lock()
+        .map_err(|_| "无法访问收藏夹索引".to_string())?;
+
+    if include_urls {
+        let urls: Vec<&str> = guard.iter().map(|entry| entry.url.as_str()).collect();
+        return Ok(json!({ "urls": urls }));
+    }
+
+    // 默认只统计各域名下的收藏数量，不导出完整网址
+    let mut hosts: BTreeMap<String, usize> = BTreeMap::new();
+    for entry in guard.iter() {
+        let host = url_host(&entry.url).unwrap_or_else(|| "(other)".to_string());
+        *hosts.entry(host).or_default() += 1;
+    }
+    Ok(json!({ "hosts": hosts }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn one_failed_check_fails_the_report_but_keeps_the_others() {
+        let health = report(vec![
+            check("config", Ok("配置文件可写".into())),
+            check("com", Err("COM 初始化失败".into())),
+            check("icon_cache", Ok("图标缓存目录可写".into())),
+        ]);
+
+        assert!(!health.ok);
+        let outcomes: Vec<(&str, bool)> = health
+            .checks
+            .iter()
+            .map(|check| (check.name, check.ok))
+            .collect();
+        assert_eq!(
+            outcomes,
+            [("config", true), ("com", false), ("icon_cache", true)]
+        );
+        assert_eq!(health.checks[1].detail, "COM 初始化失败");
+        assert!(report(Vec::new()).ok);
+    }
+
+    #[test]
+    fn hotkey_check_fails_when_unregistered_or_suspended() {
+        let state = AppState::new();
+        assert!(check_hotkey(&state).is_err());
+
+        *state.registered_hotkey.lock().unwrap() = Some("Alt+Space".into());
+        assert!(check_hotkey(&state).is_ok());
+
+        state.hotkey_suspended.store(true, Ordering::Release);
+        let err = check_hotkey(&state).unwrap_err();
+        assert!(err.contains("Alt+Space"), "{err}");
+    }
+
+    #[test]
+    fn empty_app_index_reports_why() {
+        let state = AppState::new();
+        let never = check_app_index(&state).unwrap_err();
+
+        state
+            .last_app_reindex
+            .store(1_700_000_000, Ordering::Release);
+        let last = check_app_index(&state).unwrap_err();
+        assert!(last.contains("1700000000"), "{last}");
+
+        state.app_reindex_in_progress.store(true, Ordering::Release);
+        let running = check_app_index(&state).unwrap_err();
+        assert!(never != last && last != running);
+    }
+
+    #[test]
+    fn icon_cache_check_fails_without_a_writable_directory() {
+        assert!(check_icon_cache(None).is_err());
+
+        // 以普通文件充当目录，创建目录必然失败
+        let file = std::env::temp_dir().join(format!("health-check-{}", std::process::id()));
+        fs::write(&file, b"").unwrap();
+        let result = check_icon_cache(Some(file.join("icons")));
+        let _ = fs::remove_file(&file);
+        assert!(result.is_err());
+
+        let dir = std::env::temp_dir().join(format!("health-check-dir-{}", std::process::id()));
+        let result = check_icon_cache(Some(dir.clone()));
+        let _ = fs::remove_dir_all(&dir);
+        assert!(result.is_ok(), "{result:?}");
+    }
+}
@@ -0,0 +1,261 @@
+use std::{
+    cell::RefCell,
+    sync::{
+        atomic::{AtomicU32, AtomicU8, Ordering},
+        OnceLock,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use log::{debug, info, warn};
+use tauri::{AppHandle, Manager};
+use windows::Win32::{
+    Foundation::{HINSTANCE, HWND, LPARAM, LRESULT, WPARAM},
+    System::Threading::GetCurrentThreadId,
+    UI::{
+        Input::KeyboardAndMouse::{
+            VK_LCONTROL, VK_LMENU, VK_LSHIFT, VK_LWIN, VK_RCONTROL, VK_RMENU, VK_RSHIFT, VK_RWIN,
+        },
+        WindowsAndMessaging::{
+            CallNextHookEx, DispatchMessageW, GetMessageW, PostThreadMessageW, SetTimer,
+            SetWindowsHookExW, TranslateMessage, UnhookWindowsHookEx, HC_ACTION, HHOOK,
+            KBDLLHOOKSTRUCT, MSG, WH_KEYBOARD_LL, WM_KEYDOWN, WM_KEYUP, WM_QUIT, WM_SYSKEYDOWN,
+            WM_SYSKEYUP, WM_TIMER,
+        },
+    },
+};
+
+use crate::{
+    hotkey::{canonical_modifier, toggle_window},
+    state::AppState,
+};
+
+const MAIN_WINDOW_LABEL: &str = "main";
+/// The second tap has to be released within this time after the first one.
+const DOUBLE_TAP_WINDOW: Duration = Duration::from_millis(400);
+/// A modifier held longer than this is a regular key press, not a tap.
+const MAX_TAP_HOLD: Duration = Duration::from_millis(300);
+/// How often the hook thread re-reads `double_tap_modifier` and the snooze state.
+const SYNC_INTERVAL_MS: u32 = 1000;
+
+/// Modifier the hook reacts to, see [`modifier_id`]; 0 while double tap is off.
+static ACTIVE_MODIFIER: AtomicU8 = AtomicU8::new(0);
+static HOOK_THREAD_ID: AtomicU32 = AtomicU32::new(0);
+static APP_HANDLE: OnceLock<AppHandle> = OnceLock::new();
+
+thread_local! {
+    // 钩子回调总在安装钩子的线程上运行，点击状态无需加锁
+    static TRACKER: RefCell<TapTracker> = RefCell::new(TapTracker::default());
+}
+
+#[derive(Debug, Default)]
+struct TapTracker {
+    pressed_at: Option<Instant>,
+    interrupted: bool,
+    last_tap: Option<Instant>,
+}
+
+/// Spawns the thread that owns the low-level keyboard hook behind `double_tap_modifier`.
+///
+/// The hook is only installed while a modifier is configured and the hotkey isn't snoozed;
+/// the thread re-reads both every second, so changes apply without a restart. Two taps of the
+/// modifier with no other key in between toggle the launcher like `global_hotkey` does.
+pub fn spawn_double_tap_hook(app_handle: AppHandle) {
+    if APP_HANDLE.set(app_handle).is_err() {
+        return;
+    }
+
+    let spawned = thread::Builder::new()
+        .name("double-tap-hook".into())
+        .spawn(|| unsafe {
+            HOOK_THREAD_ID.store(GetCurrentThreadId(), Ordering::Release);
+            SetTimer(HWND::default(), 0, SYNC_INTERVAL_MS, None);
+
+            let mut hook: Option<HHOOK> = None;
+            sync_hook(&mut hook);
+
+            let mut msg = MSG::default();
+            // GetMessageW 在收到 WM_QUIT 时返回 0，出错时返回 -1
+            while GetMessageW(&mut msg, HWND::default(), 0, 0).0 > 0 {
+                if msg.message == WM_TIMER {
+                    sync_hook(&mut hook);
+                    continue;
+                }
+                let _ = TranslateMessage(&msg);
+                DispatchMessageW(&msg);
+            }
+
+            if let Some(hook) = hook.take() {
+                let _ = UnhookWindowsHookEx(hook);
+            }
+            HOOK_THREAD_ID.store(0, Ordering::Release);
+            debug!("double-tap hook stopped");
+        });
+
+    if let Err(err) = spawned {
+        warn!("failed to spawn double-tap hook: {err}");
+    }
+}
+
+/// Asks the hook thread to remove the hook and exit, e.g. before the app quits.
+pub fn stop_double_tap_hook() {
+    let thread_id = HOOK_THREAD_ID.load(Ordering::Acquire);
+    if thread_id == 0 {
+        return;
+    }
+    unsafe {
+        let _ = PostThreadMessageW(thread_id, WM_QUIT, WPARAM(0), LPARAM(0));
+    }
+}
+
+/// Checks a `double_tap_modifier` value; only single modifiers are accepted.
+pub fn validate_double_tap_modifier(modifier: &str) -> Result<(), String> {
+    if modifier_id(modifier) == 0 {
+        return Err(format!(
+            "双击修饰键 {modifier} 无效，可选 Ctrl、Shift、Alt 或 Super"
+        ));
+    }
+    Ok(())
+}
+
+/// Installs or removes the hook to match the current settings.
+unsafe fn sync_hook(hook: &mut Option<HHOOK>) {
+    let modifier = configured_modifier();
+    ACTIVE_MODIFIER.store(modifier, Ordering::Release);
+
+    match (modifier != 0, hook.is_some()) {
+        (true, false) => {
+            match SetWindowsHookExW(WH_KEYBOARD_LL, Some(keyboard_proc), HINSTANCE::default(), 0) {
+                Ok(installed) => {
+                    *hook = Some(installed);
+                    info!("双击修饰键唤起已启用");
+                }
+                Err(err) => warn!("failed to install keyboard hook: {err}"),
+            }
+        }
+        (false, true) => {
+            if let Some(installed) = hook.take() {
+                if let Err(err) = UnhookWindowsHookEx(installed) {
+                    warn!("failed to remove keyboard hook: {err}");
+                }
+            }
+            TRACKER.with(|tracker| *tracker.borrow_mut() = TapTracker::default());
+            info!("双击修饰键唤起已关闭");
+        }
+        _ => {}
+    }
+}
+
+/// The configured modifier as a [`modifier_id`], 0 when off or while the hotkey is snoozed.
+fn configured_modifier() -> u8 {
+    let Some(state) = APP_HANDLE
+        .get()
+        .and_then(|app_handle| app_handle.try_state::<AppState>())
+    else {
+        return 0;
+    };
+    if state.hotkey_suspended.load(Ordering::Acquire) {
+        return 0;
+    }
+    state
+        .config
+        .lock()
+        .ok()
+        .and_then(|cfg| cfg.double_tap_modifier.as_deref().map(modifier_id))
+        .unwrap_or(0)
+}
+
+fn modifier_id(name: &str) -> u8 {
+    match canonical_modifier(name.trim()) {
+        Some("ctrl") => 1,
+        Some("shift") => 2,
+        Some("alt") => 3,
+        Some("super") => 4,
+        _ => 0,
+    }
+}
+
+/// Maps the left and right variant of a modifier key to the same [`modifier_id`].
+fn key_modifier_id(vk_code: u32) -> u8 {
+    let vk = vk_code as u16;
+    if vk == VK_LCONTROL.0 || vk == VK_RCONTROL.0 {
+        1
+    } else if vk == VK_LSHIFT.0 || vk == VK_RSHIFT.0 {
+        2
+    } else if vk == VK_LMENU.0 || vk == VK_RMENU.0 {
+        3
+    } else if vk == VK_LWIN.0 || vk == VK_RWIN.0 {
+        4
+    } else {
+        0
+    }
+}
+
+unsafe extern "system" fn keyboard_proc(code: i32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+    if code == HC_ACTION as i32 {
+        let info = &*(lparam.0 as *const KBDLLHOOKSTRUCT);
+        let message = wparam.0 as u32;
+        let is_down = message == WM_KEYDOWN || message == WM_SYSKEYDOWN;
+        let is_up = message == WM_KEYUP || message == WM_SYSKEYUP;
+        if (is_down || is_up)
+            && TRACKER.with(|tracker| {
+                tracker
+                    .borrow_mut()
+                    .on_key(key_modifier_id(info.vkCode), is_down)
+            })
+        {
+            if let Some(app_handle) = APP_HANDLE.get() {
+                // 钩子回调必须尽快返回，窗口操作交给主线程执行
+                let handle = app_handle.clone();
+                let _ = app_handle
+                    .run_on_main_thread(move || toggle_window(&handle, MAIN_WINDOW_LABEL));
+            }
+        }
+    }
+    CallNextHookEx(HHOOK::default(), code, wparam, lparam)
+}
+
+impl TapTracker {
+    /// Feeds one key event and reports whether it completed a double tap.
+    fn on_key(&mut self, modifier: u8, is_down: bool) -> bool {
+        let target = ACTIVE_MODIFIER.load(Ordering::Acquire);
+        if target == 0 {
+            return false;
+        }
+
+        if modifier != target {
+            // 组合键（如 Ctrl+C）中的修饰键不算作一次点击
+            if is_down {
+                self.interrupted = true;
+                self.last_tap = None;
+            }
+            return false;
+        }
+
+        let now = Instant::now();
+        if is_down {
+            // 按住时的自动重复不重新计时
+            if self.pressed_at.is_none() {
+                self.pressed_at = Some(now);
+                self.interrupted = false;
+            }
+            return false;
+        }
+
+        let Some(pressed_at) = self.pressed_at.take() else {
+            return false;
+        };
+        if self.interrupted || now.duration_since(pressed_at) > MAX_TAP_HOLD {
+            self.last_tap = None;
+            return false;
+        }
+        match self.last_tap.take() {
+            Some(previous) if now.duration_since(previous) <= DOUBLE_TAP_WINDOW => true,
+            _ => {
+                self.last_tap = Some(now);
+                false
+            }
+        }
+    }
+}
@@ -0,0 +1,92 @@
+use std::{
+    collections::HashSet,
+    time::{Duration, Instant},
+};
+
+use windows::Win32::Storage::FileSystem::GetLogicalDrives;
+
+/// How long a drive snapshot is reused; reconnecting a drive shows up after at most this long.
+const SNAPSHOT_TTL: Duration = Duration::from_secs(5);
+
+/// Caches the available drive letters briefly so queries don't ask the OS on every keystroke.
+#[derive(Debug, Default)]
+pub struct DriveCache {
+    taken_at: Option<Instant>,
+    letters: HashSet<char>,
+}
+
+impl DriveCache {
+    /// Upper-case letters of the drives that are currently mounted, refreshed when stale.
+    pub fn available_letters(&mut self) -> HashSet<char> {
+        let fresh = self
+            .taken_at
+            .is_some_and(|taken_at| taken_at.elapsed() < SNAPSHOT_TTL);
+        if !fresh {
+            self.letters = available_drive_letters();
+            self.taken_at = Some(Instant::now());
+        }
+        self.letters.clone()
+    }
+}
+
+fn available_drive_letters() -> HashSet<char> {
+    // 只读取位掩码，不会访问驱动器本身
+    letters_from_mask(unsafe { GetLogicalDrives() })
+}
+
+/// Drive letters set in a `GetLogicalDrives` mask; bit 0 is `A:`.
+fn letters_from_mask(mask: u32) -> HashSet<char> {
+    (0..26u8)
+        .filter(|bit| mask & (1 << bit) != 0)
+        .map(|bit| char::from(b'A' + bit))
+        .collect()
+}
+
+/// Upper-case drive letter of an absolute `X:\...` path; `None` for UNC paths, URLs and ids.
+pub fn drive_letter(path: &str) -> Option<char> {
+    let mut chars = path.trim().trim_start_matches('"').chars();
+    let letter = chars.next()?;
+    (letter.is_ascii_alphabetic() && chars.next() == Some(':')).then(|| letter.to_ascii_uppercase())
+}
+
+/// Whether `path` lives on a drive letter that isn't in `available`.
+pub fn on_unavailable_drive(path: &str, available: &HashSet<char>) -> bool {
+    drive_letter(path).is_some_and(|letter| !available.contains(&letter))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn drives(letters: &str) -> HashSet<char> {
+        letters.chars().collect()
+    }
+
+    #[test]
+    fn mask_bits_map_to_drive_letters() {
+        assert_eq!(letters_from_mask(0b1101), drives("ACD"));
+        assert_eq!(letters_from_mask(1 << 25), drives("Z"));
+        // 高于 Z: 的位不对应驱动器
+        assert!(letters_from_mask(1 << 26).is_empty());
+    }
+
+    #[test]
+    fn drive_letter_reads_absolute_paths_only() {
+        assert_eq!(drive_letter(r"e:\Games\game.exe"), Some('E'));
+        assert_eq!(drive_letter(r#""D:\Tools\tool.exe" --flag"#), Some('D'));
+        assert_eq!(drive_letter(r"\\server\share\app.exe"), None);
+        assert_eq!(drive_letter("https://example.com"), None);
+        assert_eq!(
+            drive_letter("Microsoft.WindowsCalculator_8wekyb3d8bbwe!App"),
+            None
+        );
+    }
+
+    #[test]
+    fn only_paths_on_missing_drives_are_unavailable() {
+        let available = drives("CD");
+        assert!(on_unavailable_drive(r"E:\Games\game.exe", &available));
+        assert!(!on_unavailable_drive(r"c:\Apps\app.exe", &available));
+        assert!(!on_unavailable_drive(r"\\server\share\app.exe", &available));
+    }
+}
@@ -0,0 +1,106 @@
+use serde::{ser::SerializeStruct, Serialize, Serializer};
+use thiserror::Error;
+
+/// Error returned by every command. Serializes as `{ code, message }` so the frontend can
+/// branch on the stable `code` and show its own localized text, falling back to `message`.
+#[derive(Debug, Error)]
+pub enum AppError {
+    /// The settings file or its directory can't be read or written.
+    #[error("配置读写失败: {0}")]
+    ConfigIo(String),
+    /// The accelerator can't be parsed or registered.
+    #[error("快捷键无效: {0}")]
+    HotkeyInvalid(String),
+    /// The result id is unknown, usually because a newer query replaced the result set.
+    #[error("结果已失效，请重新搜索")]
+    ActionExpired,
+    /// Starting or opening a target failed.
+    #[error("执行失败: {0}")]
+    LaunchFailed(String),
+    /// Activating a UWP app failed.
+    #[error("UWP 应用启动失败: {0}")]
+    Uwp(String),
+    /// The user declined the UAC prompt of an elevated launch.
+    #[error("已取消以管理员身份运行")]
+    ElevationCancelled,
+    /// The requested variant of an action doesn't exist for this kind of result.
+    #[error("{0}")]
+    NotApplicable(String),
+    /// A settings value was rejected.
+    #[error("{0}")]
+    InvalidSetting(String),
+    /// Anything else, e.g. a poisoned lock or a failed background task.
+    #[error("{0}")]
+    Internal(String),
+}
+
+impl AppError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            AppError::ConfigIo(_) => "config_io",
+            AppError::HotkeyInvalid(_) => "hotkey_invalid",
+            AppError::ActionExpired => "action_expired",
+            AppError::LaunchFailed(_) => "launch_failed",
+            AppError::Uwp(_) => "uwp",
+            AppError::ElevationCancelled => "elevation_cancelled",
+            AppError::NotApplicable(_) => "not_applicable",
+            AppError::InvalidSetting(_) => "invalid_setting",
+            AppError::Internal(_) => "internal",
+        }
+    }
+}
+
+impl From<String> for AppError {
+    fn from(message: String) -> Self {
+        AppError::Internal(message)
+    }
+}
+
+impl From<&str> for AppError {
+    fn from(message: &str) -> Self {
+        AppError::Internal(message.to_string())
+    }
+}
+
+impl Serialize for AppError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut error = serializer.serialize_struct("AppError", 2)?;
+        error.serialize_field("code", self.code())?;
+        error.serialize_field("message", &self.to_string())?;
+        error.end()
+    }
+}
+
+pub type AppResult<T> = Result<T, AppError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_variant_serializes_with_its_code_and_message() {
+        let cases = [
+            (AppError::ConfigIo("x".into()), "config_io"),
+            (AppError::HotkeyInvalid("x".into()), "hotkey_invalid"),
+            (AppError::ActionExpired, "action_expired"),
+            (AppError::LaunchFailed("x".into()), "launch_failed"),
+            (AppError::Uwp("x".into()), "uwp"),
+            (AppError::ElevationCancelled, "elevation_cancelled"),
+            (AppError::NotApplicable("x".into()), "not_applicable"),
+            (AppError::InvalidSetting("x".into()), "invalid_setting"),
+            (AppError::Internal("x".into()), "internal"),
+        ];
+        for (error, code) in cases {
+            let value = serde_json::to_value(&error).unwrap();
+            assert_eq!(value["code"], code);
+            assert_eq!(value["message"], error.to_string());
+        }
+    }
+
+    #[test]
+    fn plain_strings_become_internal_errors() {
+        let error = AppError::from("无法获取配置".to_string());
+        assert_eq!(error.code(), "internal");
+        assert_eq!(error.to_string(), "无法获取配置");
+    }
+}
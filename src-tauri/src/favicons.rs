@@ -0,0 +1,280 @@
+use std::{
+    collections::HashSet,
+    env, fs,
+    net::{TcpStream, ToSocketAddrs},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    thread,
+    time::{Duration, SystemTime},
+};
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use log::{debug, warn};
+use sha1::{Digest, Sha1};
+use windows::{
+    core::{IUnknown, HSTRING},
+    Win32::System::Com::{IBindStatusCallback, Urlmon::URLDownloadToFileW},
+};
+
+use crate::{bookmarks::BookmarkEntry, config::AppConfig};
+
+const FAVICON_SERVICE_HOST: &str = "www.google.com";
+/// Upper bound on favicon downloads per minute so the refresher never floods the network.
+const FAVICON_FETCHES_PER_MINUTE: u64 = 4;
+const IDLE_INTERVAL: Duration = Duration::from_secs(10 * 60);
+const OFFLINE_RETRY_INTERVAL: Duration = Duration::from_secs(5 * 60);
+const REACHABILITY_TIMEOUT: Duration = Duration::from_secs(3);
+/// Wait before a host whose favicon couldn't be fetched is tried again; doubles with every
+/// further failure, up to `favicon_max_age_hours`.
+const FAILED_RETRY_BASE: Duration = Duration::from_secs(60 * 60);
+/// Cap on the backoff exponent, so the doubling can't overflow.
+const MAX_BACKOFF_DOUBLINGS: u32 = 16;
+
+/// Fills in cached favicons for freshly loaded bookmarks without touching the network.
+pub fn apply_cached_favicons(entries: &mut [BookmarkEntry]) {
+    for entry in entries.iter_mut() {
+        if let Some(host) = url_host(&entry.url) {
+            if let Some(icon) = load_cached_favicon(&host) {
+                entry.icon_b64 = icon;
+            }
+        }
+    }
+}
+
+/// Spawns the background refresher that re-fetches missing or stale favicons.
+///
+/// The task re-reads the config on every step, so toggling `favicon_refresh_enabled`
+/// takes effect without a restart. Hosts whose favicon can't be fetched are skipped for a
+/// growing backoff instead of being retried on every cycle.
+pub fn spawn_favicon_refresher(
+    bookmark_index: Arc<Mutex<Vec<BookmarkEntry>>>,
+    config: Arc<Mutex<AppConfig>>,
+) {
+    let spawned = thread::Builder::new()
+        .name("favicon-refresher".into())
+        .spawn(move || loop {
+            let Some(max_age) = refresh_settings(&config) else {
+                thread::sleep(IDLE_INTERVAL);
+                continue;
+            };
+
+            if !is_network_reachable() {
+                debug!("network unreachable, postponing favicon refresh");
+                thread::sleep(OFFLINE_RETRY_INTERVAL);
+                continue;
+            }
+
+            for host in hosts_needing_refresh(&bookmark_index, max_age) {
+                if refresh_settings(&config).is_none() {
+                    break;
+                }
+
+                match fetch_favicon(&host) {
+                    Some(icon) => {
+                        store_cached_favicon(&host, &icon);
+                        clear_fetch_failures(&host);
+                        update_index_icons(&bookmark_index, &host, &icon);
+                    }
+                    None => {
+                        debug!("failed to fetch favicon for {host}");
+                        record_fetch_failure(&host);
+                    }
+                }
+
+                thread::sleep(Duration::from_secs(60 / FAVICON_FETCHES_PER_MINUTE));
+            }
+
+            thread::sleep(IDLE_INTERVAL);
+        });
+
+    if let Err(err) = spawned {
+        warn!("failed to spawn favicon refresher: {err}");
+    }
+}
+
+/// Returns the configured maximum icon age when the refresher is enabled.
+fn refresh_settings(config: &Mutex<AppConfig>) -> Option<Duration> {
+    let guard = config.lock().ok()?;
+    if !guard.favicon_refresh_enabled {
+        return None;
+    }
+    Some(Duration::from_secs(
+        guard.favicon_max_age_hours.saturating_mul(60 * 60),
+    ))
+}
+
+fn hosts_needing_refresh(
+    bookmark_index: &Mutex<Vec<BookmarkEntry>>,
+    max_age: Duration,
+) -> Vec<String> {
+    let urls: Vec<String> = match bookmark_index.lock() {
+        Ok(guard) => guard.iter().map(|entry| entry.url.clone()).collect(),
+        Err(_) => return Vec::new(),
+    };
+
+    let mut seen = HashSet::new();
+    urls.iter()
+        .filter_map(|url| url_host(url))
+        .filter(|host| seen.insert(host.clone()))
+        .filter(|host| {
+            cached_favicon_age(host)
+                .map(|age| age >= max_age)
+                .unwrap_or(true)
+        })
+        .filter(|host| !in_failure_backoff(host, max_age))
+        .collect()
+}
+
+/// Whether `host` failed to fetch recently enough that it should be skipped for now.
+fn in_failure_backoff(host: &str, max_age: Duration) -> bool {
+    let Some((failures, age)) = fetch_failures(host) else {
+        return false;
+    };
+    let doublings = failures.saturating_sub(1).min(MAX_BACKOFF_DOUBLINGS);
+    let wait = FAILED_RETRY_BASE
+        .saturating_mul(1 << doublings)
+        .min(max_age.max(FAILED_RETRY_BASE));
+    age < wait
+}
+
+/// Number of consecutive failed fetches for `host` and how long ago the last one was.
+fn fetch_failures(host: &str) -> Option<(u32, Duration)> {
+    let path = failure_file_path(host)?;
+    let failures = fs::read_to_string(&path).ok()?.trim().parse().ok()?;
+    let modified = fs::metadata(path).ok()?.modified().ok()?;
+    let age = SystemTime::now()
+        .duration_since(modified)
+        .unwrap_or_default();
+    Some((failures, age))
+}
+
+/// Counts a failed fetch; the file's modification time doubles as the backoff timestamp.
+fn record_fetch_failure(host: &str) {
+    let failures = fetch_failures(host).map_or(0, |(failures, _)| failures);
+    if let Some(path) = failure_file_path(host) {
+        if let Some(parent) = path.parent() {
+            if fs::create_dir_all(parent).is_err() {
+                return;
+            }
+        }
+        let _ = fs::write(path, failures.saturating_add(1).to_string());
+    }
+}
+
+fn clear_fetch_failures(host: &str) {
+    if let Some(path) = failure_file_path(host) {
+        let _ = fs::remove_file(path);
+    }
+}
+
+fn update_index_icons(bookmark_index: &Mutex<Vec<BookmarkEntry>>, host: &str, icon: &str) {
+    let Ok(mut guard) = bookmark_index.lock() else {
+        return;
+    };
+    for entry in guard.iter_mut() {
+        if url_host(&entry.url).as_deref() == Some(host) {
+            entry.icon_b64 = icon.to_string();
+        }
+    }
+}
+
+fn is_network_reachable() -> bool {
+    let Ok(mut addrs) = (FAVICON_SERVICE_HOST, 443).to_socket_addrs() else {
+        return false;
+    };
+    addrs.any(|addr| TcpStream::connect_timeout(&addr, REACHABILITY_TIMEOUT).is_ok())
+}
+
+/// Downloads the favicon of `host` as PNG and returns it base64 encoded.
+fn fetch_favicon(host: &str) -> Option<String> {
+    let url = format!(
+        "https://{FAVICON_SERVICE_HOST}/s2/favicons?domain={}&sz=64",
+        urlencoding::encode(host)
+    );
+    let temp_path = env::temp_dir().join(format!("rustlauncher-favicon-{}.png", cache_key(host)));
+
+    let downloaded = unsafe {
+        URLDownloadToFileW(
+            None::<&IUnknown>,
+            &HSTRING::from(url.as_str()),
+            &HSTRING::from(temp_path.as_os_str()),
+            0,
+            None::<&IBindStatusCallback>,
+        )
+    };
+    if let Err(err) = downloaded {
+        debug!("favicon download for {host} failed: {err}");
+        return None;
+    }
+
+    let bytes = fs::read(&temp_path).ok();
+    let _ = fs::remove_file(&temp_path);
+    bytes
+        .filter(|data| !data.is_empty())
+        .map(|data| BASE64.encode(data))
+}
+
+fn load_cached_favicon(host: &str) -> Option<String> {
+    let path = cache_file_path(host)?;
+    fs::read_to_string(path)
+        .ok()
+        .filter(|data| !data.is_empty())
+}
+
+fn cached_favicon_age(host: &str) -> Option<Duration> {
+    let path = cache_file_path(host)?;
+    let modified = fs::metadata(path).ok()?.modified().ok()?;
+    SystemTime::now().duration_since(modified).ok()
+}
+
+fn store_cached_favicon(host: &str, data: &str) {
+    if let Some(path) = cache_file_path(host) {
+        if let Some(parent) = path.parent() {
+            if fs::create_dir_all(parent).is_err() {
+                return;
+            }
+        }
+        let _ = fs::write(path, data);
+    }
+}
+
+fn cache_file_path(host: &str) -> Option<PathBuf> {
+    let mut dir = favicon_cache_dir()?;
+    dir.push(format!("{}.b64", cache_key(host)));
+    Some(dir)
+}
+
+fn failure_file_path(host: &str) -> Option<PathBuf> {
+    let mut dir = favicon_cache_dir()?;
+    dir.push(format!("{}.failed", cache_key(host)));
+    Some(dir)
+}
+
+pub(crate) fn favicon_cache_dir() -> Option<PathBuf> {
+    let base = env::var("LOCALAPPDATA").ok()?;
+    Some(Path::new(&base).join("RustLauncher").join("favicons"))
+}
+
+fn cache_key(host: &str) -> String {
+    let mut hasher = Sha1::new();
+    hasher.update(host.as_bytes());
+    hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+/// Extracts the lowercase host of an http(s) URL.
+pub(crate) fn url_host(url: &str) -> Option<String> {
+    let rest = url
+        .strip_prefix("https://")
+        .or_else(|| url.strip_prefix("http://"))?;
+    let authority = rest.split(['/', '?', '#']).next()?;
+    let host = authority.rsplit('@').next()?.split(':').next()?;
+    if host.is_empty() {
+        None
+    } else {
+        Some(host.to_ascii_lowercase())
+    }
+}
@@ -0,0 +1,150 @@
+use std::{
+    collections::VecDeque,
+    env, fs,
+    os::windows::fs::MetadataExt,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+/// Folders under the user profile searched when `file_search_roots` is empty.
+const DEFAULT_ROOT_FOLDERS: [&str; 3] = ["Desktop", "Documents", "Downloads"];
+/// A scan stops after this long and ranks what it found so far.
+const SCAN_TIMEOUT: Duration = Duration::from_millis(1500);
+/// Directory entries visited per query at most, so a huge tree can't stall the search.
+const MAX_SCANNED_ENTRIES: usize = 200_000;
+/// Matches collected per requested result before the scan stops and ranks them.
+const CANDIDATES_PER_RESULT: usize = 5;
+const FILE_ATTRIBUTE_HIDDEN: u32 = 0x2;
+const FILE_ATTRIBUTE_SYSTEM: u32 = 0x4;
+
+#[derive(Debug, Clone)]
+pub struct FileMatch {
+    pub path: PathBuf,
+    pub is_dir: bool,
+    score: i64,
+}
+
+/// The configured roots, or the user's Desktop, Documents and Downloads when none are set.
+/// Roots that are not existing directories are skipped.
+pub fn search_roots(configured: &[String]) -> Vec<PathBuf> {
+    let roots: Vec<PathBuf> = if configured.iter().any(|root| !root.trim().is_empty()) {
+        configured
+            .iter()
+            .map(|root| root.trim())
+            .filter(|root| !root.is_empty())
+            .map(PathBuf::from)
+            .collect()
+    } else {
+        let Some(profile) = env::var_os("USERPROFILE").map(PathBuf::from) else {
+            return Vec::new();
+        };
+        DEFAULT_ROOT_FOLDERS
+            .iter()
+            .map(|folder| profile.join(folder))
+            .collect()
+    };
+    roots.into_iter().filter(|root| root.is_dir()).collect()
+}
+
+/// Walks `roots` breadth-first for files and folders whose name contains every
+/// whitespace-separated term of `query`, returning the best `max_results` of them.
+///
+/// Hidden and system entries are skipped. The walk is abandoned with an error when `ticket`
+/// moves past `expected_ticket`, i.e. a newer query has started.
+pub fn search_files(
+    roots: &[PathBuf],
+    query: &str,
+    max_results: usize,
+    ticket: &Arc<AtomicU64>,
+    expected_ticket: u64,
+) -> Result<Vec<FileMatch>, String> {
+    let terms: Vec<String> = query.split_whitespace().map(str::to_lowercase).collect();
+    if terms.is_empty() || max_results == 0 {
+        return Ok(Vec::new());
+    }
+
+    let deadline = Instant::now() + SCAN_TIMEOUT;
+    let candidate_limit = max_results.saturating_mul(CANDIDATES_PER_RESULT);
+    let mut queue: VecDeque<PathBuf> = roots.iter().cloned().collect();
+    let mut matches = Vec::new();
+    let mut scanned = 0usize;
+
+    'walk: while let Some(dir) = queue.pop_front() {
+        if ticket.load(Ordering::Acquire) != expected_ticket {
+            return Err("文件搜索已被新的查询取代".into());
+        }
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            scanned += 1;
+            if scanned > MAX_SCANNED_ENTRIES || Instant::now() >= deadline {
+                log::debug!(
+                    "文件搜索达到扫描上限，返回已找到的 {} 条结果",
+                    matches.len()
+                );
+                break 'walk;
+            }
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+            if metadata.file_attributes() & (FILE_ATTRIBUTE_HIDDEN | FILE_ATTRIBUTE_SYSTEM) != 0 {
+                continue;
+            }
+            let path = entry.path();
+            // 符号链接与目录联接只作为结果，不继续深入，避免循环
+            let is_dir = metadata.is_dir();
+            if is_dir && !metadata.file_type().is_symlink() {
+                queue.push_back(path.clone());
+            }
+            if let Some(score) = score_name(&entry.file_name().to_string_lossy(), &terms) {
+                matches.push(FileMatch {
+                    path,
+                    is_dir,
+                    score,
+                });
+                if matches.len() >= candidate_limit {
+                    break 'walk;
+                }
+            }
+        }
+    }
+
+    // 同分时路径越短越靠前，通常更接近用户常用的位置
+    matches.sort_by(|a, b| {
+        b.score
+            .cmp(&a.score)
+            .then_with(|| path_depth(&a.path).cmp(&path_depth(&b.path)))
+    });
+    matches.truncate(max_results);
+    Ok(matches)
+}
+
+/// Scores a file name against lowercase `terms`: an exact name beats a prefix, which beats
+/// a plain substring. `None` when any term is missing.
+fn score_name(name: &str, terms: &[String]) -> Option<i64> {
+    let name = name.to_lowercase();
+    let stem = name
+        .rsplit_once('.')
+        .map_or(name.as_str(), |(stem, _)| stem);
+    if !terms.iter().all(|term| name.contains(term.as_str())) {
+        return None;
+    }
+    let first = terms[0].as_str();
+    let score = if terms.len() == 1 && (name == first || stem == first) {
+        300
+    } else if name.starts_with(first) {
+        200
+    } else {
+        100
+    };
+    Some(score)
+}
+
+fn path_depth(path: &Path) -> usize {
+    path.components().count()
+}
@@ -0,0 +1,166 @@
+use std::{
+    collections::HashMap,
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+use log::{debug, warn};
+use rusqlite::{Connection, OpenFlags};
+
+use crate::text_utils::extend_keywords_with_pinyin;
+
+#[derive(Debug, Clone)]
+pub struct HistoryEntry {
+    pub id: String,
+    pub title: String,
+    pub url: String,
+    pub visit_count: i64,
+    /// Chromium timestamp (microseconds since 1601-01-01), only used for ordering.
+    pub last_visit_time: i64,
+    pub keywords: Vec<String>,
+}
+
+/// Browsers whose Chromium `History` database is read, as `(label, path under LOCALAPPDATA)`.
+const CHROMIUM_BROWSERS: &[(&str, &[&str])] = &[
+    ("Chrome", &["Google", "Chrome", "User Data"]),
+    ("Edge", &["Microsoft", "Edge", "User Data"]),
+];
+
+/// Loads the `limit` most visited URLs across all Chrome/Edge profiles.
+///
+/// Each database is copied to the temp directory first because the browser keeps it locked
+/// while running.
+pub fn load_browser_history(limit: usize) -> Vec<HistoryEntry> {
+    let mut by_url: HashMap<String, HistoryEntry> = HashMap::new();
+
+    for (browser, profile_dir) in history_profile_dirs() {
+        let history_path = profile_dir.join("History");
+        match read_history_file(&history_path, browser, limit) {
+            Ok(entries) => {
+                for entry in entries {
+                    match by_url.get(&entry.url) {
+                        Some(existing) if existing.visit_count >= entry.visit_count => {}
+                        _ => {
+                            by_url.insert(entry.url.clone(), entry);
+                        }
+                    }
+                }
+            }
+            Err(err) => warn!("failed to read browser history {:?}: {err}", history_path),
+        }
+    }
+
+    let mut entries: Vec<HistoryEntry> = by_url.into_values().collect();
+    entries.sort_by(|a, b| {
+        b.visit_count
+            .cmp(&a.visit_count)
+            .then(b.last_visit_time.cmp(&a.last_visit_time))
+    });
+    entries.truncate(limit);
+    debug!("loaded {} browser history entries", entries.len());
+    entries
+}
+
+fn history_profile_dirs() -> Vec<(&'static str, PathBuf)> {
+    let mut results = Vec::new();
+    let Ok(local_app_data) = env::var("LOCALAPPDATA") else {
+        return results;
+    };
+
+    for (browser, segments) in CHROMIUM_BROWSERS {
+        let base_path = segments
+            .iter()
+            .fold(PathBuf::from(&local_app_data), |path, segment| {
+                path.join(segment)
+            });
+        let Ok(entries) = fs::read_dir(&base_path) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() && path.join("History").is_file() {
+                results.push((*browser, path));
+            }
+        }
+    }
+
+    results
+}
+
+fn read_history_file(
+    path: &Path,
+    browser: &str,
+    limit: usize,
+) -> Result<Vec<HistoryEntry>, String> {
+    let profile = path
+        .parent()
+        .and_then(|dir| dir.file_name())
+        .and_then(|name| name.to_str())
+        .unwrap_or_default()
+        .to_string();
+    let temp_path = env::temp_dir().join(format!(
+        "rustlauncher-history-{}-{}.sqlite",
+        browser.to_lowercase(),
+        profile.replace(' ', "_")
+    ));
+    fs::copy(path, &temp_path).map_err(|err| err.to_string())?;
+
+    let result = query_history(&temp_path, browser, &profile, limit);
+    let _ = fs::remove_file(&temp_path);
+    result
+}
+
+fn query_history(
+    path: &Path,
+    browser: &str,
+    profile: &str,
+    limit: usize,
+) -> Result<Vec<HistoryEntry>, String> {
+    let connection = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+        .map_err(|err| err.to_string())?;
+    let mut statement = connection
+        .prepare(
+            "SELECT url, title, visit_count, last_visit_time FROM urls \
+             WHERE hidden = 0 ORDER BY visit_count DESC, last_visit_time DESC LIMIT ?1",
+        )
+        .map_err(|err| err.to_string())?;
+
+    let rows = statement
+        .query_map([limit as i64], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, Option<String>>(1)?,
+                row.get::<_, i64>(2)?,
+                row.get::<_, i64>(3)?,
+            ))
+        })
+        .map_err(|err| err.to_string())?;
+
+    let mut entries = Vec::new();
+    for (url, title, visit_count, last_visit_time) in rows.flatten() {
+        let url = url.trim().to_string();
+        if !(url.starts_with("http://") || url.starts_with("https://")) {
+            continue;
+        }
+        let title = title
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+            .unwrap_or_else(|| url.clone());
+
+        let mut keywords = vec![title.clone(), url.clone()];
+        extend_keywords_with_pinyin(&mut keywords);
+        keywords.sort();
+        keywords.dedup();
+
+        entries.push(HistoryEntry {
+            id: format!("{browser}:{profile}:{url}"),
+            title,
+            url,
+            visit_count,
+            last_visit_time,
+            keywords,
+        });
+    }
+
+    Ok(entries)
+}
@@ -1,50 +1,531 @@
+use std::{sync::atomic::Ordering, thread, time::Duration};
+
+use serde::Serialize;
 use tauri::{AppHandle, Manager};
-use tauri_plugin_global_shortcut::{GlobalShortcutExt, ShortcutState};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
+
+use crate::{
+    config::ModeHotkey,
+    error::{AppError, AppResult},
+    search::QueryMode,
+    show_window, show_window_in_mode,
+    state::AppState,
+    tray,
+};
+
+const MAIN_WINDOW_LABEL: &str = "main";
+/// Accepted modifier names, lowercase, each with the canonical modifier it stands for so
+/// aliases like `Ctrl` and `Control` count as the same modifier.
+const MODIFIERS: &[(&str, &str)] = &[
+    ("ctrl", "ctrl"),
+    ("control", "ctrl"),
+    ("commandorcontrol", "ctrl"),
+    ("commandorctrl", "ctrl"),
+    ("cmdorctrl", "ctrl"),
+    ("cmdorcontrol", "ctrl"),
+    ("shift", "shift"),
+    ("alt", "alt"),
+    ("option", "alt"),
+    ("super", "super"),
+    ("cmd", "super"),
+    ("command", "super"),
+    ("meta", "super"),
+    ("win", "super"),
+];
+
+/// The configured launcher hotkey and whether it is currently registered with the OS.
+#[derive(Debug, Clone, Serialize)]
+pub struct RegisteredShortcuts {
+    pub hotkey: Option<String>,
+    /// The mode hotkeys bound next to it; suspended together with it.
+    pub mode_hotkeys: Vec<ModeHotkey>,
+    /// False while the hotkey is suspended via `disable_hotkey`.
+    pub enabled: bool,
+}
+
+/// Checks an accelerator such as `Alt+Space` before it is registered: any number of distinct
+/// modifiers followed by exactly one key, joined with `+`. The error names the part at fault.
+pub fn validate_hotkey(hotkey: &str) -> Result<(), String> {
+    let hotkey = hotkey.trim();
+    if hotkey.is_empty() {
+        return Err("快捷键不能为空".into());
+    }
+
+    let parts: Vec<&str> = hotkey.split('+').map(str::trim).collect();
+    if parts.iter().any(|part| part.is_empty()) {
+        return Err(format!("快捷键 {hotkey} 中有空的按键"));
+    }
+    let (key, modifiers) = parts.split_last().ok_or("快捷键不能为空")?;
+
+    let mut seen: Vec<&str> = Vec::new();
+    for modifier in modifiers {
+        let Some(canonical) = canonical_modifier(modifier) else {
+            return Err(format!("未知的修饰键 {modifier}"));
+        };
+        if seen.contains(&canonical) {
+            return Err(format!("修饰键 {modifier} 重复"));
+        }
+        seen.push(canonical);
+    }
+    if canonical_modifier(key).is_some() {
+        return Err(format!("快捷键 {hotkey} 缺少主键，不能只有修饰键"));
+    }
+
+    hotkey
+        .parse::<Shortcut>()
+        .map(|_| ())
+        .map_err(|err| format!("无法识别的按键 {key}: {err}"))
+}
+
+/// The canonical name (`ctrl`, `shift`, `alt` or `super`) of a modifier or one of its aliases.
+pub(crate) fn canonical_modifier(name: &str) -> Option<&'static str> {
+    let lower = name.to_ascii_lowercase();
+    MODIFIERS
+        .iter()
+        .find(|(alias, _)| *alias == lower)
+        .map(|(_, canonical)| *canonical)
+}
+
+/// Checks every binding with [`validate_hotkey`] and rejects accelerators bound twice or equal
+/// to `main_hotkey`.
+pub fn check_mode_hotkeys(
+    bindings: &[ModeHotkey],
+    main_hotkey: Option<&str>,
+) -> Result<(), String> {
+    let main = main_hotkey.and_then(|hotkey| hotkey.trim().parse::<Shortcut>().ok());
+    let mut seen: Vec<Shortcut> = Vec::new();
+    for binding in bindings {
+        validate_hotkey(&binding.hotkey)?;
+        let hotkey = binding.hotkey.trim();
+        let Ok(shortcut) = hotkey.parse::<Shortcut>() else {
+            continue;
+        };
+        if main == Some(shortcut) {
+            return Err(format!("快捷键 {hotkey} 与呼出快捷键相同"));
+        }
+        if seen.contains(&shortcut) {
+            return Err(format!("快捷键 {hotkey} 被绑定了多次"));
+        }
+        seen.push(shortcut);
+    }
+    Ok(())
+}
+
+/// Replaces the registered mode hotkeys with `bindings`. When one can't be registered, e.g.
+/// because another program owns it, the previous bindings are restored and the error names
+/// the failing hotkey. While snoozed the bindings are only recorded.
+pub fn bind_mode_hotkeys(
+    app_handle: &AppHandle,
+    state: &AppState,
+    bindings: &[ModeHotkey],
+) -> AppResult<()> {
+    let main_hotkey = state
+        .registered_hotkey
+        .lock()
+        .map_err(|_| "无法获取快捷键状态".to_string())?
+        .clone();
+    check_mode_hotkeys(bindings, main_hotkey.as_deref()).map_err(AppError::HotkeyInvalid)?;
 
-use crate::{show_window, state::AppState};
+    let mut current = state
+        .registered_mode_hotkeys
+        .lock()
+        .map_err(|_| "无法获取快捷键状态".to_string())?;
+    if !state.hotkey_suspended.load(Ordering::Acquire) {
+        unregister_mode_hotkeys(app_handle, &current);
+        if let Err(err) = register_mode_hotkeys(app_handle, bindings) {
+            if let Err(restore_err) = register_mode_hotkeys(app_handle, &current) {
+                log::warn!("failed to restore previous mode hotkeys: {restore_err}");
+            }
+            return Err(err);
+        }
+    }
+    *current = bindings.to_vec();
+    Ok(())
+}
+
+/// Registers `bindings` in order; on the first failure the ones already registered are
+/// released again.
+fn register_mode_hotkeys(app_handle: &AppHandle, bindings: &[ModeHotkey]) -> AppResult<()> {
+    for (index, binding) in bindings.iter().enumerate() {
+        if let Err(err) = register_mode_shortcut(app_handle, binding) {
+            unregister_mode_hotkeys(app_handle, &bindings[..index]);
+            return Err(err);
+        }
+    }
+    Ok(())
+}
+
+fn unregister_mode_hotkeys(app_handle: &AppHandle, bindings: &[ModeHotkey]) {
+    for binding in bindings {
+        let hotkey = binding.hotkey.trim();
+        if let Err(err) = app_handle.global_shortcut().unregister(hotkey) {
+            log::warn!("failed to unregister mode hotkey {hotkey}: {err}");
+        }
+    }
+}
+
+fn register_mode_shortcut(app_handle: &AppHandle, binding: &ModeHotkey) -> AppResult<()> {
+    let hotkey = binding.hotkey.trim();
+    let mode = QueryMode::from_option(Some(binding.mode.clone()));
+    app_handle
+        .global_shortcut()
+        .on_shortcut(hotkey, move |app_handle, _, event| {
+            if event.state == ShortcutState::Pressed {
+                // 与呼出快捷键相同，每次按下都是一次新的唤起
+                app_handle
+                    .state::<AppState>()
+                    .english_forced_this_activation
+                    .store(false, Ordering::Release);
+                show_window_in_mode(app_handle, mode);
+            }
+        })
+        .map_err(|err| AppError::HotkeyInvalid(format!("无法注册 {hotkey}: {err}")))
+}
 
 pub fn bind_hotkey(
     app_handle: &AppHandle,
     state: &AppState,
     hotkey: &str,
     window_label: &str,
-) -> Result<(), String> {
-    if hotkey.trim().is_empty() {
-        return Err("快捷键不能为空".into());
-    }
+) -> AppResult<()> {
+    validate_hotkey(hotkey).map_err(AppError::HotkeyInvalid)?;
 
     let mut current_hotkey = state
         .registered_hotkey
         .lock()
         .map_err(|_| "无法获取快捷键状态".to_string())?;
 
+    let hotkey_string = hotkey.trim().to_string();
+    // 暂停期间只记录新的快捷键，恢复时再注册
+    if state.hotkey_suspended.load(Ordering::Acquire) {
+        *current_hotkey = Some(hotkey_string);
+        return Ok(());
+    }
+
     if let Some(previous) = current_hotkey.as_deref() {
         if let Err(err) = app_handle.global_shortcut().unregister(previous) {
             log::warn!("failed to unregister previous hotkey {previous}: {err}");
         }
     }
 
-    let hotkey_string = hotkey.trim().to_string();
-    let shortcut_literal = hotkey_string.clone();
+    register_shortcut(app_handle, &hotkey_string, window_label)?;
+    *current_hotkey = Some(hotkey_string);
+    Ok(())
+}
+
+fn register_shortcut(app_handle: &AppHandle, hotkey: &str, window_label: &str) -> AppResult<()> {
     let window_label_string = window_label.to_string();
     app_handle
         .global_shortcut()
-        .on_shortcut(shortcut_literal.as_str(), {
+        .on_shortcut(hotkey, {
             let window_label = window_label_string;
             move |app_handle, _, event| {
                 if event.state == ShortcutState::Pressed {
-                    if let Some(window) = app_handle.get_webview_window(&window_label) {
-                        if window.is_visible().unwrap_or(false) {
-                            let _ = window.hide();
-                        } else {
-                            show_window(app_handle);
-                        }
-                    }
+                    toggle_window(app_handle, &window_label);
                 }
             }
         })
-        .map_err(|err| err.to_string())?;
+        .map_err(|err| AppError::HotkeyInvalid(format!("无法注册 {hotkey}: {err}")))
+}
+
+/// Hides the window when it is visible and shows it otherwise, as the launcher hotkey does.
+pub(crate) fn toggle_window(app_handle: &AppHandle, window_label: &str) {
+    let Some(window) = app_handle.get_webview_window(window_label) else {
+        return;
+    };
+    if window.is_visible().unwrap_or(false) {
+        let _ = window.hide();
+    } else {
+        // 每次按下快捷键都是一次新的唤起，允许再次切换为英文输入
+        app_handle
+            .state::<AppState>()
+            .english_forced_this_activation
+            .store(false, Ordering::Release);
+        show_window(app_handle);
+    }
+}
+
+/// Unregisters the launcher hotkey while keeping the configured accelerator, so
+/// [`resume_hotkey`] can bind exactly the same one again. With `duration` the hotkey comes
+/// back on its own unless it was resumed or suspended again in the meantime.
+pub fn suspend_hotkey(
+    app_handle: &AppHandle,
+    state: &AppState,
+    duration: Option<Duration>,
+) -> AppResult<()> {
+    let generation = state
+        .hotkey_snooze_generation
+        .fetch_add(1, Ordering::AcqRel)
+        + 1;
+    if suspend_registration(state, app_handle)? {
+        log::info!("全局快捷键已暂停");
+        refresh_tray(app_handle, state);
+    }
+
+    if let Some(duration) = duration {
+        let app_handle = app_handle.clone();
+        let spawned = thread::Builder::new()
+            .name("hotkey-snooze".into())
+            .spawn(move || {
+                thread::sleep(duration);
+                let state = app_handle.state::<AppState>();
+                if state.hotkey_snooze_generation.load(Ordering::Acquire) != generation {
+                    return;
+                }
+                if let Err(err) = resume_hotkey(&app_handle, &state) {
+                    log::warn!("failed to re-enable hotkey after snooze: {err}");
+                }
+            });
+        if let Err(err) = spawned {
+            log::warn!("failed to schedule hotkey re-enable: {err}");
+        }
+    }
 
-    *current_hotkey = Some(hotkey_string);
     Ok(())
 }
+
+/// Registers the configured hotkey again after [`suspend_hotkey`]. Does nothing when it
+/// isn't suspended.
+pub fn resume_hotkey(app_handle: &AppHandle, state: &AppState) -> AppResult<()> {
+    state
+        .hotkey_snooze_generation
+        .fetch_add(1, Ordering::AcqRel);
+    if resume_registration(state, app_handle)? {
+        log::info!("全局快捷键已恢复");
+        refresh_tray(app_handle, state);
+    }
+    Ok(())
+}
+
+/// The OS side of suspending and resuming the hotkeys, so the bookkeeping in
+/// [`suspend_registration`] and [`resume_registration`] can be tested without an app.
+trait ShortcutRegistrar {
+    fn unregister(&self, hotkey: &str) -> AppResult<()>;
+    fn register(&self, hotkey: &str) -> AppResult<()>;
+    fn unregister_modes(&self, bindings: &[ModeHotkey]);
+    fn register_modes(&self, bindings: &[ModeHotkey]) -> AppResult<()>;
+}
+
+impl ShortcutRegistrar for AppHandle {
+    fn unregister(&self, hotkey: &str) -> AppResult<()> {
+        self.global_shortcut()
+            .unregister(hotkey)
+            .map_err(|err| AppError::Internal(format!("无法暂停快捷键 {hotkey}: {err}")))
+    }
+
+    fn register(&self, hotkey: &str) -> AppResult<()> {
+        register_shortcut(self, hotkey, MAIN_WINDOW_LABEL)
+    }
+
+    fn unregister_modes(&self, bindings: &[ModeHotkey]) {
+        unregister_mode_hotkeys(self, bindings);
+    }
+
+    fn register_modes(&self, bindings: &[ModeHotkey]) -> AppResult<()> {
+        register_mode_hotkeys(self, bindings)
+    }
+}
+
+/// Unregisters the recorded hotkeys and marks them suspended. Returns `false` when they
+/// already were; a failure leaves them registered and not suspended.
+fn suspend_registration(state: &AppState, registrar: &impl ShortcutRegistrar) -> AppResult<bool> {
+    if state.hotkey_suspended.swap(true, Ordering::AcqRel) {
+        return Ok(false);
+    }
+    // update_settings 会先持有配置锁再绑定快捷键，返回后才刷新托盘，避免持锁时死锁
+    let current_hotkey = state
+        .registered_hotkey
+        .lock()
+        .map_err(|_| "无法获取快捷键状态".to_string())?;
+    if let Some(hotkey) = current_hotkey.as_deref() {
+        if let Err(err) = registrar.unregister(hotkey) {
+            state.hotkey_suspended.store(false, Ordering::Release);
+            return Err(err);
+        }
+    }
+    if let Ok(mode_hotkeys) = state.registered_mode_hotkeys.lock() {
+        registrar.unregister_modes(&mode_hotkeys);
+    }
+    Ok(true)
+}
+
+/// Registers the recorded hotkeys again and clears the suspended flag. Returns `false` when
+/// they weren't suspended.
+fn resume_registration(state: &AppState, registrar: &impl ShortcutRegistrar) -> AppResult<bool> {
+    if !state.hotkey_suspended.load(Ordering::Acquire) {
+        return Ok(false);
+    }
+    let current_hotkey = state
+        .registered_hotkey
+        .lock()
+        .map_err(|_| "无法获取快捷键状态".to_string())?;
+    if let Some(hotkey) = current_hotkey.as_deref() {
+        registrar.register(hotkey)?;
+    }
+    if let Ok(mode_hotkeys) = state.registered_mode_hotkeys.lock() {
+        // 模式快捷键恢复失败不影响主快捷键
+        if let Err(err) = registrar.register_modes(&mode_hotkeys) {
+            log::warn!("failed to re-register mode hotkeys: {err}");
+        }
+    }
+    drop(current_hotkey);
+
+    state.hotkey_suspended.store(false, Ordering::Release);
+    Ok(true)
+}
+
+pub fn registered_shortcuts(state: &AppState) -> RegisteredShortcuts {
+    RegisteredShortcuts {
+        hotkey: state
+            .registered_hotkey
+            .lock()
+            .ok()
+            .and_then(|guard| guard.clone()),
+        mode_hotkeys: state
+            .registered_mode_hotkeys
+            .lock()
+            .map(|guard| guard.clone())
+            .unwrap_or_default(),
+        enabled: !state.hotkey_suspended.load(Ordering::Acquire),
+    }
+}
+
+/// The tray menu shows whether the hotkey is suspended, so rebuild it after a toggle.
+fn refresh_tray(app_handle: &AppHandle, state: &AppState) {
+    let locale = state
+        .config
+        .lock()
+        .map(|cfg| cfg.locale)
+        .unwrap_or_default();
+    if let Err(err) = tray::refresh_tray_menu(app_handle, locale) {
+        log::warn!("failed to rebuild tray menu: {err}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use super::*;
+
+    #[test]
+    fn accepts_modifiers_followed_by_one_key() {
+        assert!(validate_hotkey("Alt+Space").is_ok());
+        assert!(validate_hotkey("Ctrl+Shift+P").is_ok());
+    }
+
+    #[test]
+    fn rejects_an_empty_key() {
+        assert!(validate_hotkey("").is_err());
+        assert!(validate_hotkey("Ctrl+").is_err());
+        assert!(validate_hotkey("Ctrl+Shift").is_err());
+    }
+
+    #[test]
+    fn rejects_an_unknown_modifier() {
+        assert!(validate_hotkey("Foo+A").is_err());
+    }
+
+    #[test]
+    fn rejects_a_modifier_given_twice_under_different_names() {
+        assert!(validate_hotkey("Ctrl+Control+A").is_err());
+    }
+
+    /// Keeps the set of registered accelerators instead of talking to the OS.
+    #[derive(Default)]
+    struct FakeRegistrar {
+        registered: RefCell<Vec<String>>,
+        fail_unregister: bool,
+    }
+
+    impl FakeRegistrar {
+        fn registered(&self) -> Vec<String> {
+            let mut registered = self.registered.borrow().clone();
+            registered.sort();
+            registered
+        }
+    }
+
+    impl ShortcutRegistrar for FakeRegistrar {
+        fn unregister(&self, hotkey: &str) -> AppResult<()> {
+            if self.fail_unregister {
+                return Err(AppError::Internal(format!("无法暂停快捷键 {hotkey}")));
+            }
+            self.registered.borrow_mut().retain(|bound| bound != hotkey);
+            Ok(())
+        }
+
+        fn register(&self, hotkey: &str) -> AppResult<()> {
+            self.registered.borrow_mut().push(hotkey.to_string());
+            Ok(())
+        }
+
+        fn unregister_modes(&self, bindings: &[ModeHotkey]) {
+            for binding in bindings {
+                let _ = self.unregister(&binding.hotkey);
+            }
+        }
+
+        fn register_modes(&self, bindings: &[ModeHotkey]) -> AppResult<()> {
+            bindings
+                .iter()
+                .try_for_each(|binding| self.register(&binding.hotkey))
+        }
+    }
+
+    fn bound_state(registrar: &FakeRegistrar) -> AppState {
+        let state = AppState::new();
+        *state.registered_hotkey.lock().unwrap() = Some("Alt+Space".to_string());
+        *state.registered_mode_hotkeys.lock().unwrap() = vec![ModeHotkey {
+            hotkey: "Alt+B".to_string(),
+            mode: "bookmark".to_string(),
+        }];
+        registrar.register("Alt+Space").unwrap();
+        registrar.register("Alt+B").unwrap();
+        state
+    }
+
+    #[test]
+    fn disable_then_enable_restores_the_bindings() {
+        let registrar = FakeRegistrar::default();
+        let state = bound_state(&registrar);
+        let before = registrar.registered();
+
+        assert!(suspend_registration(&state, &registrar).unwrap());
+        assert!(registrar.registered().is_empty());
+        assert!(!registered_shortcuts(&state).enabled);
+
+        assert!(resume_registration(&state, &registrar).unwrap());
+        assert_eq!(registrar.registered(), before);
+        let shortcuts = registered_shortcuts(&state);
+        assert!(shortcuts.enabled);
+        assert_eq!(shortcuts.hotkey.as_deref(), Some("Alt+Space"));
+    }
+
+    #[test]
+    fn repeated_disable_and_enable_do_nothing() {
+        let registrar = FakeRegistrar::default();
+        let state = bound_state(&registrar);
+
+        assert!(!resume_registration(&state, &registrar).unwrap());
+        assert!(suspend_registration(&state, &registrar).unwrap());
+        assert!(!suspend_registration(&state, &registrar).unwrap());
+        assert!(resume_registration(&state, &registrar).unwrap());
+        assert!(!resume_registration(&state, &registrar).unwrap());
+        // 重复恢复不会重复注册
+        assert_eq!(registrar.registered(), ["Alt+B", "Alt+Space"]);
+    }
+
+    #[test]
+    fn failed_disable_keeps_the_hotkey_enabled() {
+        let registrar = FakeRegistrar::default();
+        let state = bound_state(&registrar);
+        let failing = FakeRegistrar {
+            fail_unregister: true,
+            ..FakeRegistrar::default()
+        };
+
+        assert!(suspend_registration(&state, &failing).is_err());
+        assert!(registered_shortcuts(&state).enabled);
+    }
+}
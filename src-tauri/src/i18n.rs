@@ -0,0 +1,124 @@
+use serde::{Deserialize, Serialize};
+
+/// UI language for strings produced by the backend (tray menu, built-in result titles).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Locale {
+    #[default]
+    #[serde(rename = "zh-CN")]
+    ZhCn,
+    #[serde(rename = "en")]
+    En,
+}
+
+const ZH_CN: &[(&str, &str)] = &[
+    ("tray.show", "显示窗口"),
+    ("tray.hide", "隐藏窗口"),
+    ("tray.settings", "打开设置"),
+    ("tray.disable_hotkey", "暂停快捷键"),
+    ("tray.enable_hotkey", "恢复快捷键"),
+    ("tray.quit", "退出"),
+    ("result.open_url", "打开网址: {}"),
+    ("result.web_search", "在 {engine} 上搜索: {}"),
+    ("result.web_search_subtitle", "{engine} 搜索"),
+    ("result.bang_search", "用 !{bang} 搜索: {}"),
+    ("result.raw_open", "直接打开: {}"),
+    ("result.raw_open_subtitle", "交由系统打开该路径"),
+    ("result.open_with", "打开方式…"),
+    ("result.pin_to_taskbar", "固定到任务栏"),
+    ("result.restart_app", "重启应用"),
+    ("result.copy_icon", "复制图标"),
+    ("result.launch_with_args", "带参数启动: {}"),
+    ("result.bookmark_source", "收藏夹"),
+    ("result.history_source", "历史记录"),
+    ("result.instant_subtitle", "即时答案 · 回车复制"),
+    ("result.content_source", "文件内容"),
+    ("result.clipboard_subtitle", "复制于 {} · 回车重新复制"),
+    ("result.run_command", "运行 {}"),
+    ("result.run_subtitle", "像“运行”对话框一样启动命令"),
+    ("result.run_history_subtitle", "最近运行 · 回车再次运行"),
+    ("result.custom_command_input_hint", "在关键词后输入内容"),
+    ("result.invalid_regex", "正则表达式无效"),
+    ("result.open_bookmark_folder", "打开文件夹中的全部收藏: {}"),
+    ("result.bookmark_folder_count", "{} 个链接"),
+    (
+        "prompt.focus_running",
+        "{} 已在运行，是否切换到已打开的窗口？",
+    ),
+    (
+        "prompt.open_bookmark_folder",
+        "将打开 {} 个链接，是否继续？",
+    ),
+];
+
+const EN: &[(&str, &str)] = &[
+    ("tray.show", "Show Window"),
+    ("tray.hide", "Hide Window"),
+    ("tray.settings", "Settings"),
+    ("tray.disable_hotkey", "Pause Hotkey"),
+    ("tray.enable_hotkey", "Resume Hotkey"),
+    ("tray.quit", "Quit"),
+    ("result.open_url", "Open URL: {}"),
+    ("result.web_search", "Search {engine} for: {}"),
+    ("result.web_search_subtitle", "{engine} Search"),
+    ("result.bang_search", "Search !{bang} for: {}"),
+    ("result.raw_open", "Open directly: {}"),
+    ("result.raw_open_subtitle", "Let the system open this path"),
+    ("result.open_with", "Open with…"),
+    ("result.pin_to_taskbar", "Pin to taskbar"),
+    ("result.restart_app", "Restart app"),
+    ("result.copy_icon", "Copy icon"),
+    ("result.launch_with_args", "Launch with arguments: {}"),
+    ("result.bookmark_source", "Bookmarks"),
+    ("result.history_source", "History"),
+    (
+        "result.instant_subtitle",
+        "Instant answer · press Enter to copy",
+    ),
+    ("result.content_source", "File contents"),
+    (
+        "result.clipboard_subtitle",
+        "Copied {} · press Enter to copy again",
+    ),
+    ("result.run_command", "Run {}"),
+    (
+        "result.run_subtitle",
+        "Start the command like the Run dialog does",
+    ),
+    (
+        "result.run_history_subtitle",
+        "Recently run · press Enter to run again",
+    ),
+    (
+        "result.custom_command_input_hint",
+        "Type text after the keyword",
+    ),
+    ("result.invalid_regex", "Invalid regular expression"),
+    ("result.open_bookmark_folder", "Open all bookmarks in: {}"),
+    ("result.bookmark_folder_count", "{} links"),
+    (
+        "prompt.focus_running",
+        "{} is already running. Switch to its window?",
+    ),
+    (
+        "prompt.open_bookmark_folder",
+        "This opens {} links. Continue?",
+    ),
+];
+
+/// Looks up `key` in the table of `locale`, falling back to the key itself when it's missing.
+pub fn tr(locale: Locale, key: &'static str) -> &'static str {
+    let table = match locale {
+        Locale::ZhCn => ZH_CN,
+        Locale::En => EN,
+    };
+    table
+        .iter()
+        .find(|(candidate, _)| *candidate == key)
+        .map(|(_, text)| *text)
+        .unwrap_or(key)
+}
+
+/// Like [`tr`], substituting `arg` for the `{}` placeholder of the translated template.
+pub fn tr_with(locale: Locale, key: &'static str, arg: &str) -> String {
+    tr(locale, key).replacen("{}", arg, 1)
+}
@@ -0,0 +1,118 @@
+// Stable ids for indexed entries.
+//
+// Ids are derived only from attributes that survive reindexing, so anything keyed by id
+// (display names, hidden results, ...) keeps working and entries from different sources
+// never collide:
+//
+// - Start Menu shortcut: `startmenu:<target path>`, plus `|<arguments>` when it passes any
+// - Internet shortcut: `url:<url>`
+// - Steam game: `url:steam://rungameid/<app id>`, the same as Steam's own Start Menu shortcut
+// - Uninstall registry key: `installed:<uninstall subkey>\<entry>`
+// - UWP app: `uwp:<app user model id>`
+// - Linux `.desktop` entry: `desktop:<desktop file id>`
+// - macOS app bundle: `macapp:<bundle path>`
+// - Custom command: `custom:<keyword>`
+//
+// Everything after the prefix is lowercased, and paths use backslashes. A Start Menu
+// shortcut whose target can't be resolved falls back to the path of the `.lnk` itself.
+// New sources get their own prefix here.
+
+use crate::processes::normalize_exe_path;
+
+/// Id of a Start Menu shortcut, keyed by what it launches rather than where the `.lnk` lives,
+/// so moving or renaming the shortcut keeps the id.
+pub fn startmenu_id(target: Option<&str>, arguments: Option<&str>, link_path: &str) -> String {
+    let target = normalize_exe_path(target.unwrap_or(link_path));
+    match arguments.map(str::trim).filter(|value| !value.is_empty()) {
+        Some(arguments) => format!("startmenu:{target}|{}", arguments.to_lowercase()),
+        None => format!("startmenu:{target}"),
+    }
+}
+
+pub fn url_id(url: &str) -> String {
+    format!("url:{}", url.trim().to_lowercase())
+}
+
+pub fn installed_id(subkey: &str, entry: &str) -> String {
+    format!("installed:{subkey}\\{entry}").to_lowercase()
+}
+
+pub fn uwp_id(app_user_model_id: &str) -> String {
+    format!("uwp:{}", app_user_model_id.to_lowercase())
+}
+
+pub fn desktop_id(desktop_file_id: &str) -> String {
+    format!("desktop:{}", desktop_file_id.to_lowercase())
+}
+
+pub fn mac_app_id(bundle_path: &str) -> String {
+    format!("macapp:{}", bundle_path.to_lowercase())
+}
+
+pub fn custom_id(keyword: &str) -> String {
+    format!("custom:{}", keyword.trim().to_lowercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Every id of one pass over a fixed set of sources, as a reindex would build them.
+    fn index_pass() -> Vec<String> {
+        vec![
+            startmenu_id(
+                Some(r"C:\Program Files\Code\Code.exe"),
+                None,
+                r"C:\ProgramData\Microsoft\Windows\Start Menu\Programs\Code.lnk",
+            ),
+            startmenu_id(
+                Some(r"C:\Apps\browser.exe"),
+                Some("--profile work"),
+                r"C:\Links\Work.lnk",
+            ),
+            startmenu_id(None, None, r"C:\Links\Broken.lnk"),
+            url_id("https://example.com/"),
+            url_id("steam://rungameid/570"),
+            installed_id(
+                r"SOFTWARE\Microsoft\Windows\CurrentVersion\Uninstall",
+                "Tool",
+            ),
+            uwp_id("Microsoft.WindowsCalculator_8wekyb3d8bbwe!App"),
+            desktop_id("org.gnome.Terminal.desktop"),
+            mac_app_id("/Applications/Safari.app"),
+            custom_id("gh"),
+        ]
+    }
+
+    #[test]
+    fn ids_are_stable_across_reindexes() {
+        let first = index_pass();
+        assert_eq!(first, index_pass());
+
+        let mut unique = first.clone();
+        unique.sort();
+        unique.dedup();
+        assert_eq!(unique.len(), first.len(), "{first:?}");
+    }
+
+    #[test]
+    fn ids_ignore_case_separators_and_the_shortcut_location() {
+        assert_eq!(
+            startmenu_id(Some(r"C:\Apps\Code.exe"), None, r"C:\Links\Code.lnk"),
+            startmenu_id(
+                Some(" c:/apps/CODE.EXE "),
+                Some("  "),
+                r"D:\Moved\Editor.lnk"
+            ),
+        );
+        assert_eq!(
+            url_id(" HTTPS://Example.com/ "),
+            url_id("https://example.com/")
+        );
+        assert_eq!(custom_id(" GH "), custom_id("gh"));
+        assert_ne!(
+            startmenu_id(Some(r"C:\Apps\browser.exe"), Some("--profile work"), ""),
+            startmenu_id(Some(r"C:\Apps\browser.exe"), None, ""),
+        );
+    }
+}
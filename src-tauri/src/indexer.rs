@@ -1,31 +1,145 @@
 use std::{
-    collections::HashSet,
+    collections::{HashMap, HashSet},
     env, fs,
     path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+    thread,
+    time::Duration,
 };
 
 use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
 use log::{debug, error, warn};
 use tauri::async_runtime;
 use windows::{
-    core::Result as WinResult, Foundation::Size, Management::Deployment::PackageManager,
+    core::{Result as WinResult, RuntimeType},
+    Foundation::{AsyncStatus, IAsyncOperation, Size},
+    Management::Deployment::PackageManager,
     Storage::Streams::DataReader,
 };
 use winreg::{enums::*, RegKey};
 
+#[cfg(target_os = "linux")]
+use crate::desktop_entries;
+#[cfg(target_os = "macos")]
+use crate::mac_apps;
 use crate::{
-    models::{AppType, ApplicationInfo},
+    ids,
+    models::{AppOrigin, AppSummary, AppType, ApplicationInfo, IndexDiff, IndexedName},
+    steam,
     text_utils::extend_keywords_with_pinyin,
     windows_utils::{
         expand_env_vars, extract_icon_from_path, parse_internet_shortcut, resolve_shell_link,
     },
 };
 
+/// Settings that shape the application index.
+#[derive(Debug, Clone, Default)]
+pub struct IndexOptions {
+    /// With this off no icon is loaded at all and every `icon_b64` stays empty, which skips
+    /// the GDI/stream work that dominates indexing time.
+    pub extract_icons: bool,
+    /// Leave Win32 icons empty during the build so the index is published sooner; they are
+    /// filled in afterwards by [`prewarm_icons`]. Only matters with `extract_icons`.
+    pub defer_icons: bool,
+    /// Requested icon edge length, see [`crate::windows_utils::extract_icon_from_path`].
+    pub icon_size: u32,
+    /// User-chosen names by app id, see [`apply_display_names`].
+    pub display_names: HashMap<String, String>,
+    /// Saved launch arguments by app id, see [`apply_launch_args`].
+    pub launch_args: HashMap<String, String>,
+    /// Executables skipped while scanning, see [`ExeFilter`].
+    pub exclude_exe_patterns: Vec<String>,
+    /// Also list installed Steam games, see [`crate::steam::enumerate_steam_games`].
+    pub steam_games: bool,
+}
+
+/// Executable file names to leave out of the index, such as installers and crash handlers.
+///
+/// Patterns are matched case-insensitively against the file name only. `*` matches any run of
+/// characters and `?` a single one; a pattern without wildcards matches anywhere in the name.
+#[derive(Debug, Clone, Default)]
+pub struct ExeFilter {
+    patterns: Vec<String>,
+}
+
+impl ExeFilter {
+    pub fn new(patterns: &[String]) -> Self {
+        Self {
+            patterns: patterns
+                .iter()
+                .map(|pattern| pattern.trim().to_lowercase())
+                .filter(|pattern| !pattern.is_empty())
+                .collect(),
+        }
+    }
+
+    /// Whether the file name of `path` matches any pattern.
+    pub fn excludes(&self, path: &str) -> bool {
+        if self.patterns.is_empty() {
+            return false;
+        }
+        let file_name = path
+            .trim()
+            .trim_matches('"')
+            .rsplit(['\\', '/'])
+            .next()
+            .unwrap_or_default()
+            .to_lowercase();
+        self.patterns.iter().any(|pattern| {
+            if pattern.contains(['*', '?']) {
+                wildcard_match(pattern, &file_name)
+            } else {
+                file_name.contains(pattern.as_str())
+            }
+        })
+    }
+}
+
+/// Matches `text` against a pattern where `*` is any run of characters and `?` one character.
+fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    // 最近一个 * 的位置及其当时对应的文本位置，失配时从这里回溯
+    let mut backtrack: Option<(usize, usize)> = None;
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            backtrack = Some((p, t));
+            p += 1;
+        } else if let Some((star, matched)) = backtrack {
+            p = star + 1;
+            t = matched + 1;
+            backtrack = Some((star, matched + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|&ch| ch == '*')
+}
+
 /// Build the application index by scanning Start Menu shortcuts, installed Win32 software and UWP apps.
-pub async fn build_index() -> Vec<ApplicationInfo> {
+#[cfg(target_os = "windows")]
+pub async fn build_index(options: IndexOptions) -> Vec<ApplicationInfo> {
+    let extract_icons = options.extract_icons;
+    // UWP 徽标来自包内资源流而非 GDI 提取，始终随索引一并加载
+    let inline_icons = extracts_icons_inline(&options);
+    let icon_size = options.icon_size;
+    let filter = ExeFilter::new(&options.exclude_exe_patterns);
     let mut results = Vec::new();
 
-    let start_menu = match async_runtime::spawn_blocking(enumerate_start_menu_programs).await {
+    // Win32 图标不在枚举时逐个提取，而是在去重后并行补齐，见 extract_icons_parallel
+    let start_menu_filter = filter.clone();
+    let start_menu = match async_runtime::spawn_blocking(move || {
+        enumerate_start_menu_programs(&start_menu_filter)
+    })
+    .await
+    {
         Ok(apps) => apps,
         Err(err) => {
             warn!("start menu index task failed: {err}");
@@ -35,7 +149,22 @@ pub async fn build_index() -> Vec<ApplicationInfo> {
     debug!("indexed {} start menu shortcuts", start_menu.len());
     results.extend(start_menu);
 
-    let win32 = match async_runtime::spawn_blocking(enumerate_installed_win32_apps).await {
+    // 排在开始菜单之后：Steam 自建的 .url 快捷方式优先保留，同一游戏只出现一次
+    if options.steam_games {
+        match async_runtime::spawn_blocking(move || steam::enumerate_steam_games(extract_icons))
+            .await
+        {
+            Ok(games) => {
+                debug!("indexed {} Steam games", games.len());
+                results.extend(games);
+            }
+            Err(err) => warn!("steam index task failed: {err}"),
+        }
+    }
+
+    let win32 = match async_runtime::spawn_blocking(move || enumerate_installed_win32_apps(&filter))
+        .await
+    {
         Ok(apps) => apps,
         Err(err) => {
             error!("win32 index task failed: {err}");
@@ -45,7 +174,7 @@ pub async fn build_index() -> Vec<ApplicationInfo> {
     debug!("indexed {} installed Win32 apps", win32.len());
     results.extend(win32);
 
-    match enumerate_uwp_apps().await {
+    match enumerate_uwp_apps(extract_icons).await {
         Ok(mut uwp_apps) => {
             debug!("indexed {} UWP entries", uwp_apps.len());
             results.append(&mut uwp_apps);
@@ -67,9 +196,315 @@ pub async fn build_index() -> Vec<ApplicationInfo> {
             .map(|value| value.to_ascii_lowercase());
         seen.insert((app.app_type.clone(), key_path, argument_key))
     });
-    results.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
+
+    if inline_icons {
+        let pending = std::mem::take(&mut results);
+        match async_runtime::spawn_blocking(move || {
+            let mut apps = pending;
+            let filled = extract_icons_parallel(&mut apps, |source, index| {
+                extract_icon_from_path(source, index, icon_size)
+            });
+            debug!("extracted {filled} icons");
+            apps
+        })
+        .await
+        {
+            Ok(apps) => results = apps,
+            Err(err) => error!("icon extraction task failed: {err}"),
+        }
+    }
+
+    finish_index(results, &options)
+}
+
+/// Build the application index from the `.desktop` files of the XDG data directories.
+#[cfg(target_os = "linux")]
+pub async fn build_index(options: IndexOptions) -> Vec<ApplicationInfo> {
+    let extract_icons = options.extract_icons;
+    let results = match async_runtime::spawn_blocking(move || {
+        desktop_entries::enumerate_desktop_entries(extract_icons)
+    })
+    .await
+    {
+        Ok(apps) => apps,
+        Err(err) => {
+            error!("desktop entry index task failed: {err}");
+            Vec::new()
+        }
+    };
+    finish_index(results, &options)
+}
+
+/// Build the application index from the `.app` bundles of the applications folders. Bundle
+/// icons are `.icns` files and aren't loaded; the frontend shows its fallback glyph.
+#[cfg(target_os = "macos")]
+pub async fn build_index(options: IndexOptions) -> Vec<ApplicationInfo> {
+    let results = match async_runtime::spawn_blocking(mac_apps::enumerate_app_bundles).await {
+        Ok(apps) => apps,
+        Err(err) => {
+            error!("app bundle index task failed: {err}");
+            Vec::new()
+        }
+    };
+    finish_index(results, &options)
+}
+
+/// Applies the per-app overrides shared by every platform and sorts the index by name.
+fn finish_index(mut results: Vec<ApplicationInfo>, options: &IndexOptions) -> Vec<ApplicationInfo> {
+    apply_display_names(&mut results, &options.display_names);
+    apply_launch_args(&mut results, &options.launch_args);
+    sort_by_name(&mut results);
     results
 }
+
+/// Keeps the index in the case-insensitive name order it is built in.
+pub fn sort_by_name(apps: &mut [ApplicationInfo]) {
+    apps.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
+}
+
+/// Replaces the names of apps that have a display-name override. The original name stays in
+/// the keywords so typing it still finds the app, and apps whose override was removed get their
+/// indexed name back. Applying the same overrides twice changes nothing.
+pub fn apply_display_names(apps: &mut [ApplicationInfo], display_names: &HashMap<String, String>) {
+    for app in apps.iter_mut() {
+        // 先恢复索引时的名称，再按当前设置重新应用
+        if let Some(indexed) = app.indexed_name.take() {
+            app.name = indexed.name;
+            app.keywords = indexed.keywords;
+        }
+        let Some(display_name) = display_names.get(&app.id) else {
+            continue;
+        };
+        app.indexed_name = Some(IndexedName {
+            name: app.name.clone(),
+            keywords: app.keywords.clone(),
+        });
+        let original = std::mem::replace(&mut app.name, display_name.clone());
+        app.keywords.push(original);
+        app.keywords.push(display_name.clone());
+        extend_keywords_with_pinyin(&mut app.keywords);
+        app.keywords.sort();
+        app.keywords.dedup();
+    }
+}
+
+/// Attaches the saved launch arguments to their Win32 apps; UWP apps can't take arguments.
+pub fn apply_launch_args(apps: &mut [ApplicationInfo], launch_args: &HashMap<String, String>) {
+    for app in apps.iter_mut() {
+        app.launch_args = launch_args
+            .get(&app.id)
+            .filter(|_| app.app_type == AppType::Win32)
+            .cloned();
+    }
+}
+
+/// Compares two builds of the app index by id. Both lists keep the order of their index.
+pub fn diff_index(previous: &[ApplicationInfo], current: &[ApplicationInfo]) -> IndexDiff {
+    let previous_ids: HashSet<&str> = previous.iter().map(|app| app.id.as_str()).collect();
+    let current_ids: HashSet<&str> = current.iter().map(|app| app.id.as_str()).collect();
+    let summarize = |app: &ApplicationInfo| AppSummary {
+        id: app.id.clone(),
+        name: app.name.clone(),
+    };
+    IndexDiff {
+        added: current
+            .iter()
+            .filter(|app| !previous_ids.contains(app.id.as_str()))
+            .map(summarize)
+            .collect(),
+        removed: previous
+            .iter()
+            .filter(|app| !current_ids.contains(app.id.as_str()))
+            .map(summarize)
+            .collect(),
+    }
+}
+
+/// Whether [`build_index`] extracts the Win32 icons before it returns; with `defer_icons`
+/// they are left to [`prewarm_icons`], and without `extract_icons` no icon is extracted.
+fn extracts_icons_inline(options: &IndexOptions) -> bool {
+    options.extract_icons && !options.defer_icons
+}
+
+/// Upper bound on the threads [`extract_icons_parallel`] uses.
+const MAX_ICON_WORKERS: usize = 8;
+
+/// Extracts the missing icons of every entry that has an `icon_source`, spread over a small
+/// pool of threads. Entries sharing a source are extracted once, and already cached icons are
+/// read from the on-disk cache. Returns how many entries got an icon.
+fn extract_icons_parallel(
+    apps: &mut [ApplicationInfo],
+    extract: impl Fn(&str, i32) -> Option<String> + Sync,
+) -> usize {
+    let mut sources: Vec<(String, i32)> = apps
+        .iter()
+        .filter(|app| app.icon_b64.is_empty())
+        .filter_map(|app| app.icon_source.clone())
+        .collect();
+    sources.sort();
+    sources.dedup();
+    if sources.is_empty() {
+        return 0;
+    }
+
+    let workers = thread::available_parallelism()
+        .map_or(1, |count| count.get())
+        .clamp(1, MAX_ICON_WORKERS)
+        .min(sources.len());
+    let next = AtomicUsize::new(0);
+    let icons: HashMap<(String, i32), String> = thread::scope(|scope| {
+        let handles: Vec<_> = (0..workers)
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut extracted = Vec::new();
+                    loop {
+                        let position = next.fetch_add(1, Ordering::Relaxed);
+                        let Some((source, index)) = sources.get(position) else {
+                            break;
+                        };
+                        if let Some(icon) = extract(source, *index) {
+                            extracted.push(((source.clone(), *index), icon));
+                        }
+                    }
+                    extracted
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .filter_map(|handle| handle.join().ok())
+            .flatten()
+            .collect()
+    });
+
+    let mut filled = 0;
+    for app in apps.iter_mut().filter(|app| app.icon_b64.is_empty()) {
+        if let Some(icon) = app.icon_source.as_ref().and_then(|key| icons.get(key)) {
+            app.icon_b64 = icon.clone();
+            filled += 1;
+        }
+    }
+    filled
+}
+
+/// Pause between two icon extractions while prewarming, so queries rarely wait on the index lock.
+const PREWARM_INTERVAL: Duration = Duration::from_millis(15);
+
+/// Extracts the icons a deferred build left empty, one entry at a time, patching the index and
+/// warming the on-disk icon cache. Stops early once `superseded` returns true, e.g. because a
+/// newer build started. Returns how many icons were filled in.
+pub fn prewarm_icons(
+    app_index: &Mutex<Vec<ApplicationInfo>>,
+    icon_size: u32,
+    superseded: impl Fn() -> bool,
+) -> usize {
+    fill_missing_icons(app_index, superseded, |source, index| {
+        extract_icon_from_path(source, index, icon_size)
+    })
+}
+
+/// The loop of [`prewarm_icons`], with the icon extraction passed in.
+fn fill_missing_icons(
+    app_index: &Mutex<Vec<ApplicationInfo>>,
+    superseded: impl Fn() -> bool,
+    extract: impl Fn(&str, i32) -> Option<String>,
+) -> usize {
+    let pending: Vec<(String, String, i32)> = match app_index.lock() {
+        Ok(apps) => apps
+            .iter()
+            .filter(|app| app.icon_b64.is_empty())
+            .filter_map(|app| {
+                let (source, index) = app.icon_source.as_ref()?;
+                Some((app.id.clone(), source.clone(), *index))
+            })
+            .collect(),
+        Err(_) => return 0,
+    };
+
+    let mut filled = 0;
+    for (id, source, index) in pending {
+        if superseded() {
+            break;
+        }
+        let Some(icon) = extract(&source, index) else {
+            continue;
+        };
+        if let Ok(mut apps) = app_index.lock() {
+            if let Some(app) = apps.iter_mut().find(|app| app.id == id) {
+                app.icon_b64 = icon;
+                filled += 1;
+            }
+        }
+        thread::sleep(PREWARM_INTERVAL);
+    }
+    filled
+}
+
+/// Puts the result of [`reload_entry`] in place of the entry `app_id`. A moved target changes
+/// the id, so another entry that already has the new id is dropped rather than duplicated.
+pub fn replace_entry(apps: &mut Vec<ApplicationInfo>, app_id: &str, repaired: ApplicationInfo) {
+    apps.retain(|app| app.id == app_id || app.id != repaired.id);
+    match apps.iter_mut().find(|app| app.id == app_id) {
+        Some(entry) => *entry = repaired,
+        None => apps.push(repaired),
+    }
+}
+
+/// Reads a single Win32 entry again from its source, picking up a target that moved after an
+/// update and refreshing its icon. Fails when the source is gone or no longer yields an app.
+pub fn reload_entry(
+    origin: &AppOrigin,
+    extract_icons: bool,
+    icon_size: u32,
+    filter: &ExeFilter,
+) -> Result<ApplicationInfo, String> {
+    let mut app = read_entry(origin, filter)?;
+    if extract_icons {
+        if let Some((source, index)) = &app.icon_source {
+            app.icon_b64 = extract_icon_from_path(source, *index, icon_size).unwrap_or_default();
+        }
+    }
+    Ok(app)
+}
+
+fn read_entry(origin: &AppOrigin, filter: &ExeFilter) -> Result<ApplicationInfo, String> {
+    match origin {
+        AppOrigin::StartMenu { link_path } => {
+            let path = Path::new(link_path);
+            if !path.is_file() {
+                return Err(format!("快捷方式 {link_path} 已不存在"));
+            }
+            let is_url = path
+                .extension()
+                .and_then(|ext| ext.to_str())
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("url"));
+            let app = if is_url {
+                internet_shortcut_to_application(path)
+            } else {
+                shortcut_to_application(path, filter)
+            };
+            app.ok_or_else(|| format!("无法从快捷方式 {link_path} 解析应用"))
+        }
+        AppOrigin::Registry {
+            root,
+            subkey,
+            entry,
+        } => {
+            let (root_name, root_key) = registry_roots()
+                .into_iter()
+                .find(|(name, _)| name == root)
+                .ok_or_else(|| format!("未知的注册表根键 {root}"))?;
+            let key = root_key
+                .open_subkey(format!("{subkey}\\{entry}"))
+                .map_err(|_| format!("注册表项 {root}\\{subkey}\\{entry} 已不存在"))?;
+            registry_entry_to_app(&key, root_name, subkey, entry, filter)
+                .ok_or_else(|| format!("注册表项 {entry} 已不再指向可执行文件"))
+        }
+        AppOrigin::Uwp => Err("UWP 应用无需修复路径".into()),
+        AppOrigin::Steam { .. } => Err("Steam 游戏由 Steam 客户端启动，无需修复路径".into()),
+    }
+}
+
 const UNINSTALL_SUBKEYS: &[&str] = &[
     r"SOFTWARE\Microsoft\Windows\CurrentVersion\Uninstall",
     r"SOFTWARE\WOW6432Node\Microsoft\Windows\CurrentVersion\Uninstall",
@@ -77,7 +512,7 @@ const UNINSTALL_SUBKEYS: &[&str] = &[
 
 const SUPPORTED_URL_PROTOCOLS: &[&str] = &["steam://", "com.epicgames.launcher://apps/"];
 
-fn enumerate_start_menu_programs() -> Vec<ApplicationInfo> {
+fn enumerate_start_menu_programs(filter: &ExeFilter) -> Vec<ApplicationInfo> {
     let startup_dirs = startup_directories();
     let mut applications = Vec::new();
 
@@ -119,7 +554,7 @@ fn enumerate_start_menu_programs() -> Vec<ApplicationInfo> {
 
                 match extension.as_deref() {
                     Some("lnk") => {
-                        if let Some(app) = shortcut_to_application(&path) {
+                        if let Some(app) = shortcut_to_application(&path, filter) {
                             applications.push(app);
                         }
                     }
@@ -137,7 +572,7 @@ fn enumerate_start_menu_programs() -> Vec<ApplicationInfo> {
     applications
 }
 
-fn shortcut_to_application(path: &Path) -> Option<ApplicationInfo> {
+fn shortcut_to_application(path: &Path, filter: &ExeFilter) -> Option<ApplicationInfo> {
     let shortcut = resolve_shell_link(path)?;
     let name = path
         .file_stem()
@@ -166,6 +601,12 @@ fn shortcut_to_application(path: &Path) -> Option<ApplicationInfo> {
     {
         return None;
     }
+    if resolved_target
+        .as_deref()
+        .is_some_and(|target| filter.excludes(target))
+    {
+        return None;
+    }
 
     let mut keywords = vec![name.clone()];
     if let Some(ref target) = display_target {
@@ -189,7 +630,7 @@ fn shortcut_to_application(path: &Path) -> Option<ApplicationInfo> {
     let icon_source = icon_candidate
         .or_else(|| display_target.clone())
         .unwrap_or_else(|| path.to_string_lossy().into_owned());
-    let icon_b64 = extract_icon_from_path(&icon_source, shortcut.icon_index).unwrap_or_default();
+    let icon_source = Some((icon_source, shortcut.icon_index));
 
     let description = shortcut
         .description
@@ -213,16 +654,26 @@ fn shortcut_to_application(path: &Path) -> Option<ApplicationInfo> {
     });
 
     Some(ApplicationInfo {
-        id: format!("win32:startmenu:{}", path_string.to_lowercase()),
+        id: ids::startmenu_id(
+            resolved_target.as_deref(),
+            arguments.as_deref(),
+            &path_string,
+        ),
         name,
         path: path_string,
         source_path: display_target,
         app_type: AppType::Win32,
-        icon_b64,
+        icon_b64: String::new(),
         description,
         keywords,
         working_directory,
         arguments,
+        origin: Some(AppOrigin::StartMenu {
+            link_path: path.to_string_lossy().into_owned(),
+        }),
+        icon_source,
+        launch_args: None,
+        indexed_name: None,
     })
 }
 
@@ -266,23 +717,29 @@ fn internet_shortcut_to_application(path: &Path) -> Option<ApplicationInfo> {
 
     let icon_candidate = shortcut.icon_path.as_deref().and_then(sanitize_icon_source);
     let icon_source = icon_candidate.unwrap_or_else(|| path.to_string_lossy().into_owned());
-    let icon_b64 = extract_icon_from_path(&icon_source, shortcut.icon_index).unwrap_or_default();
+    let icon_source = Some((icon_source, shortcut.icon_index));
     let path_string = path.to_string_lossy().into_owned();
     let description = shortcut
         .description
         .filter(|value| !value.trim().is_empty());
 
     Some(ApplicationInfo {
-        id: format!("win32:url:{}", path_string.to_lowercase()),
+        id: ids::url_id(&normalized_url),
         name,
         path: path_string,
         source_path: Some(normalized_url),
         app_type: AppType::Win32,
-        icon_b64,
+        icon_b64: String::new(),
         description,
         keywords,
         working_directory: None,
         arguments: None,
+        origin: Some(AppOrigin::StartMenu {
+            link_path: path.to_string_lossy().into_owned(),
+        }),
+        icon_source,
+        launch_args: None,
+        indexed_name: None,
     })
 }
 
@@ -323,15 +780,10 @@ fn sanitize_icon_source(raw: &str) -> Option<String> {
     }
 }
 
-fn enumerate_installed_win32_apps() -> Vec<ApplicationInfo> {
+fn enumerate_installed_win32_apps(filter: &ExeFilter) -> Vec<ApplicationInfo> {
     let mut applications = Vec::new();
     let mut seen = HashSet::new();
-    let roots = [
-        RegKey::predef(HKEY_LOCAL_MACHINE),
-        RegKey::predef(HKEY_CURRENT_USER),
-    ];
-
-    for root in roots {
+    for (root_name, root) in registry_roots() {
         for subkey in UNINSTALL_SUBKEYS {
             let Ok(uninstall_key) = root.open_subkey(subkey) else {
                 continue;
@@ -342,7 +794,9 @@ fn enumerate_installed_win32_apps() -> Vec<ApplicationInfo> {
                     continue;
                 };
 
-                if let Some(app) = registry_entry_to_app(&app_key, subkey, &entry) {
+                if let Some(app) =
+                    registry_entry_to_app(&app_key, root_name, subkey, &entry, filter)
+                {
                     if seen.insert(app.id.clone()) {
                         applications.push(app);
                     }
@@ -354,10 +808,19 @@ fn enumerate_installed_win32_apps() -> Vec<ApplicationInfo> {
     applications
 }
 
+fn registry_roots() -> [(&'static str, RegKey); 2] {
+    [
+        ("HKLM", RegKey::predef(HKEY_LOCAL_MACHINE)),
+        ("HKCU", RegKey::predef(HKEY_CURRENT_USER)),
+    ]
+}
+
 fn registry_entry_to_app(
     key: &RegKey,
+    root_name: &str,
     parent_path: &str,
     entry_name: &str,
+    filter: &ExeFilter,
 ) -> Option<ApplicationInfo> {
     // Skip system or hidden components.
     if key.get_value::<u32, _>("SystemComponent").ok() == Some(1) {
@@ -384,17 +847,18 @@ fn registry_entry_to_app(
     let explicit_executable = key
         .get_value::<String, _>("ExecutablePath")
         .ok()
-        .and_then(|value| sanitize_executable_path(&value));
+        .and_then(|value| sanitize_executable_path(&value))
+        .filter(|candidate| !filter.excludes(candidate));
 
     let install_executable = key
         .get_value::<String, _>("InstallLocation")
         .ok()
-        .and_then(|value| fallback_executable_from_folder(&value));
+        .and_then(|value| fallback_executable_from_folder(&value, filter));
 
     let install_source_executable = key
         .get_value::<String, _>("InstallSource")
         .ok()
-        .and_then(|value| fallback_executable_from_folder(&value));
+        .and_then(|value| fallback_executable_from_folder(&value, filter));
 
     let path = install_executable
         .or(explicit_executable)
@@ -402,6 +866,7 @@ fn registry_entry_to_app(
             display_icon_path
                 .clone()
                 .filter(|candidate| !looks_like_uninstaller(candidate))
+                .filter(|candidate| !filter.excludes(candidate))
         })
         .or(install_source_executable)?;
 
@@ -427,19 +892,27 @@ fn registry_entry_to_app(
     keywords.dedup();
 
     let icon_source = display_icon_path.unwrap_or_else(|| path.clone());
-    let icon_b64 = extract_icon_from_path(&icon_source, 0).unwrap_or_default();
+    let icon_source = Some((icon_source, 0));
 
     Some(ApplicationInfo {
-        id: format!("win32:installed:{}:{}", parent_path, entry_name).to_lowercase(),
+        id: ids::installed_id(parent_path, entry_name),
         name: display_name,
         path: path.clone(),
         source_path: Some(path),
         app_type: AppType::Win32,
-        icon_b64,
+        icon_b64: String::new(),
         description,
         keywords,
         working_directory: None,
         arguments: None,
+        origin: Some(AppOrigin::Registry {
+            root: root_name.to_string(),
+            subkey: parent_path.to_string(),
+            entry: entry_name.to_string(),
+        }),
+        icon_source,
+        launch_args: None,
+        indexed_name: None,
     })
 }
 
@@ -467,7 +940,8 @@ fn sanitize_executable_path(raw: &str) -> Option<String> {
     }
 }
 
-fn fallback_executable_from_folder(raw: &str) -> Option<String> {
+/// The largest executable directly inside the folder `raw`, skipping those `filter` excludes.
+fn fallback_executable_from_folder(raw: &str, filter: &ExeFilter) -> Option<String> {
     let trimmed = raw.trim();
     if trimmed.is_empty() {
         return None;
@@ -495,6 +969,7 @@ fn fallback_executable_from_folder(raw: &str) -> Option<String> {
                 .and_then(|ext| ext.to_str())
                 .map(|ext| ext.eq_ignore_ascii_case("exe"))
                 .unwrap_or(false)
+                && !filter.excludes(&file_path.to_string_lossy())
             {
                 candidates.push(file_path);
             }
@@ -512,7 +987,7 @@ fn looks_like_uninstaller(path: &str) -> bool {
     lower.contains("unins") || lower.contains("uninstall")
 }
 
-async fn enumerate_uwp_apps() -> WinResult<Vec<ApplicationInfo>> {
+async fn enumerate_uwp_apps(extract_icons: bool) -> WinResult<Vec<ApplicationInfo>> {
     let manager = PackageManager::new()?;
     let mut applications = Vec::new();
 
@@ -523,7 +998,15 @@ async fn enumerate_uwp_apps() -> WinResult<Vec<ApplicationInfo>> {
         iterator.MoveNext()?;
 
         let entries_future = package.GetAppListEntriesAsync()?;
-        let entries = entries_future.get()?;
+        let Some(entries) = wait_with_timeout(&entries_future, UWP_PACKAGE_TIMEOUT)? else {
+            let package_name = package
+                .Id()
+                .and_then(|id| id.FullName())
+                .map(|name| name.to_string())
+                .unwrap_or_default();
+            warn!("skipping UWP package {package_name}: listing its apps timed out");
+            continue;
+        };
 
         let size = entries.Size()?;
         for index in 0..size {
@@ -561,10 +1044,14 @@ async fn enumerate_uwp_apps() -> WinResult<Vec<ApplicationInfo>> {
             keywords.sort();
             keywords.dedup();
 
-            let icon_b64 = load_uwp_logo(&display_info).unwrap_or_default();
+            let icon_b64 = if extract_icons {
+                load_uwp_logo(&display_info).unwrap_or_default()
+            } else {
+                String::new()
+            };
 
             applications.push(ApplicationInfo {
-                id: format!("uwp:{}", app_id.to_lowercase()),
+                id: ids::uwp_id(&app_id),
                 name: display_name,
                 path: app_id,
                 source_path: None,
@@ -574,6 +1061,10 @@ async fn enumerate_uwp_apps() -> WinResult<Vec<ApplicationInfo>> {
                 keywords,
                 working_directory: None,
                 arguments: None,
+                origin: Some(AppOrigin::Uwp),
+                icon_source: None,
+                launch_args: None,
+                indexed_name: None,
             });
         }
     }
@@ -581,6 +1072,43 @@ async fn enumerate_uwp_apps() -> WinResult<Vec<ApplicationInfo>> {
     Ok(applications)
 }
 
+/// How long a single package may take to list its app entries before it is skipped.
+const UWP_PACKAGE_TIMEOUT: Duration = Duration::from_secs(3);
+const UWP_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Waits for `operation` by polling its status instead of blocking in `get()`, so a hung
+/// operation can be abandoned. Returns `None` (after cancelling it) when `timeout` passes.
+fn wait_with_timeout<T: RuntimeType + 'static>(
+    operation: &IAsyncOperation<T>,
+    timeout: Duration,
+) -> WinResult<Option<T>> {
+    let finished = wait_until(timeout, UWP_POLL_INTERVAL, || {
+        Ok(operation.Status()? != AsyncStatus::Started)
+    })?;
+    if !finished {
+        let _ = operation.Cancel();
+        return Ok(None);
+    }
+    operation.GetResults().map(Some)
+}
+
+/// Polls `finished` every `interval` until it returns true, or returns `false` once `timeout`
+/// has passed; a package that never finishes is simulated in the tests through this seam.
+fn wait_until(
+    timeout: Duration,
+    interval: Duration,
+    mut finished: impl FnMut() -> WinResult<bool>,
+) -> WinResult<bool> {
+    let deadline = std::time::Instant::now() + timeout;
+    while !finished()? {
+        if std::time::Instant::now() >= deadline {
+            return Ok(false);
+        }
+        thread::sleep(interval);
+    }
+    Ok(true)
+}
+
 fn load_uwp_logo(display_info: &windows::ApplicationModel::AppDisplayInfo) -> Option<String> {
     let logo_ref = display_info
         .GetLogo(Size {
@@ -609,3 +1137,368 @@ fn load_uwp_logo(display_info: &windows::ApplicationModel::AppDisplayInfo) -> Op
 
     Some(BASE64.encode(buffer))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::AppConfig;
+
+    fn app(id: &str, name: &str) -> ApplicationInfo {
+        ApplicationInfo {
+            id: id.to_string(),
+            name: name.to_string(),
+            path: format!(r"C:\Apps\{id}.exe"),
+            source_path: None,
+            app_type: AppType::Win32,
+            icon_b64: String::new(),
+            description: None,
+            keywords: Vec::new(),
+            working_directory: None,
+            arguments: None,
+            origin: None,
+            icon_source: None,
+            launch_args: None,
+            indexed_name: None,
+        }
+    }
+
+    fn summary_ids(summaries: &[AppSummary]) -> Vec<&str> {
+        summaries
+            .iter()
+            .map(|summary| summary.id.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn diff_reports_added_and_removed_apps_but_not_unchanged_ones() {
+        let previous = [app("code", "Code"), app("paint", "Paint")];
+        let current = [
+            app("code", "Code"),
+            app("terminal", "Terminal"),
+            app("notepad", "Notepad"),
+        ];
+        let diff = diff_index(&previous, &current);
+        assert_eq!(summary_ids(&diff.added), ["terminal", "notepad"]);
+        assert_eq!(summary_ids(&diff.removed), ["paint"]);
+        assert_eq!(diff.added[0].name, "Terminal");
+    }
+
+    #[test]
+    fn diff_of_identical_indexes_is_empty() {
+        let apps = [app("code", "Code")];
+        let diff = diff_index(&apps, &apps);
+        assert!(diff.added.is_empty());
+        assert!(diff.removed.is_empty());
+    }
+
+    #[test]
+    fn default_patterns_exclude_uninstallers_and_setups() {
+        let filter = ExeFilter::new(&AppConfig::default().exclude_exe_patterns);
+        for path in [
+            r"C:\Program Files\Tool\unins000.exe",
+            r"C:\Program Files\Tool\Uninstall.exe",
+            r"C:\Downloads\ToolSetup.exe",
+            r"C:\Program Files\Tool\setup.exe",
+            r"C:\Program Files\Tool\crashpad_handler.exe",
+        ] {
+            assert!(filter.excludes(path), "{path}");
+        }
+    }
+
+    #[test]
+    fn default_patterns_keep_regular_executables() {
+        let filter = ExeFilter::new(&AppConfig::default().exclude_exe_patterns);
+        for path in [
+            r"C:\Program Files\Microsoft VS Code\Code.exe",
+            r"C:\Windows\notepad.exe",
+            // 目录名中的 setup 不影响判断
+            r"C:\Setup Tools\tool.exe",
+            "\"C:\\Apps\\Paint.exe\"",
+        ] {
+            assert!(!filter.excludes(path), "{path}");
+        }
+    }
+
+    #[test]
+    fn user_patterns_match_case_insensitively() {
+        let filter = ExeFilter::new(&[
+            " Updater ".to_string(),
+            "tool?.exe".to_string(),
+            String::new(),
+        ]);
+        assert!(filter.excludes(r"C:\Apps\GoogleUpdater.exe"));
+        assert!(filter.excludes(r"C:\Apps\TOOL2.exe"));
+        assert!(!filter.excludes(r"C:\Apps\tool12.exe"));
+        assert!(!filter.excludes(r"C:\Apps\Code.exe"));
+        assert!(!ExeFilter::new(&[]).excludes(r"C:\Apps\setup.exe"));
+    }
+
+    fn display_names(entries: &[(&str, &str)]) -> HashMap<String, String> {
+        entries
+            .iter()
+            .map(|(id, name)| (id.to_string(), name.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn display_name_replaces_the_name_and_keeps_the_original_searchable() {
+        let mut apps = vec![app("code", "Visual Studio Code"), app("paint", "Paint")];
+        apps[0].keywords = vec!["editor".to_string()];
+        apply_display_names(&mut apps, &display_names(&[("code", "VS Code")]));
+
+        assert_eq!(apps[0].name, "VS Code");
+        for keyword in ["editor", "Visual Studio Code", "VS Code"] {
+            assert!(apps[0].keywords.iter().any(|k| k == keyword), "{keyword}");
+        }
+        assert_eq!(apps[1].name, "Paint");
+        assert!(apps[1].indexed_name.is_none());
+    }
+
+    #[test]
+    fn cleared_display_name_restores_the_indexed_entry_in_place() {
+        let mut apps = vec![app("code", "Visual Studio Code")];
+        apps[0].keywords = vec!["editor".to_string()];
+        let before = apps[0].keywords.clone();
+        apply_display_names(&mut apps, &display_names(&[("code", "VS Code")]));
+        apply_display_names(&mut apps, &HashMap::new());
+
+        assert_eq!(apps[0].name, "Visual Studio Code");
+        assert_eq!(apps[0].keywords, before);
+        assert!(apps[0].indexed_name.is_none());
+    }
+
+    #[test]
+    fn changed_display_name_drops_the_previous_override() {
+        let mut apps = vec![app("code", "Visual Studio Code")];
+        apply_display_names(&mut apps, &display_names(&[("code", "VS Code")]));
+        apply_display_names(&mut apps, &display_names(&[("code", "Editor")]));
+
+        assert_eq!(apps[0].name, "Editor");
+        assert!(!apps[0].keywords.iter().any(|k| k == "VS Code"));
+        assert_eq!(
+            apps[0]
+                .indexed_name
+                .as_ref()
+                .map(|indexed| indexed.name.as_str()),
+            Some("Visual Studio Code")
+        );
+    }
+
+    #[test]
+    fn applying_the_same_display_names_twice_changes_nothing() {
+        let names = display_names(&[("code", "VS Code")]);
+        let mut apps = vec![app("code", "Visual Studio Code")];
+        apply_display_names(&mut apps, &names);
+        let once = (apps[0].name.clone(), apps[0].keywords.clone());
+        apply_display_names(&mut apps, &names);
+
+        assert_eq!((apps[0].name.clone(), apps[0].keywords.clone()), once);
+    }
+
+    #[test]
+    fn display_names_survive_the_config_and_index_cache_round_trip() {
+        let mut config = AppConfig::default();
+        config.display_names = display_names(&[("code", "VS Code")]);
+        let config: AppConfig =
+            serde_json::from_str(&serde_json::to_string(&config).unwrap()).unwrap();
+        assert_eq!(config.display_names, display_names(&[("code", "VS Code")]));
+
+        // 缓存中的索引保留原名，重启后清除覆盖仍可就地恢复
+        let mut apps = vec![app("code", "Visual Studio Code")];
+        apply_display_names(&mut apps, &config.display_names);
+        let mut cached: Vec<ApplicationInfo> =
+            serde_json::from_str(&serde_json::to_string(&apps).unwrap()).unwrap();
+        apply_display_names(&mut cached, &HashMap::new());
+        assert_eq!(cached[0].name, "Visual Studio Code");
+    }
+
+    #[test]
+    fn index_is_sorted_by_name_after_a_rename() {
+        let mut apps = vec![app("a", "Alpha"), app("b", "Beta")];
+        apply_display_names(&mut apps, &display_names(&[("b", "aardvark")]));
+        sort_by_name(&mut apps);
+
+        assert_eq!(
+            apps.iter().map(|app| app.id.as_str()).collect::<Vec<_>>(),
+            ["b", "a"]
+        );
+    }
+
+    fn moved(app: &ApplicationInfo, id: &str) -> ApplicationInfo {
+        ApplicationInfo {
+            id: id.to_string(),
+            path: format!(r"D:\Moved\{id}.exe"),
+            ..app.clone()
+        }
+    }
+
+    #[test]
+    fn repaired_entry_with_a_moved_target_replaces_the_old_one_in_place() {
+        let mut apps = vec![app("a", "Alpha"), app("code", "Code"), app("z", "Zeta")];
+        let repaired = moved(&apps[1], "code-new");
+        replace_entry(&mut apps, "code", repaired);
+
+        assert_eq!(
+            apps.iter().map(|app| app.id.as_str()).collect::<Vec<_>>(),
+            ["a", "code-new", "z"]
+        );
+        assert_eq!(apps[1].path, r"D:\Moved\code-new.exe");
+    }
+
+    #[test]
+    fn repaired_entry_does_not_duplicate_an_entry_with_its_new_id() {
+        // 新路径已被另一次索引收录时，修复后只保留一项
+        let mut apps = vec![app("code", "Code"), app("code-new", "Code")];
+        let repaired = moved(&apps[0], "code-new");
+        replace_entry(&mut apps, "code", repaired);
+
+        assert_eq!(apps.len(), 1);
+        assert_eq!(apps[0].id, "code-new");
+        assert_eq!(apps[0].path, r"D:\Moved\code-new.exe");
+    }
+
+    #[test]
+    fn repaired_entry_is_added_when_the_old_one_is_gone() {
+        let mut apps = vec![app("a", "Alpha")];
+        let repaired = moved(&app("code", "Code"), "code");
+        replace_entry(&mut apps, "code", repaired);
+
+        assert_eq!(apps.len(), 2);
+        assert_eq!(apps[1].path, r"D:\Moved\code.exe");
+    }
+
+    fn deferred(id: &str, source: Option<&str>) -> ApplicationInfo {
+        ApplicationInfo {
+            icon_source: source.map(|source| (source.to_string(), 0)),
+            ..app(id, id)
+        }
+    }
+
+    #[test]
+    fn prewarm_fills_the_icons_a_deferred_build_left_empty() {
+        let mut has_icon = deferred("done", Some("done.exe"));
+        has_icon.icon_b64 = "cached".to_string();
+        let index = Mutex::new(vec![
+            has_icon,
+            deferred("code", Some("code.exe")),
+            deferred("broken", Some("broken.exe")),
+            deferred("nosource", None),
+        ]);
+        let extracted = std::cell::RefCell::new(Vec::new());
+
+        let filled = fill_missing_icons(
+            &index,
+            || false,
+            |source, _| {
+                extracted.borrow_mut().push(source.to_string());
+                (source != "broken.exe").then(|| format!("icon:{source}"))
+            },
+        );
+
+        assert_eq!(filled, 1);
+        assert_eq!(*extracted.borrow(), ["code.exe", "broken.exe"]);
+        let apps = index.lock().unwrap();
+        let icons: Vec<&str> = apps.iter().map(|app| app.icon_b64.as_str()).collect();
+        assert_eq!(icons, ["cached", "icon:code.exe", "", ""]);
+    }
+
+    #[test]
+    fn prewarm_stops_once_superseded() {
+        let index = Mutex::new(vec![
+            deferred("a", Some("a.exe")),
+            deferred("b", Some("b.exe")),
+        ]);
+        let calls = std::cell::Cell::new(0);
+
+        let filled = fill_missing_icons(
+            &index,
+            || calls.get() >= 1,
+            |source, _| {
+                calls.set(calls.get() + 1);
+                Some(source.to_string())
+            },
+        );
+
+        assert_eq!(filled, 1);
+        assert!(index.lock().unwrap()[1].icon_b64.is_empty());
+    }
+
+    #[test]
+    fn a_package_that_never_finishes_is_abandoned_after_the_timeout() {
+        let started = std::time::Instant::now();
+        let finished = wait_until(Duration::from_millis(50), Duration::from_millis(1), || {
+            Ok(false)
+        });
+
+        assert!(!finished.unwrap());
+        let elapsed = started.elapsed();
+        assert!(elapsed >= Duration::from_millis(50), "{elapsed:?}");
+        assert!(elapsed < Duration::from_secs(2), "{elapsed:?}");
+    }
+
+    #[test]
+    fn a_slow_package_that_finishes_in_time_is_kept() {
+        let mut polls = 0;
+        let finished = wait_until(Duration::from_secs(5), Duration::from_millis(1), || {
+            polls += 1;
+            Ok(polls == 3)
+        });
+
+        assert!(finished.unwrap());
+        assert_eq!(polls, 3);
+    }
+
+    #[test]
+    fn a_failing_status_check_is_reported() {
+        let result = wait_until(Duration::from_secs(5), Duration::from_millis(1), || {
+            Err(windows::Win32::Foundation::E_FAIL.into())
+        });
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn icons_are_extracted_inline_only_when_enabled_and_not_deferred() {
+        let options = |extract_icons, defer_icons| IndexOptions {
+            extract_icons,
+            defer_icons,
+            ..IndexOptions::default()
+        };
+        assert!(extracts_icons_inline(&options(true, false)));
+        assert!(!extracts_icons_inline(&options(true, true)));
+        assert!(!extracts_icons_inline(&options(false, false)));
+        assert!(!extracts_icons_inline(&options(false, true)));
+    }
+
+    #[test]
+    fn parallel_extraction_reads_each_shared_source_once() {
+        let mut has_icon = deferred("done", Some("done.exe"));
+        has_icon.icon_b64 = "cached".to_string();
+        let mut apps = vec![
+            has_icon,
+            deferred("code", Some("code.exe")),
+            deferred("code-insiders", Some("code.exe")),
+            deferred("paint", Some("paint.exe")),
+            deferred("nosource", None),
+        ];
+        let calls = AtomicUsize::new(0);
+
+        let filled = extract_icons_parallel(&mut apps, |source, _| {
+            calls.fetch_add(1, Ordering::Relaxed);
+            Some(format!("icon:{source}"))
+        });
+
+        assert_eq!(filled, 3);
+        assert_eq!(calls.load(Ordering::Relaxed), 2);
+        let icons: Vec<&str> = apps.iter().map(|app| app.icon_b64.as_str()).collect();
+        assert_eq!(
+            icons,
+            [
+                "cached",
+                "icon:code.exe",
+                "icon:code.exe",
+                "icon:paint.exe",
+                ""
+            ]
+        );
+    }
+}
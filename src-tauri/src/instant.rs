@@ -0,0 +1,57 @@
+use chrono::{DateTime, Datelike, Local, Utc, Weekday};
+
+use crate::i18n::Locale;
+
+/// A locally computed answer; `value` is what gets copied to the clipboard.
+#[derive(Debug, Clone)]
+pub struct InstantAnswer {
+    pub value: String,
+}
+
+/// Recognizes a handful of keywords (`time`, `date`, `now`, `today`, `utc`) and formats the
+/// given clock reading for them. Anything else yields no answer.
+pub fn instant_answer(query: &str, locale: Locale, now: DateTime<Local>) -> Option<InstantAnswer> {
+    let value = match query.trim().to_lowercase().as_str() {
+        "time" | "时间" => format_time(locale, now),
+        "date" | "today" | "日期" | "今天" => format_date(locale, now),
+        "now" | "现在" => format!("{} {}", format_date(locale, now), format_time(locale, now)),
+        "utc" => now
+            .with_timezone(&Utc)
+            .format("%Y-%m-%d %H:%M:%S UTC")
+            .to_string(),
+        _ => return None,
+    };
+    Some(InstantAnswer { value })
+}
+
+fn format_time(locale: Locale, now: DateTime<Local>) -> String {
+    match locale {
+        Locale::ZhCn => now.format("%H:%M:%S").to_string(),
+        Locale::En => now.format("%-I:%M:%S %p").to_string(),
+    }
+}
+
+fn format_date(locale: Locale, now: DateTime<Local>) -> String {
+    match locale {
+        Locale::ZhCn => format!(
+            "{}年{}月{}日 {}",
+            now.year(),
+            now.month(),
+            now.day(),
+            chinese_weekday(now.weekday())
+        ),
+        Locale::En => now.format("%A, %B %-d, %Y").to_string(),
+    }
+}
+
+fn chinese_weekday(weekday: Weekday) -> &'static str {
+    match weekday {
+        Weekday::Mon => "星期一",
+        Weekday::Tue => "星期二",
+        Weekday::Wed => "星期三",
+        Weekday::Thu => "星期四",
+        Weekday::Fri => "星期五",
+        Weekday::Sat => "星期六",
+        Weekday::Sun => "星期日",
+    }
+}
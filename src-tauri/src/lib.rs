@@ -1,29 +1,63 @@
+mod app_cache;
+mod bookmark_watcher;
 mod bookmarks;
+mod clipboard_history;
 mod commands;
 mod config;
+mod config_watcher;
+mod content_search;
+mod custom_commands;
+#[cfg(target_os = "linux")]
+mod desktop_entries;
+mod diagnostics;
+mod double_tap;
+mod drives;
+mod error;
+mod favicons;
+mod file_search;
+mod history;
 mod hotkey;
+mod i18n;
+mod ids;
 mod indexer;
+mod instant;
+#[cfg(target_os = "macos")]
+mod mac_apps;
 mod models;
+mod processes;
+mod reindex_scheduler;
+mod run_history;
+mod search;
 mod state;
+mod steam;
 mod text_utils;
+mod tray;
+mod usage;
+mod validation;
 mod windows_utils;
 
+use std::sync::atomic::Ordering;
+
 use commands::{
-    execute_action, get_settings, submit_query, trigger_reindex, update_hotkey, update_settings,
-    FOCUS_INPUT_EVENT, HIDE_WINDOW_EVENT, OPEN_SETTINGS_EVENT,
+    clear_display_name, clear_run_history, clear_session_hidden, clear_usage_history,
+    create_profile, disable_hotkey, emit_hide_window, enable_hotkey, execute_action,
+    execute_action_elevated, execute_actions, execute_fallback, execute_quick, export_diagnostics,
+    export_settings, get_analytics, get_paths, get_registered_shortcuts, get_settings,
+    health_check, hide_result_for_session, import_settings, list_profiles, preview_query,
+    preview_query_with_config, refresh_icon, reindex_apps, reindex_bookmarks, repair_entry,
+    request_hide, reset_settings, set_display_name, set_launch_args, submit_content_query,
+    submit_file_query, submit_query, submit_query_grouped, switch_profile, sync_input_method,
+    trigger_reindex, update_hotkey, update_settings, validate_config, FocusInputPayload,
+    HideReason, CONFIG_UNAVAILABLE_EVENT, FOCUS_INPUT_EVENT,
 };
 use config::AppConfig;
-use hotkey::bind_hotkey;
-use log::warn;
+use hotkey::{bind_hotkey, bind_mode_hotkeys};
+use log::{error, info, warn};
+use search::QueryMode;
 use state::AppState;
-use tauri::{menu::MenuBuilder, tray::TrayIconBuilder, AppHandle, Emitter, Manager};
+use tauri::{AppHandle, Emitter, Manager};
 
 const MAIN_WINDOW_LABEL: &str = "main";
-const TRAY_ID: &str = "main-tray";
-const MENU_SHOW: &str = "tray-show";
-const MENU_HIDE: &str = "tray-hide";
-const MENU_SETTINGS: &str = "tray-settings";
-const MENU_QUIT: &str = "tray-quit";
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
@@ -36,20 +70,106 @@ pub fn run() {
         .manage(AppState::new())
         .invoke_handler(tauri::generate_handler![
             submit_query,
+            submit_query_grouped,
+            preview_query,
+            preview_query_with_config,
+            submit_content_query,
+            submit_file_query,
             execute_action,
+            execute_action_elevated,
+            execute_actions,
+            execute_fallback,
+            execute_quick,
+            set_display_name,
+            clear_display_name,
+            set_launch_args,
+            disable_hotkey,
+            enable_hotkey,
+            get_registered_shortcuts,
             trigger_reindex,
+            reindex_apps,
+            reindex_bookmarks,
+            repair_entry,
+            refresh_icon,
             get_settings,
+            get_paths,
+            health_check,
+            export_diagnostics,
+            get_analytics,
+            clear_usage_history,
+            clear_run_history,
             update_hotkey,
-            update_settings
+            update_settings,
+            reset_settings,
+            export_settings,
+            import_settings,
+            list_profiles,
+            create_profile,
+            switch_profile,
+            validate_config,
+            sync_input_method,
+            request_hide,
+            hide_result_for_session,
+            clear_session_hidden
         ])
         .setup(|app| {
             let handle = app.handle();
             let state = app.state::<AppState>();
 
+            // 配置目录不可用时设置只在本次运行内有效，需要明确告知用户而不是静默丢失
+            if let Err(err) = config::check_config_persistence(handle) {
+                error!("settings will not be saved: {err}");
+                if let Ok(mut guard) = state.config_persistence_error.lock() {
+                    *guard = Some(err.to_string());
+                }
+                let _ = handle.emit(CONFIG_UNAVAILABLE_EVENT, err);
+            }
+
             let config = AppConfig::load(handle);
             if let Ok(mut guard) = state.config.lock() {
                 *guard = config.clone();
             }
+            if let Ok(mut guard) = state.usage_log.lock() {
+                *guard = usage::load(handle);
+                if let Ok(mut counts) = state.launch_counts.lock() {
+                    *counts = usage::launch_counts(&guard);
+                }
+            }
+            if let Ok(mut guard) = state.run_history.lock() {
+                *guard = match run_history::load(handle) {
+                    Some(entries) => entries,
+                    None => {
+                        // 旧版本把运行记录存在设置里，首次启动时迁移到单独的文件
+                        let entries = config.legacy_run_history.clone();
+                        if !entries.is_empty() {
+                            if let Err(err) = run_history::save(handle, &entries) {
+                                warn!("failed to migrate run history: {err}");
+                            }
+                        }
+                        entries
+                    }
+                };
+            }
+            // 先载入上次运行保存的应用索引，启动后立即可搜索；前端随后触发的重建会刷新它
+            if let Some(apps) = app_cache::load() {
+                info!("已从缓存载入 {} 个应用", apps.len());
+                if let Ok(mut guard) = state.app_index.lock() {
+                    *guard = apps;
+                }
+            }
+
+            bookmark_watcher::spawn_bookmark_watcher(handle.clone());
+
+            reindex_scheduler::spawn_reindex_scheduler(handle.clone());
+
+            config_watcher::spawn_config_watcher(handle.clone());
+
+            favicons::spawn_favicon_refresher(state.bookmark_index.clone(), state.config.clone());
+
+            clipboard_history::spawn_clipboard_monitor(
+                state.clipboard_history.clone(),
+                state.config.clone(),
+            );
 
             if let Err(err) = windows_utils::configure_launch_on_startup(config.launch_on_startup) {
                 warn!("failed to sync launch-on-startup setting: {err}");
@@ -62,40 +182,12 @@ pub fn run() {
                     config.global_hotkey, err
                 );
             }
+            if let Err(err) = bind_mode_hotkeys(handle, &state, &config.mode_hotkeys) {
+                warn!("failed to register mode hotkeys: {err}");
+            }
+            double_tap::spawn_double_tap_hook(handle.clone());
 
-            let tray_menu = MenuBuilder::new(app)
-                .text(MENU_SHOW, "显示窗口")
-                .text(MENU_HIDE, "隐藏窗口")
-                .separator()
-                .text(MENU_SETTINGS, "打开设置")
-                .separator()
-                .text(MENU_QUIT, "退出")
-                .build()?;
-
-            let tray_builder = if let Some(icon) = app.default_window_icon().cloned() {
-                TrayIconBuilder::with_id(TRAY_ID).icon(icon)
-            } else {
-                TrayIconBuilder::with_id(TRAY_ID)
-            };
-
-            tray_builder
-                .menu(&tray_menu)
-                .tooltip("RustLauncher")
-                .on_menu_event(|app_handle, event| match event.id().as_ref() {
-                    MENU_SHOW => show_window(app_handle),
-                    MENU_HIDE => {
-                        // 通过事件通知前端统一执行“重置搜索 + 隐藏窗口”逻辑
-                        let _ = app_handle.emit(HIDE_WINDOW_EVENT, ());
-                    }
-                    MENU_SETTINGS => {
-                        let _ = app_handle.emit(OPEN_SETTINGS_EVENT, ());
-                    }
-                    MENU_QUIT => {
-                        app_handle.exit(0);
-                    }
-                    _ => {}
-                })
-                .build(app)?;
+            tray::create_tray(app, config.locale)?;
 
             Ok(())
         })
@@ -107,8 +199,8 @@ pub fn run() {
                 if let WindowEvent::Focused(false) = event {
                     let app_handle = window.app_handle();
 
-                    // 通知前端重置搜索状态
-                    let _ = app_handle.emit(HIDE_WINDOW_EVENT, ());
+                    // 通知前端重置搜索状态（是否清空由 clear_query_on_hide 决定）
+                    emit_hide_window(app_handle, HideReason::Blur);
 
                     // 隐藏主窗口
                     if let Some(main_window) = app_handle.get_webview_window(MAIN_WINDOW_LABEL) {
@@ -117,24 +209,160 @@ pub fn run() {
                 }
             }
         })
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|_app_handle, event| {
+            // 无论从托盘还是系统关机退出，都先卸载键盘钩子
+            if let tauri::RunEvent::Exit = event {
+                double_tap::stop_double_tap_hook();
+            }
+        });
 }
 
 pub(crate) fn show_window(app_handle: &AppHandle) {
     if let Some(window) = app_handle.get_webview_window(MAIN_WINDOW_LABEL) {
         let _ = window.show();
         let _ = window.set_focus();
-        if should_force_english_input(app_handle) {
-            windows_utils::switch_to_english_input_method();
-        }
-        let _ = app_handle.emit(FOCUS_INPUT_EVENT, ());
+        // 唤起时搜索框处于默认模式，按该模式的配置决定是否切换输入法
+        force_english_on_show(app_handle, QueryMode::All);
+        let _ = app_handle.emit(FOCUS_INPUT_EVENT, FocusInputPayload { mode: None });
     }
 }
 
-fn should_force_english_input(app_handle: &AppHandle) -> bool {
+/// Shows the launcher like [`show_window`] and tells the frontend to switch to `mode`, for
+/// the mode hotkeys.
+pub(crate) fn show_window_in_mode(app_handle: &AppHandle, mode: QueryMode) {
+    if let Some(window) = app_handle.get_webview_window(MAIN_WINDOW_LABEL) {
+        let _ = window.show();
+        let _ = window.set_focus();
+        force_english_on_show(app_handle, mode);
+        let _ = app_handle.emit(
+            FOCUS_INPUT_EVENT,
+            FocusInputPayload {
+                mode: Some(mode.label()),
+            },
+        );
+    }
+}
+
+/// Forces English input when the window is shown, at most once per hotkey activation when
+/// `force_english_first_show_only` is set, and never over a query typed in a non-Latin script.
+fn force_english_on_show(app_handle: &AppHandle, mode: QueryMode) {
+    let due = match app_handle.try_state::<AppState>() {
+        Some(state) => english_switch_due(&state, mode),
+        None => true,
+    };
+    if due {
+        windows_utils::switch_to_english_input_method();
+    }
+}
+
+/// Whether showing the window in `mode` should switch to English input now. Claims the
+/// switch for the current activation when `force_english_first_show_only` is set.
+fn english_switch_due(state: &AppState, mode: QueryMode) -> bool {
+    let (wanted, first_show_only) = state
+        .config
+        .lock()
+        .map(|cfg| {
+            (
+                cfg.should_force_english(mode),
+                cfg.force_english_first_show_only,
+            )
+        })
+        .unwrap_or((true, true));
+    if !wanted || state.query_non_latin.load(Ordering::Acquire) {
+        return false;
+    }
+    if first_show_only
+        && state
+            .english_forced_this_activation
+            .swap(true, Ordering::AcqRel)
+    {
+        return false;
+    }
+    true
+}
+
+/// Letters beyond the Latin blocks, e.g. CJK or Cyrillic.
+pub(crate) fn has_non_latin(text: &str) -> bool {
+    text.chars()
+        .any(|ch| ch.is_alphabetic() && u32::from(ch) > 0x024F)
+}
+
+pub(crate) fn should_force_english_input(app_handle: &AppHandle, mode: QueryMode) -> bool {
     app_handle
         .try_state::<AppState>()
-        .and_then(|state| state.config.lock().ok().map(|cfg| cfg.force_english_input))
+        .and_then(|state| {
+            state
+                .config
+                .lock()
+                .ok()
+                .map(|cfg| cfg.should_force_english(mode))
+        })
         .unwrap_or(true)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state_forcing_english(first_show_only: bool) -> AppState {
+        let state = AppState::new();
+        {
+            let mut config = state.config.lock().unwrap();
+            config.force_english_input = true;
+            config.force_english_first_show_only = first_show_only;
+        }
+        state
+    }
+
+    /// What a hotkey press does before showing the window.
+    fn new_activation(state: &AppState) {
+        state
+            .english_forced_this_activation
+            .store(false, Ordering::Release);
+    }
+
+    #[test]
+    fn english_is_forced_once_per_activation() {
+        let state = state_forcing_english(true);
+        new_activation(&state);
+        assert!(english_switch_due(&state, QueryMode::All));
+        // 同一次唤起中的再次显示不再切换
+        assert!(!english_switch_due(&state, QueryMode::All));
+        assert!(!english_switch_due(&state, QueryMode::Bookmark));
+
+        new_activation(&state);
+        assert!(english_switch_due(&state, QueryMode::All));
+    }
+
+    #[test]
+    fn every_show_forces_english_without_the_guard() {
+        let state = state_forcing_english(false);
+        new_activation(&state);
+        assert!(english_switch_due(&state, QueryMode::All));
+        assert!(english_switch_due(&state, QueryMode::All));
+    }
+
+    #[test]
+    fn non_latin_queries_and_disabled_modes_keep_the_input_method() {
+        let state = state_forcing_english(true);
+        state.config.lock().unwrap().force_english_by_mode.bookmark = Some(false);
+        new_activation(&state);
+        assert!(!english_switch_due(&state, QueryMode::Bookmark));
+
+        state.query_non_latin.store(true, Ordering::Release);
+        assert!(!english_switch_due(&state, QueryMode::All));
+        // 跳过的显示不占用本次唤起的切换
+        state.query_non_latin.store(false, Ordering::Release);
+        assert!(english_switch_due(&state, QueryMode::All));
+    }
+
+    #[test]
+    fn non_latin_means_letters_beyond_latin_blocks() {
+        assert!(has_non_latin("微信"));
+        assert!(has_non_latin("Привет"));
+        assert!(!has_non_latin("café 123"));
+        assert!(!has_non_latin("→ !?"));
+    }
+}
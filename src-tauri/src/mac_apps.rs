@@ -0,0 +1,174 @@
+use std::{
+    collections::HashSet,
+    env, fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use log::debug;
+
+use crate::{
+    ids,
+    models::{AppType, ApplicationInfo},
+    text_utils::extend_keywords_with_pinyin,
+};
+
+/// Folders below an applications directory that are searched one level deeper, such as
+/// `/Applications/Utilities`.
+const NESTED_FOLDERS: &[&str] = &["Utilities"];
+
+/// Lists the `.app` bundles in `/Applications`, `/System/Applications` and `~/Applications`.
+/// The name comes from `CFBundleDisplayName` or `CFBundleName` in the bundle's `Info.plist`,
+/// falling back to the bundle's file name when the plist is binary or has neither key.
+pub fn enumerate_app_bundles() -> Vec<ApplicationInfo> {
+    let mut seen = HashSet::new();
+    let mut apps = Vec::new();
+    for dir in application_dirs() {
+        for bundle in app_bundles(&dir) {
+            let key = bundle.to_string_lossy().to_lowercase();
+            if !seen.insert(key) {
+                continue;
+            }
+            if let Some(app) = bundle_to_application(&bundle) {
+                apps.push(app);
+            }
+        }
+    }
+    debug!("indexed {} app bundles", apps.len());
+    apps
+}
+
+/// Opens a bundle through Launch Services, like double-clicking it in Finder.
+pub fn launch(bundle_path: &str) -> Result<(), String> {
+    Command::new("open")
+        .arg("-a")
+        .arg(bundle_path)
+        .spawn()
+        .map(|_| ())
+        .map_err(|err| format!("无法启动 {bundle_path}: {err}"))
+}
+
+fn application_dirs() -> Vec<PathBuf> {
+    let mut dirs = vec![
+        PathBuf::from("/Applications"),
+        PathBuf::from("/System/Applications"),
+    ];
+    if let Some(home) = env::var_os("HOME") {
+        dirs.push(PathBuf::from(home).join("Applications"));
+    }
+    dirs.retain(|dir| dir.is_dir());
+    dirs
+}
+
+fn app_bundles(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut bundles = Vec::new();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if is_app_bundle(&path) {
+            bundles.push(path);
+        } else if path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| NESTED_FOLDERS.contains(&name))
+        {
+            bundles.extend(app_bundles(&path));
+        }
+    }
+    bundles
+}
+
+fn is_app_bundle(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == "app") && path.is_dir()
+}
+
+fn bundle_to_application(bundle: &Path) -> Option<ApplicationInfo> {
+    let file_stem = bundle.file_stem()?.to_str()?.to_string();
+    let plist = fs::read_to_string(bundle.join("Contents/Info.plist")).unwrap_or_default();
+    let name = plist_string(&plist, "CFBundleDisplayName")
+        .or_else(|| plist_string(&plist, "CFBundleName"))
+        .filter(|name| !name.trim().is_empty())
+        .unwrap_or_else(|| file_stem.clone());
+
+    let mut keywords = vec![name.clone(), file_stem];
+    if let Some(identifier) = plist_string(&plist, "CFBundleIdentifier") {
+        keywords.push(identifier);
+    }
+    extend_keywords_with_pinyin(&mut keywords);
+    keywords.sort();
+    keywords.dedup();
+
+    let path = bundle.to_string_lossy().into_owned();
+    Some(ApplicationInfo {
+        id: ids::mac_app_id(&path),
+        name,
+        path,
+        source_path: None,
+        app_type: AppType::MacApp,
+        icon_b64: String::new(),
+        description: None,
+        keywords,
+        working_directory: None,
+        arguments: None,
+        origin: None,
+        icon_source: None,
+        launch_args: None,
+        indexed_name: None,
+    })
+}
+
+/// The `<string>` value that follows `<key>key</key>` in an XML property list. Only the flat
+/// top-level keys of `Info.plist` are needed, so this is a plain text scan rather than a parser.
+fn plist_string(plist: &str, key: &str) -> Option<String> {
+    let marker = format!("<key>{key}</key>");
+    let rest = plist[plist.find(&marker)? + marker.len()..].trim_start();
+    let value = rest.strip_prefix("<string>")?;
+    let value = &value[..value.find("</string>")?];
+    Some(unescape_xml(value.trim()))
+}
+
+fn unescape_xml(value: &str) -> String {
+    value
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INFO_PLIST: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<plist version="1.0">
+<dict>
+	<key>CFBundleDisplayName</key>
+	<string>Visual Studio Code</string>
+	<key>CFBundleIdentifier</key>
+	<string>com.microsoft.VSCode</string>
+	<key>CFBundleName</key>
+	<string>Code &amp; More</string>
+</dict>
+</plist>"#;
+
+    #[test]
+    fn reads_string_values_by_key() {
+        assert_eq!(
+            plist_string(INFO_PLIST, "CFBundleDisplayName").as_deref(),
+            Some("Visual Studio Code")
+        );
+        assert_eq!(
+            plist_string(INFO_PLIST, "CFBundleName").as_deref(),
+            Some("Code & More")
+        );
+    }
+
+    #[test]
+    fn missing_key_or_binary_plist_yields_none() {
+        assert_eq!(plist_string(INFO_PLIST, "CFBundleVersion"), None);
+        assert_eq!(plist_string("bplist00\u{1}", "CFBundleName"), None);
+    }
+}
@@ -4,6 +4,10 @@ use serde::{Deserialize, Serialize};
 pub enum AppType {
     Win32,
     Uwp,
+    /// A freedesktop `.desktop` entry on Linux; `path` holds its `Exec` line.
+    Desktop,
+    /// A macOS `.app` bundle; `path` is the bundle directory.
+    MacApp,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -18,6 +22,47 @@ pub struct ApplicationInfo {
     pub keywords: Vec<String>,
     pub working_directory: Option<String>,
     pub arguments: Option<String>,
+    /// Where the entry was read from, so [`crate::indexer::reload_entry`] can read it again.
+    #[serde(default)]
+    pub origin: Option<AppOrigin>,
+    /// File and index the icon is extracted from, kept so a deferred build can load it later.
+    #[serde(skip)]
+    pub icon_source: Option<(String, i32)>,
+    /// Saved arguments passed on every launch, already quoted; see `set_launch_args`.
+    #[serde(default)]
+    pub launch_args: Option<String>,
+    /// Name and keywords from the index source while a display-name override replaces them,
+    /// so clearing the override restores them without a reindex.
+    #[serde(default)]
+    pub indexed_name: Option<IndexedName>,
+}
+
+/// What [`crate::indexer::apply_display_names`] replaced on an [`ApplicationInfo`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IndexedName {
+    pub name: String,
+    pub keywords: Vec<String>,
+}
+
+/// The index source an [`ApplicationInfo`] came from.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum AppOrigin {
+    /// A `.lnk` or `.url` file under a Start Menu root.
+    StartMenu {
+        link_path: String,
+    },
+    /// An uninstall entry; `root` is `HKLM` or `HKCU`.
+    Registry {
+        root: String,
+        subkey: String,
+        entry: String,
+    },
+    Uwp,
+    /// A game from a Steam library manifest, launched through `steam://rungameid/<app_id>`.
+    Steam {
+        app_id: String,
+    },
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -28,4 +73,126 @@ pub struct SearchResult {
     pub icon: String,
     pub score: i64,
     pub action_id: String,
+    /// Extra actions offered on this result; each `id` can be passed to `execute_action`.
+    pub secondary_actions: Vec<ResultAction>,
+    /// Set for Win32 apps that currently have a running process.
+    pub running: bool,
+    /// 1-9 for the first nine results in display order, for Alt+number quick select.
+    pub quick_key: Option<u8>,
+    /// The target is on a drive letter that is currently not mounted, e.g. an unplugged
+    /// external disk, so launching it will fail.
+    pub drive_unavailable: bool,
+}
+
+/// An app entry identified by id and display name, as reported by index change events.
+#[derive(Debug, Clone, Serialize)]
+pub struct AppSummary {
+    pub id: String,
+    pub name: String,
+}
+
+/// What changed between two builds of the app index, keyed by id.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct IndexDiff {
+    pub added: Vec<AppSummary>,
+    pub removed: Vec<AppSummary>,
+}
+
+/// Structured reply of `submit_query`.
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchResponse {
+    pub results: Vec<SearchResult>,
+    /// Set while any index is being rebuilt: the results come from the previous index and may
+    /// miss recent changes.
+    pub stale_index: bool,
+    /// Increases with every published result set; pass it back to `execute_quick`.
+    pub generation: u64,
+}
+
+/// Category a result is filed under by `submit_query_grouped`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ResultGroupKind {
+    Applications,
+    /// Bookmarks, bookmark folders and browser history.
+    Bookmarks,
+    Web,
+    Url,
+    /// Instant answers such as calculations and unit conversions.
+    Calculator,
+    /// Custom commands, run commands, paths, files, clipboard entries and hints.
+    Other,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ResultGroup {
+    pub kind: ResultGroupKind,
+    pub results: Vec<SearchResult>,
+}
+
+/// Reply of `submit_query_grouped`: the results of [`SearchResponse`] bucketed by category.
+#[derive(Debug, Clone, Serialize)]
+pub struct GroupedSearchResponse {
+    /// Ordered by each group's best result; results keep their ranking within a group.
+    pub groups: Vec<ResultGroup>,
+    pub stale_index: bool,
+    pub generation: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ResultAction {
+    pub id: String,
+    pub title: String,
+    /// Destructive actions (e.g. killing processes) must be confirmed by the user first.
+    pub requires_confirmation: bool,
+}
+
+/// Result of `execute_action`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum ExecuteOutcome {
+    Done,
+    /// Nothing ran yet; ask the user and call again with an explicit `running_choice`.
+    NeedsConfirmation {
+        prompt: String,
+    },
+    /// Nothing ran yet because the action opens many targets at once; call again with
+    /// `approved: true` once the user agreed.
+    NeedsApproval {
+        prompt: String,
+    },
+}
+
+/// Outcome of a single health check.
+#[derive(Debug, Clone, Serialize)]
+pub struct HealthCheck {
+    pub name: &'static str,
+    pub ok: bool,
+    /// What was found when the check passed, or why it failed.
+    pub detail: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HealthReport {
+    /// True only when every check passed.
+    pub ok: bool,
+    pub checks: Vec<HealthCheck>,
+}
+
+/// Resolved on-disk locations used by the app; `None` when a location can't be determined.
+#[derive(Debug, Clone, Serialize)]
+pub struct AppPaths {
+    pub config_file: Option<String>,
+    /// False when settings can't be saved; `config_error` then says why.
+    pub config_persistent: bool,
+    pub config_error: Option<String>,
+    pub icon_cache_dir: Option<String>,
+    pub favicon_cache_dir: Option<String>,
+    /// Directory of every profile's settings file; display names and launch arguments are
+    /// stored in those.
+    pub profiles_dir: Option<String>,
+    pub active_profile_file: Option<String>,
+    pub usage_file: Option<String>,
+    pub run_history_file: Option<String>,
+    pub app_index_cache_file: Option<String>,
 }
@@ -0,0 +1,226 @@
+use std::{
+    collections::HashSet,
+    mem::size_of,
+    path::Path,
+    thread,
+    time::{Duration, Instant},
+};
+
+use log::warn;
+use windows::{
+    core::PWSTR,
+    Win32::{
+        Foundation::{CloseHandle, BOOL, FALSE, HANDLE, HWND, LPARAM, TRUE},
+        System::{
+            Diagnostics::ToolHelp::{
+                CreateToolhelp32Snapshot, Process32FirstW, Process32NextW, PROCESSENTRY32W,
+                TH32CS_SNAPPROCESS,
+            },
+            Threading::{
+                OpenProcess, QueryFullProcessImageNameW, TerminateProcess, PROCESS_NAME_WIN32,
+                PROCESS_QUERY_LIMITED_INFORMATION, PROCESS_TERMINATE,
+            },
+        },
+        UI::WindowsAndMessaging::{
+            EnumWindows, GetWindow, GetWindowThreadProcessId, IsIconic, IsWindowVisible,
+            SetForegroundWindow, ShowWindow, GW_OWNER, SW_RESTORE,
+        },
+    },
+};
+
+use crate::models::ApplicationInfo;
+
+/// How long a process snapshot is reused before taking a new one.
+const SNAPSHOT_TTL: Duration = Duration::from_secs(3);
+
+/// Time given to terminated processes to release their files before relaunching.
+const RESTART_GRACE_PERIOD: Duration = Duration::from_millis(500);
+
+#[derive(Debug, Clone)]
+pub struct ProcessInfo {
+    pub pid: u32,
+    pub exe_path: String,
+}
+
+/// Takes a toolhelp snapshot of the running processes whose image path can be resolved.
+pub fn running_processes() -> Vec<ProcessInfo> {
+    let mut processes = Vec::new();
+
+    unsafe {
+        let snapshot = match CreateToolhelp32Snapshot(TH32CS_SNAPPROCESS, 0) {
+            Ok(handle) => handle,
+            Err(err) => {
+                warn!("failed to snapshot processes: {err}");
+                return processes;
+            }
+        };
+
+        let mut entry = PROCESSENTRY32W {
+            dwSize: size_of::<PROCESSENTRY32W>() as u32,
+            ..Default::default()
+        };
+        let mut has_entry = Process32FirstW(snapshot, &mut entry).is_ok();
+        while has_entry {
+            if let Some(exe_path) = process_image_path(entry.th32ProcessID) {
+                processes.push(ProcessInfo {
+                    pid: entry.th32ProcessID,
+                    exe_path,
+                });
+            }
+            has_entry = Process32NextW(snapshot, &mut entry).is_ok();
+        }
+
+        let _ = CloseHandle(snapshot);
+    }
+
+    processes
+}
+
+/// Caches the running executables briefly so queries don't snapshot on every keystroke.
+#[derive(Debug, Default)]
+pub struct ProcessCache {
+    taken_at: Option<Instant>,
+    exe_paths: HashSet<String>,
+}
+
+impl ProcessCache {
+    /// Normalized paths of running executables, refreshed when older than the TTL.
+    pub fn running_exe_paths(&mut self) -> HashSet<String> {
+        let fresh = self
+            .taken_at
+            .is_some_and(|taken_at| taken_at.elapsed() < SNAPSHOT_TTL);
+        if !fresh {
+            self.exe_paths = running_processes()
+                .iter()
+                .map(|process| normalize_exe_path(&process.exe_path))
+                .collect();
+            self.taken_at = Some(Instant::now());
+        }
+        self.exe_paths.clone()
+    }
+}
+
+/// Executable a Win32 app runs as: the shortcut target for Start Menu entries, otherwise
+/// the indexed path itself.
+pub fn app_executable(app: &ApplicationInfo) -> &str {
+    app.source_path.as_deref().unwrap_or(&app.path)
+}
+
+/// Case- and separator-insensitive form of an executable path used for comparisons.
+pub fn normalize_exe_path(path: &str) -> String {
+    path.trim().replace('/', "\\").to_lowercase()
+}
+
+/// Whether two executable paths point at the same file.
+pub fn same_executable(left: &str, right: &str) -> bool {
+    normalize_exe_path(left) == normalize_exe_path(right)
+}
+
+/// Terminates every process running `exe_path`; returns how many were killed.
+pub fn terminate_processes_by_path(exe_path: &str) -> Result<usize, String> {
+    let targets: Vec<u32> = running_processes()
+        .into_iter()
+        .filter(|process| same_executable(&process.exe_path, exe_path))
+        .map(|process| process.pid)
+        .collect();
+
+    let mut terminated = 0;
+    for pid in targets {
+        unsafe {
+            let handle = OpenProcess(PROCESS_TERMINATE, false, pid)
+                .map_err(|err| format!("无法结束进程 {pid}: {err}"))?;
+            let result = TerminateProcess(handle, 1);
+            let _ = CloseHandle(handle);
+            result.map_err(|err| format!("无法结束进程 {pid}: {err}"))?;
+        }
+        terminated += 1;
+    }
+
+    if terminated > 0 {
+        thread::sleep(RESTART_GRACE_PERIOD);
+    }
+    Ok(terminated)
+}
+
+/// Whether any process is currently running `exe_path`.
+pub fn is_running(exe_path: &str) -> bool {
+    running_processes()
+        .iter()
+        .any(|process| same_executable(&process.exe_path, exe_path))
+}
+
+/// Brings the first visible top-level window of a process running `exe_path` to the front,
+/// restoring it if minimized. Returns `false` when there is no such window, e.g. for apps
+/// that only live in the tray.
+pub fn focus_running_window(exe_path: &str) -> bool {
+    let pids: HashSet<u32> = running_processes()
+        .into_iter()
+        .filter(|process| same_executable(&process.exe_path, exe_path))
+        .map(|process| process.pid)
+        .collect();
+    if pids.is_empty() {
+        return false;
+    }
+
+    let mut search = WindowSearch { pids, found: None };
+    unsafe {
+        // 回调找到窗口后返回 FALSE 提前结束枚举，此时 EnumWindows 会报错，可以忽略
+        let _ = EnumWindows(
+            Some(find_process_window),
+            LPARAM(&mut search as *mut WindowSearch as isize),
+        );
+        let Some(hwnd) = search.found else {
+            return false;
+        };
+        if IsIconic(hwnd).as_bool() {
+            let _ = ShowWindow(hwnd, SW_RESTORE);
+        }
+        SetForegroundWindow(hwnd).as_bool()
+    }
+}
+
+struct WindowSearch {
+    pids: HashSet<u32>,
+    found: Option<HWND>,
+}
+
+unsafe extern "system" fn find_process_window(hwnd: HWND, lparam: LPARAM) -> BOOL {
+    let search = &mut *(lparam.0 as *mut WindowSearch);
+    if !IsWindowVisible(hwnd).as_bool() {
+        return TRUE;
+    }
+    // 跳过有所有者的窗口（对话框、工具窗口等），只切换到主窗口
+    if GetWindow(hwnd, GW_OWNER).is_ok_and(|owner| !owner.is_invalid()) {
+        return TRUE;
+    }
+
+    let mut pid = 0u32;
+    GetWindowThreadProcessId(hwnd, Some(&mut pid));
+    if search.pids.contains(&pid) {
+        search.found = Some(hwnd);
+        return FALSE;
+    }
+    TRUE
+}
+
+fn process_image_path(pid: u32) -> Option<String> {
+    if pid == 0 {
+        return None;
+    }
+
+    unsafe {
+        let handle: HANDLE = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid).ok()?;
+        let mut buffer = [0u16; 1024];
+        let mut length = buffer.len() as u32;
+        let result = QueryFullProcessImageNameW(
+            handle,
+            PROCESS_NAME_WIN32,
+            PWSTR(buffer.as_mut_ptr()),
+            &mut length,
+        );
+        let _ = CloseHandle(handle);
+        result.ok()?;
+        let path = String::from_utf16_lossy(&buffer[..length as usize]);
+        Path::new(&path).is_absolute().then_some(path)
+    }
+}
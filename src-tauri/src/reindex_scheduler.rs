@@ -0,0 +1,53 @@
+use std::{
+    thread,
+    time::{Duration, Instant},
+};
+
+use log::{info, warn};
+use tauri::{AppHandle, Manager};
+
+use crate::{commands::start_full_reindex, state::AppState};
+
+/// How often the scheduler wakes to re-read the interval and check whether a run is due.
+const POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Spawns the background task that rebuilds the indexes every `reindex_interval_minutes`.
+///
+/// The interval is re-read on every wake-up, so changing it or setting it to 0 takes effect
+/// without a restart. Runs go through the same reindex helpers as `trigger_reindex`, which
+/// skip any index whose previous rebuild is still in progress.
+pub fn spawn_reindex_scheduler(app_handle: AppHandle) {
+    let spawned = thread::Builder::new()
+        .name("reindex-scheduler".into())
+        .spawn(move || {
+            let mut last_run = Instant::now();
+            loop {
+                thread::sleep(POLL_INTERVAL);
+
+                let Some(state) = app_handle.try_state::<AppState>() else {
+                    continue;
+                };
+                let minutes = state
+                    .config
+                    .lock()
+                    .map(|cfg| cfg.reindex_interval_minutes)
+                    .unwrap_or(0);
+                if minutes == 0 {
+                    // 关闭期间不累计时间，重新开启后从此刻起计算间隔
+                    last_run = Instant::now();
+                    continue;
+                }
+                if last_run.elapsed() < Duration::from_secs(minutes.saturating_mul(60)) {
+                    continue;
+                }
+
+                last_run = Instant::now();
+                info!("定时刷新索引（间隔 {minutes} 分钟）");
+                start_full_reindex(&app_handle, &state);
+            }
+        });
+
+    if let Err(err) = spawned {
+        warn!("failed to spawn reindex scheduler: {err}");
+    }
+}
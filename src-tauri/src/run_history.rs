@@ -0,0 +1,56 @@
+use std::{fs, path::PathBuf};
+
+use tauri::{AppHandle, Manager};
+
+const RUN_HISTORY_FILE: &str = "run_history.json";
+/// Entries kept in the run history.
+pub const MAX_RUN_HISTORY: usize = 20;
+
+/// Reads the command lines started in run mode, newest first. `None` when the file doesn't
+/// exist yet, so entries from the old `run_history` setting can be carried over.
+pub fn load(handle: &AppHandle) -> Option<Vec<String>> {
+    let content = fs::read_to_string(run_history_path(handle)?).ok()?;
+    Some(serde_json::from_str(&content).unwrap_or_default())
+}
+
+pub fn save(handle: &AppHandle, entries: &[String]) -> Result<(), String> {
+    let path = run_history_path(handle).ok_or_else(|| "无法确定配置目录".to_string())?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|err| err.to_string())?;
+    }
+    let data = serde_json::to_string(entries).map_err(|err| err.to_string())?;
+    fs::write(path, data).map_err(|err| err.to_string())
+}
+
+/// Moves `command` to the front, dropping the oldest entries beyond [`MAX_RUN_HISTORY`].
+pub fn push_command(entries: &mut Vec<String>, command: &str) {
+    entries.retain(|entry| entry != command);
+    entries.insert(0, command.to_string());
+    entries.truncate(MAX_RUN_HISTORY);
+}
+
+pub(crate) fn run_history_path(handle: &AppHandle) -> Option<PathBuf> {
+    handle
+        .path()
+        .app_config_dir()
+        .ok()
+        .map(|dir| dir.join(RUN_HISTORY_FILE))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn push_command_moves_repeats_to_the_front_and_caps_the_list() {
+        let mut entries = vec!["ping".to_string(), "notepad".to_string()];
+        push_command(&mut entries, "notepad");
+        assert_eq!(entries, ["notepad", "ping"]);
+
+        for index in 0..MAX_RUN_HISTORY {
+            push_command(&mut entries, &format!("cmd {index}"));
+        }
+        assert_eq!(entries.len(), MAX_RUN_HISTORY);
+        assert_eq!(entries[0], format!("cmd {}", MAX_RUN_HISTORY - 1));
+    }
+}
@@ -0,0 +1,2078 @@
+use std::{
+    collections::{HashMap, HashSet},
+    path::Path,
+};
+
+use chrono::{DateTime, Local};
+use fuzzy_matcher::skim::SkimMatcherV2;
+use fuzzy_matcher::FuzzyMatcher;
+use regex::{Regex, RegexBuilder};
+
+use crate::{
+    bookmarks::{BookmarkEntry, BookmarkFolder},
+    clipboard_history::ClipboardEntry,
+    config::{AppConfig, EmptyEnterAction, MatchAlgorithm, SEARCH_QUERY_PLACEHOLDER},
+    content_search::ContentMatch,
+    custom_commands::{
+        combine_launch_arguments, join_windows_arguments, split_windows_arguments, ExpandedCommand,
+    },
+    drives,
+    file_search::FileMatch,
+    history::HistoryEntry,
+    i18n::{tr, tr_with, Locale},
+    ids,
+    instant::instant_answer,
+    models::{
+        AppOrigin, AppType, ApplicationInfo, ResultAction, ResultGroup, ResultGroupKind,
+        SearchResult,
+    },
+    processes::{app_executable, normalize_exe_path},
+    state::PendingAction,
+    text_utils::fold_accents,
+};
+
+/// Search scope selected by the frontend mode prefix.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QueryMode {
+    All,
+    Bookmark,
+    Application,
+    Search,
+    /// Files and folders, served by `submit_file_query` rather than [`rank_results`].
+    File,
+    /// Recently copied text, served by [`clipboard_results`] rather than [`rank_results`].
+    Clipboard,
+    /// A raw command line, served by [`run_results`] rather than [`rank_results`].
+    Run,
+}
+
+impl QueryMode {
+    pub fn from_option(mode: Option<String>) -> Self {
+        match mode
+            .as_deref()
+            .map(|value| value.trim().to_lowercase())
+            .as_deref()
+        {
+            Some("bookmark") | Some("bookmarks") | Some("b") => Self::Bookmark,
+            Some("app") | Some("apps") | Some("application") | Some("r") => Self::Application,
+            Some("search") | Some("s") => Self::Search,
+            Some("file") | Some("files") | Some("f") => Self::File,
+            Some("clipboard") | Some("clip") | Some("v") => Self::Clipboard,
+            Some("run") | Some("cmd") | Some("c") => Self::Run,
+            _ => Self::All,
+        }
+    }
+
+    /// Stable name used when the mode is recorded, e.g. in the usage log.
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::All => "all",
+            Self::Bookmark => "bookmark",
+            Self::Application => "app",
+            Self::Search => "search",
+            Self::File => "file",
+            Self::Clipboard => "clipboard",
+            Self::Run => "run",
+        }
+    }
+
+    pub fn allows_bookmarks(&self) -> bool {
+        matches!(self, Self::All | Self::Bookmark)
+    }
+
+    pub fn allows_applications(&self) -> bool {
+        matches!(self, Self::All | Self::Application)
+    }
+
+    pub fn allows_web_search(&self) -> bool {
+        matches!(self, Self::All | Self::Search)
+    }
+}
+
+/// Index snapshots handed to [`rank_results`]. Providers that are disabled or gated out by the
+/// query mode are `None`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SearchSources<'a> {
+    pub apps: Option<&'a [ApplicationInfo]>,
+    pub bookmarks: Option<&'a [BookmarkEntry]>,
+    /// Folders of `bookmarks`, offered as "open all" results.
+    pub bookmark_folders: Option<&'a [BookmarkFolder]>,
+    pub history: Option<&'a [HistoryEntry]>,
+    /// Normalized executable paths of running processes (see [`normalize_exe_path`]).
+    pub running_exes: Option<&'a HashSet<String>>,
+    /// Drive letters currently mounted; `None` skips the drive check.
+    pub available_drives: Option<&'a HashSet<char>>,
+    /// Result ids the user hid for the current session.
+    pub hidden_ids: Option<&'a HashSet<String>>,
+    /// Launch counts by app or bookmark id (see [`crate::usage::launch_counts`]).
+    pub launch_counts: Option<&'a HashMap<String, u32>>,
+    /// Clock reading for instant answers; `None` disables them.
+    pub now: Option<DateTime<Local>>,
+}
+
+/// Scores one candidate string against the query with the configured [`MatchAlgorithm`].
+/// New algorithms get a [`MatcherKind`] variant; the providers only call
+/// [`Matcher::fuzzy_match`].
+struct Matcher {
+    kind: MatcherKind,
+    /// Fold diacritics on both sides before matching, see [`fold_accents`].
+    fold_accents: bool,
+}
+
+enum MatcherKind {
+    Skim(SkimMatcherV2),
+    Substring,
+    /// A `/pattern/` query; the pattern passed to [`Matcher::fuzzy_match`] is ignored.
+    Regex(Regex),
+}
+
+impl Matcher {
+    fn new(config: &AppConfig) -> Self {
+        let kind = match config.match_algorithm {
+            MatchAlgorithm::Skim if config.ignore_case => {
+                MatcherKind::Skim(SkimMatcherV2::default().ignore_case())
+            }
+            // 默认的智能大小写：查询含大写字母时才区分大小写
+            MatchAlgorithm::Skim => MatcherKind::Skim(SkimMatcherV2::default().smart_case()),
+            MatchAlgorithm::Substring => MatcherKind::Substring,
+        };
+        Self {
+            kind,
+            fold_accents: config.ignore_accents,
+        }
+    }
+
+    /// The regex is written by the user, so candidates are matched as they are.
+    fn regex(regex: Regex) -> Self {
+        Self {
+            kind: MatcherKind::Regex(regex),
+            fold_accents: false,
+        }
+    }
+
+    fn fuzzy_match(&self, choice: &str, pattern: &str) -> Option<i64> {
+        if self.fold_accents {
+            return self.match_kind(&fold_accents(choice), &fold_accents(pattern));
+        }
+        self.match_kind(choice, pattern)
+    }
+
+    fn match_kind(&self, choice: &str, pattern: &str) -> Option<i64> {
+        match &self.kind {
+            MatcherKind::Skim(matcher) => matcher.fuzzy_match(choice, pattern),
+            MatcherKind::Substring => substring_score(choice, pattern),
+            MatcherKind::Regex(regex) => regex_score(regex, choice),
+        }
+    }
+}
+
+/// The pattern of a `/pattern/` query, or `None` when `query` isn't wrapped in slashes.
+fn regex_pattern(query: &str) -> Option<&str> {
+    query
+        .strip_prefix('/')
+        .and_then(|rest| rest.strip_suffix('/'))
+        .filter(|pattern| !pattern.is_empty())
+}
+
+/// Compiles a regex query with smart case: case-insensitive unless the pattern contains an
+/// uppercase letter.
+fn compile_regex_query(pattern: &str) -> Result<Regex, regex::Error> {
+    RegexBuilder::new(pattern)
+        .case_insensitive(!pattern.chars().any(char::is_uppercase))
+        .build()
+}
+
+/// Score of a contiguous, case-insensitive match of `pattern` in `choice`: longer patterns
+/// score higher, every character before the match costs a point, and a match at the start
+/// or right after a separator gets a bonus. Roughly on the scale of skim scores so the
+/// per-source offsets keep working.
+fn substring_score(choice: &str, pattern: &str) -> Option<i64> {
+    let choice_lower = choice.to_lowercase();
+    let pattern_lower = pattern.to_lowercase();
+    if pattern_lower.is_empty() {
+        return None;
+    }
+    let byte_index = choice_lower.find(&pattern_lower)?;
+    Some(span_score(
+        &choice_lower,
+        byte_index,
+        byte_index + pattern_lower.len(),
+    ))
+}
+
+/// Scores the first match of `regex` in `choice` like [`substring_score`]. Empty matches
+/// (e.g. from `/a*/`) count as no match.
+fn regex_score(regex: &Regex, choice: &str) -> Option<i64> {
+    let found = regex.find(choice).filter(|found| !found.is_empty())?;
+    Some(span_score(choice, found.start(), found.end()))
+}
+
+/// Score of the match at byte range `start..end` of `choice`; see [`substring_score`].
+fn span_score(choice: &str, start: usize, end: usize) -> i64 {
+    const CHAR_SCORE: i64 = 16;
+    const BOUNDARY_BONUS: i64 = 24;
+
+    let position = choice[..start].chars().count() as i64;
+    let at_boundary = choice[..start]
+        .chars()
+        .next_back()
+        .is_none_or(|ch| !ch.is_alphanumeric());
+
+    let mut score = choice[start..end].chars().count() as i64 * CHAR_SCORE - position;
+    if at_boundary {
+        score += BOUNDARY_BONUS;
+    }
+    score.max(1)
+}
+
+/// Ranks every source in `sources` against `query` and returns the results together with
+/// the actions they resolve to.
+///
+/// This is pure: nothing outside the returned values is touched.
+pub fn rank_results(
+    query: &str,
+    query_mode: QueryMode,
+    config: &AppConfig,
+    sources: SearchSources<'_>,
+) -> (Vec<SearchResult>, HashMap<String, PendingAction>) {
+    let trimmed = query.trim();
+    if trimmed.is_empty() {
+        return (Vec::new(), HashMap::new());
+    }
+
+    let locale = config.locale;
+    let (min_limit, max_limit) = config.advanced.result_limit_bounds();
+    let result_limit = config.max_results.clamp(min_limit, max_limit) as usize;
+
+    // !bang 查询只给出对应搜索引擎的结果，未知的 bang 退回默认网络搜索
+    if query_mode.allows_web_search() {
+        if let Some((bang, terms)) = parse_bang(trimmed) {
+            return bang_result(&bang, terms, trimmed, config);
+        }
+    }
+
+    // 正则表达式无效时只返回一条提示，不再进行任何匹配
+    let regex_query = match config
+        .regex_queries
+        .then(|| regex_pattern(trimmed))
+        .flatten()
+    {
+        Some(pattern) => match compile_regex_query(pattern) {
+            Ok(regex) => Some(regex),
+            Err(err) => return (vec![invalid_regex_result(locale, &err)], HashMap::new()),
+        },
+        None => None,
+    };
+
+    let mut results = Vec::new();
+    let mut counter = 0usize;
+    let mut pending_actions: HashMap<String, PendingAction> = HashMap::new();
+
+    if regex_query.is_none() && is_url_like(trimmed) {
+        let result_id = format!("url-{counter}");
+        pending_actions.insert(result_id.clone(), PendingAction::Url(trimmed.to_string()));
+        results.push(SearchResult {
+            id: result_id,
+            title: tr_with(locale, "result.open_url", trimmed),
+            subtitle: trimmed.to_string(),
+            icon: String::new(),
+            score: 200,
+            action_id: "url".to_string(),
+            secondary_actions: Vec::new(),
+            running: false,
+            quick_key: None,
+            drive_unavailable: false,
+        });
+        counter += 1;
+    }
+
+    if let Some(answer) = sources
+        .now
+        .filter(|_| query_mode == QueryMode::All)
+        .and_then(|now| instant_answer(trimmed, locale, now))
+    {
+        let result_id = format!("instant-{counter}");
+        pending_actions.insert(
+            result_id.clone(),
+            PendingAction::CopyText(answer.value.clone()),
+        );
+        results.push(SearchResult {
+            id: result_id,
+            title: answer.value,
+            subtitle: tr(locale, "result.instant_subtitle").to_string(),
+            icon: String::new(),
+            score: 250,
+            action_id: "instant".to_string(),
+            secondary_actions: Vec::new(),
+            running: false,
+            quick_key: None,
+            drive_unavailable: false,
+        });
+        counter += 1;
+    }
+
+    if query_mode == QueryMode::All {
+        let mut used_ids = HashSet::new();
+        for command in &config.custom_commands {
+            let Some(input) = command.match_input(trimmed) else {
+                continue;
+            };
+            // 模板需要参数但尚未输入时只给出提示，不注册可执行的动作
+            let awaiting_input = input.is_empty() && command.needs_input();
+            // 关键词重复时追加序号，保证 id 唯一且与配置顺序一致
+            let base_id = ids::custom_id(&command.keyword);
+            let mut result_id = base_id.clone();
+            let mut suffix = 2;
+            while !used_ids.insert(result_id.clone()) {
+                result_id = format!("{base_id}#{suffix}");
+                suffix += 1;
+            }
+            let (subtitle, action) = match command.expand(input) {
+                ExpandedCommand::Url(url) => (url.clone(), PendingAction::Url(url)),
+                ExpandedCommand::Command { target, arguments } => (
+                    format!("{target} {arguments}").trim().to_string(),
+                    PendingAction::Command { target, arguments },
+                ),
+            };
+            if !awaiting_input {
+                pending_actions.insert(result_id.clone(), action);
+            }
+            results.push(SearchResult {
+                id: result_id,
+                title: if input.is_empty() {
+                    command.title.clone()
+                } else {
+                    format!("{}: {input}", command.title)
+                },
+                subtitle: if awaiting_input {
+                    tr(locale, "result.custom_command_input_hint").to_string()
+                } else {
+                    subtitle
+                },
+                icon: String::new(),
+                score: 300,
+                action_id: "custom".to_string(),
+                secondary_actions: Vec::new(),
+                running: false,
+                quick_key: None,
+                drive_unavailable: false,
+            });
+            counter += 1;
+        }
+    }
+
+    // `notepad C:\foo.txt`：首个词与应用名称或关键词完全一致时，其余部分作为启动参数
+    let launch_arguments = regex_query
+        .is_none()
+        .then(|| split_launch_arguments(trimmed))
+        .flatten();
+    let matcher = match regex_query {
+        Some(regex) => Matcher::regex(regex),
+        None => Matcher::new(config),
+    };
+    if let Some(apps) = sources.apps {
+        let mut app_results = Vec::new();
+        for app in apps.iter() {
+            if let Some((head, arguments)) = &launch_arguments {
+                if accepts_launch_arguments(app, head) {
+                    counter += 1;
+                    let result_id = format!("args-{}", app.id);
+                    let mut launched = app.clone();
+                    launched.launch_args = Some(combine_launch_arguments(
+                        app.launch_args.as_deref(),
+                        arguments,
+                        config.typed_launch_args,
+                    ));
+                    pending_actions.insert(result_id.clone(), PendingAction::Application(launched));
+                    app_results.push(SearchResult {
+                        id: result_id,
+                        title: app.name.clone(),
+                        subtitle: tr_with(locale, "result.launch_with_args", arguments),
+                        icon: app.icon_b64.clone(),
+                        score: LAUNCH_WITH_ARGUMENTS_SCORE,
+                        action_id: "app".to_string(),
+                        secondary_actions: Vec::new(),
+                        running: false,
+                        quick_key: None,
+                        drive_unavailable: false,
+                    });
+                }
+            }
+            let drive_unavailable = app.app_type == AppType::Win32
+                && sources.available_drives.is_some_and(|drives| {
+                    drives::on_unavailable_drive(app_executable(app), drives)
+                });
+            if drive_unavailable && config.exclude_unavailable_drives {
+                continue;
+            }
+            if let Some(score) = match_application(&matcher, app, trimmed, config) {
+                let score = score + usage_bonus(sources.launch_counts, &app.id, config.usage_boost);
+                counter += 1;
+                let result_id = format!("app-{}", app.id);
+                pending_actions.insert(result_id.clone(), PendingAction::Application(app.clone()));
+                let running = app.app_type == AppType::Win32
+                    && sources.running_exes.is_some_and(|exes| {
+                        exes.contains(&normalize_exe_path(app_executable(app)))
+                    });
+                let mut secondary_actions = Vec::new();
+                if app.app_type == AppType::Win32 {
+                    let pin_id = format!("pin-{}", app.id);
+                    pending_actions.insert(
+                        pin_id.clone(),
+                        PendingAction::PinToTaskbar(app.path.clone()),
+                    );
+                    secondary_actions.push(ResultAction {
+                        id: pin_id,
+                        title: tr(locale, "result.pin_to_taskbar").to_string(),
+                        requires_confirmation: false,
+                    });
+
+                    let restart_id = format!("restart-{}", app.id);
+                    pending_actions
+                        .insert(restart_id.clone(), PendingAction::RestartApp(app.clone()));
+                    secondary_actions.push(ResultAction {
+                        id: restart_id,
+                        title: tr(locale, "result.restart_app").to_string(),
+                        requires_confirmation: true,
+                    });
+                }
+                // 没有图标的结果不提供复制图标
+                if !app.icon_b64.is_empty() {
+                    let copy_icon_id = format!("copy-icon-{}", app.id);
+                    pending_actions.insert(
+                        copy_icon_id.clone(),
+                        PendingAction::CopyImage(app.icon_b64.clone()),
+                    );
+                    secondary_actions.push(ResultAction {
+                        id: copy_icon_id,
+                        title: tr(locale, "result.copy_icon").to_string(),
+                        requires_confirmation: false,
+                    });
+                }
+                let subtitle = app
+                    .description
+                    .clone()
+                    .filter(|d| !d.is_empty())
+                    .or_else(|| app.source_path.clone())
+                    .unwrap_or_else(|| app.path.clone());
+                app_results.push(SearchResult {
+                    id: result_id,
+                    title: app.name.clone(),
+                    subtitle,
+                    icon: app.icon_b64.clone(),
+                    score,
+                    action_id: match app.app_type {
+                        AppType::Win32 | AppType::Desktop | AppType::MacApp => "app".to_string(),
+                        AppType::Uwp => "uwp".to_string(),
+                    },
+                    secondary_actions,
+                    running,
+                    quick_key: None,
+                    drive_unavailable,
+                });
+            }
+        }
+        results.extend(cap_provider_results(
+            app_results,
+            config.max_app_results,
+            sources.hidden_ids,
+        ));
+    }
+
+    if let Some(bookmarks) = sources.bookmarks {
+        let mut ordered: Vec<&BookmarkEntry> = bookmarks.iter().collect();
+        // 后续排序是稳定的，先按添加时间倒序排列，同分时较新的收藏即排在前面
+        if config.bookmark_recency_tiebreak {
+            ordered.sort_by(|a, b| b.date_added.cmp(&a.date_added));
+        }
+        let mut bookmark_results = Vec::new();
+        for bookmark in ordered {
+            if let Some(score) = match_bookmark(&matcher, bookmark, trimmed, config) {
+                let score =
+                    score + usage_bonus(sources.launch_counts, &bookmark.id, config.usage_boost);
+                counter += 1;
+                let source = tr(locale, "result.bookmark_source");
+                let browser = &bookmark.browser;
+                let profile = &bookmark.profile;
+                let subtitle = match &bookmark.folder_path {
+                    Some(path) => {
+                        format!(
+                            "{source} · {browser} · {profile} · {path} · {}",
+                            bookmark.url
+                        )
+                    }
+                    None => format!("{source} · {browser} · {profile} · {}", bookmark.url),
+                };
+                let result_id = format!("bookmark-{}", bookmark.id);
+                pending_actions
+                    .insert(result_id.clone(), PendingAction::Bookmark(bookmark.clone()));
+                bookmark_results.push(SearchResult {
+                    id: result_id,
+                    title: bookmark.title.clone(),
+                    subtitle,
+                    icon: bookmark.icon_b64.clone(),
+                    score,
+                    action_id: "bookmark".to_string(),
+                    secondary_actions: Vec::new(),
+                    running: false,
+                    quick_key: None,
+                    drive_unavailable: false,
+                });
+            }
+        }
+        for folder in sources.bookmark_folders.unwrap_or_default() {
+            let Some(score) = match_bookmark_folder(&matcher, folder, trimmed, config) else {
+                continue;
+            };
+            let result_id = format!("bookmark-folder-{}", folder.id);
+            pending_actions.insert(
+                result_id.clone(),
+                PendingAction::OpenBookmarkFolder(folder.urls.clone()),
+            );
+            let count = folder.urls.len().to_string();
+            bookmark_results.push(SearchResult {
+                id: result_id,
+                title: tr_with(locale, "result.open_bookmark_folder", &folder.name),
+                subtitle: format!(
+                    "{} · {} · {} · {} · {}",
+                    tr(locale, "result.bookmark_source"),
+                    folder.browser,
+                    folder.profile,
+                    folder.path,
+                    tr_with(locale, "result.bookmark_folder_count", &count)
+                ),
+                icon: String::new(),
+                score,
+                action_id: "bookmark_folder".to_string(),
+                secondary_actions: Vec::new(),
+                running: false,
+                quick_key: None,
+                drive_unavailable: false,
+            });
+        }
+        results.extend(cap_provider_results(
+            bookmark_results,
+            config.max_bookmark_results,
+            sources.hidden_ids,
+        ));
+    }
+
+    if let Some(history) = sources.history {
+        let mut history_results = Vec::new();
+        for entry in history.iter() {
+            if let Some(score) = match_history(&matcher, entry, trimmed, config) {
+                counter += 1;
+                let result_id = format!("history-{counter}");
+                pending_actions.insert(result_id.clone(), PendingAction::Url(entry.url.clone()));
+                history_results.push(SearchResult {
+                    id: result_id,
+                    title: entry.title.clone(),
+                    subtitle: format!("{} · {}", tr(locale, "result.history_source"), entry.url),
+                    icon: String::new(),
+                    score,
+                    action_id: "history".to_string(),
+                    secondary_actions: Vec::new(),
+                    running: false,
+                    quick_key: None,
+                    drive_unavailable: false,
+                });
+            }
+        }
+        results.extend(cap_provider_results(
+            history_results,
+            config.max_history_results,
+            sources.hidden_ids,
+        ));
+    }
+
+    if let Some(hidden_ids) = sources.hidden_ids {
+        results.retain(|result| !hidden_ids.contains(&result.id));
+    }
+    results.sort_by(|a, b| b.score.cmp(&a.score));
+    apply_relative_score_cutoff(&mut results, config.relative_score_cutoff);
+    if result_limit > 1 && results.len() >= result_limit {
+        results.truncate(result_limit - 1);
+    } else {
+        results.truncate(result_limit);
+    }
+
+    // Web 搜索结果总会追加，需在此之前判断是否没有任何匹配
+    let nothing_matched = results.is_empty();
+
+    // 仅在允许的模式下追加 Web 搜索结果；没有匹配时按配置在任何模式下都追加
+    let fallback = nothing_matched && config.fallback_on_empty_results;
+    if query_mode.allows_web_search() || fallback {
+        push_web_search_result(&mut results, &mut pending_actions, counter, trimmed, config);
+        counter += 1;
+    }
+
+    // 没有任何匹配时，按配置追加兜底项，保证用户总能对输入执行操作
+    if fallback {
+        if is_path_like(trimmed) {
+            let result_id = format!("open-{counter}");
+            pending_actions.insert(
+                result_id.clone(),
+                PendingAction::RawOpen(trimmed.to_string()),
+            );
+            // 看起来是文件（带扩展名）时，额外提供“打开方式”作为次要操作
+            let secondary_actions = if Path::new(trimmed).extension().is_some() {
+                vec![open_with_action(
+                    &result_id,
+                    trimmed,
+                    locale,
+                    &mut pending_actions,
+                )]
+            } else {
+                Vec::new()
+            };
+            results.push(SearchResult {
+                id: result_id,
+                title: tr_with(locale, "result.raw_open", trimmed),
+                subtitle: tr(locale, "result.raw_open_subtitle").to_string(),
+                icon: String::new(),
+                score: i64::MIN,
+                action_id: "open".to_string(),
+                secondary_actions,
+                running: false,
+                quick_key: None,
+                drive_unavailable: false,
+            });
+        }
+    }
+
+    // max_visible_results 只限制最终返回的条数，不影响上面的匹配数量上限
+    if config.max_visible_results > 0 {
+        results.truncate(config.max_visible_results as usize);
+    }
+    assign_quick_keys(&mut results);
+    (results, pending_actions)
+}
+
+/// A hint result for a `/pattern/` query that doesn't compile. It has no action, so Enter
+/// does nothing.
+fn invalid_regex_result(locale: Locale, err: &regex::Error) -> SearchResult {
+    // 语法错误的描述有多行，最后一行是具体原因
+    let message = err.to_string();
+    let reason = message
+        .lines()
+        .rev()
+        .find(|line| !line.trim().is_empty())
+        .unwrap_or_default()
+        .trim()
+        .to_string();
+    SearchResult {
+        id: "regex-invalid".to_string(),
+        title: tr(locale, "result.invalid_regex").to_string(),
+        subtitle: reason,
+        icon: String::new(),
+        score: 0,
+        action_id: "hint".to_string(),
+        secondary_actions: Vec::new(),
+        running: false,
+        quick_key: None,
+        drive_unavailable: false,
+    }
+}
+
+/// Turns ripgrep matches into results that open the matching file, keeping ripgrep's order.
+pub fn content_results(
+    matches: &[ContentMatch],
+    locale: Locale,
+) -> (Vec<SearchResult>, HashMap<String, PendingAction>) {
+    let mut results = Vec::new();
+    let mut pending_actions = HashMap::new();
+    let source = tr(locale, "result.content_source");
+
+    for (index, found) in matches.iter().enumerate() {
+        let result_id = format!("content-{index}");
+        pending_actions.insert(
+            result_id.clone(),
+            PendingAction::RawOpen(found.path.clone()),
+        );
+        let open_with = open_with_action(&result_id, &found.path, locale, &mut pending_actions);
+        let title = Path::new(&found.path)
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| found.path.clone());
+        results.push(SearchResult {
+            id: result_id,
+            title,
+            subtitle: format!(
+                "{source} · {}:{} · {}",
+                found.path, found.line_number, found.line
+            ),
+            icon: String::new(),
+            score: (matches.len() - index) as i64,
+            action_id: "file".to_string(),
+            secondary_actions: vec![open_with],
+            running: false,
+            quick_key: None,
+            drive_unavailable: false,
+        });
+    }
+
+    assign_quick_keys(&mut results);
+    (results, pending_actions)
+}
+
+/// Turns file search matches into results titled by name with the parent folder as subtitle,
+/// keeping the order of `matches`. Files also offer the "open with" dialog.
+pub fn file_results(
+    matches: &[FileMatch],
+    locale: Locale,
+) -> (Vec<SearchResult>, HashMap<String, PendingAction>) {
+    let mut results = Vec::new();
+    let mut pending_actions = HashMap::new();
+
+    for (index, found) in matches.iter().enumerate() {
+        let result_id = format!("file-{index}");
+        let path = found.path.to_string_lossy().into_owned();
+        pending_actions.insert(result_id.clone(), PendingAction::OpenPath(path.clone()));
+        let secondary_actions = if found.is_dir {
+            Vec::new()
+        } else {
+            vec![open_with_action(
+                &result_id,
+                &path,
+                locale,
+                &mut pending_actions,
+            )]
+        };
+        let title = found
+            .path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| path.clone());
+        let subtitle = found
+            .path
+            .parent()
+            .map(|parent| parent.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        results.push(SearchResult {
+            id: result_id,
+            title,
+            subtitle,
+            icon: String::new(),
+            score: (matches.len() - index) as i64,
+            action_id: if found.is_dir { "folder" } else { "file" }.to_string(),
+            secondary_actions,
+            running: false,
+            quick_key: None,
+            drive_unavailable: false,
+        });
+    }
+
+    assign_quick_keys(&mut results);
+    (results, pending_actions)
+}
+
+/// Registers the shell's "open with" dialog for the file at `path` as a secondary action of
+/// `result_id`.
+fn open_with_action(
+    result_id: &str,
+    path: &str,
+    locale: Locale,
+    pending_actions: &mut HashMap<String, PendingAction>,
+) -> ResultAction {
+    let id = format!("openwith-{result_id}");
+    pending_actions.insert(id.clone(), PendingAction::OpenWith(path.to_string()));
+    ResultAction {
+        id,
+        title: tr(locale, "result.open_with").to_string(),
+        requires_confirmation: false,
+    }
+}
+
+/// Turns clipboard history entries containing every whitespace-separated term of `query`
+/// (case-insensitively) into results that copy the entry back, newest first. An empty query
+/// lists the whole history.
+pub fn clipboard_results(
+    entries: &[ClipboardEntry],
+    query: &str,
+    max_results: usize,
+    locale: Locale,
+) -> (Vec<SearchResult>, HashMap<String, PendingAction>) {
+    let terms: Vec<String> = query.split_whitespace().map(str::to_lowercase).collect();
+    let mut results = Vec::new();
+    let mut pending_actions = HashMap::new();
+
+    let matching = entries.iter().filter(|entry| {
+        let text = entry.text.to_lowercase();
+        terms.iter().all(|term| text.contains(term.as_str()))
+    });
+    for (index, entry) in matching.take(max_results).enumerate() {
+        let result_id = format!("clip-{index}");
+        pending_actions.insert(
+            result_id.clone(),
+            PendingAction::CopyText(entry.text.clone()),
+        );
+        let copied_at = entry.copied_at.format("%m-%d %H:%M").to_string();
+        results.push(SearchResult {
+            id: result_id,
+            title: clipboard_preview(&entry.text),
+            subtitle: tr_with(locale, "result.clipboard_subtitle", &copied_at),
+            icon: String::new(),
+            score: (max_results - index) as i64,
+            action_id: "clipboard".to_string(),
+            secondary_actions: Vec::new(),
+            running: false,
+            quick_key: None,
+            drive_unavailable: false,
+        });
+    }
+
+    assign_quick_keys(&mut results);
+    (results, pending_actions)
+}
+
+/// Offers to run `command` as typed, followed by the entries of `history` containing every
+/// whitespace-separated term of it (case-insensitively). An empty command lists the history.
+pub fn run_results(
+    command: &str,
+    history: &[String],
+    max_results: usize,
+    locale: Locale,
+) -> (Vec<SearchResult>, HashMap<String, PendingAction>) {
+    let command = command.trim();
+    let terms: Vec<String> = command.split_whitespace().map(str::to_lowercase).collect();
+    let mut results = Vec::new();
+    let mut pending_actions = HashMap::new();
+
+    if !command.is_empty() {
+        let result_id = "run".to_string();
+        pending_actions.insert(
+            result_id.clone(),
+            PendingAction::RunCommand(command.to_string()),
+        );
+        results.push(run_result(
+            result_id,
+            tr_with(locale, "result.run_command", command),
+            tr(locale, "result.run_subtitle").to_string(),
+            (max_results + 1) as i64,
+        ));
+    }
+
+    // 与当前输入完全相同的记录已由第一项覆盖
+    let matching = history.iter().filter(|entry| {
+        let lower = entry.to_lowercase();
+        !entry.eq_ignore_ascii_case(command)
+            && terms.iter().all(|term| lower.contains(term.as_str()))
+    });
+    let remaining = max_results.saturating_sub(results.len());
+    for (index, entry) in matching.take(remaining).enumerate() {
+        let result_id = format!("run-{index}");
+        pending_actions.insert(result_id.clone(), PendingAction::RunCommand(entry.clone()));
+        results.push(run_result(
+            result_id,
+            entry.clone(),
+            tr(locale, "result.run_history_subtitle").to_string(),
+            (max_results - index) as i64,
+        ));
+    }
+
+    assign_quick_keys(&mut results);
+    (results, pending_actions)
+}
+
+fn run_result(id: String, title: String, subtitle: String, score: i64) -> SearchResult {
+    SearchResult {
+        id,
+        title,
+        subtitle,
+        icon: String::new(),
+        score,
+        action_id: "run".to_string(),
+        secondary_actions: Vec::new(),
+        running: false,
+        quick_key: None,
+        drive_unavailable: false,
+    }
+}
+
+/// First non-blank line of `text` with runs of whitespace collapsed, cut to fit one row.
+fn clipboard_preview(text: &str) -> String {
+    const MAX_PREVIEW_CHARS: usize = 80;
+    let line = text
+        .lines()
+        .find(|line| !line.trim().is_empty())
+        .unwrap_or_default();
+    let collapsed = line.split_whitespace().collect::<Vec<_>>().join(" ");
+    if collapsed.chars().count() <= MAX_PREVIEW_CHARS {
+        return collapsed;
+    }
+    let mut preview: String = collapsed.chars().take(MAX_PREVIEW_CHARS - 1).collect();
+    preview.push('…');
+    preview
+}
+
+/// Category of a result, derived from its `action_id`.
+pub fn result_group_kind(result: &SearchResult) -> ResultGroupKind {
+    match result.action_id.as_str() {
+        "app" | "uwp" => ResultGroupKind::Applications,
+        "bookmark" | "bookmark_folder" | "history" => ResultGroupKind::Bookmarks,
+        "search" => ResultGroupKind::Web,
+        "url" => ResultGroupKind::Url,
+        "instant" => ResultGroupKind::Calculator,
+        _ => ResultGroupKind::Other,
+    }
+}
+
+/// Buckets ranked `results` by [`result_group_kind`]. Groups appear in the order of their
+/// best result and keep the ranking inside each group.
+pub fn group_results(results: Vec<SearchResult>) -> Vec<ResultGroup> {
+    let mut groups: Vec<ResultGroup> = Vec::new();
+    for result in results {
+        let kind = result_group_kind(&result);
+        match groups.iter_mut().find(|group| group.kind == kind) {
+            Some(group) => group.results.push(result),
+            None => groups.push(ResultGroup {
+                kind,
+                results: vec![result],
+            }),
+        }
+    }
+    groups
+}
+
+/// Reorders ranked `results` group by group as [`group_results`] lays them out, and renumbers
+/// the quick keys to match that display order.
+pub fn order_by_group(results: Vec<SearchResult>) -> Vec<SearchResult> {
+    let mut ordered: Vec<SearchResult> = group_results(results)
+        .into_iter()
+        .flat_map(|group| group.results)
+        .collect();
+    for result in ordered.iter_mut() {
+        result.quick_key = None;
+    }
+    assign_quick_keys(&mut ordered);
+    ordered
+}
+
+/// Numbers the first nine results 1-9 in their final display order.
+fn assign_quick_keys(results: &mut [SearchResult]) {
+    for (key, result) in (1..=9u8).zip(results.iter_mut()) {
+        result.quick_key = Some(key);
+    }
+}
+
+/// Keeps the best `cap` results of a single provider so one noisy source can't crowd out the
+/// others before the global limit applies. A cap of 0 means unlimited. Results hidden for the
+/// session are dropped first so they don't use up the cap.
+fn cap_provider_results(
+    mut provider_results: Vec<SearchResult>,
+    cap: u32,
+    hidden_ids: Option<&HashSet<String>>,
+) -> Vec<SearchResult> {
+    if let Some(hidden_ids) = hidden_ids {
+        provider_results.retain(|result| !hidden_ids.contains(&result.id));
+    }
+    if cap > 0 {
+        provider_results.sort_by(|a, b| b.score.cmp(&a.score));
+        provider_results.truncate(cap as usize);
+    }
+    provider_results
+}
+
+/// Drops results whose score falls below `cutoff` times the top score; expects `results` sorted descending.
+/// A cutoff of 0 (or a non-positive top score) leaves the list untouched.
+fn apply_relative_score_cutoff(results: &mut Vec<SearchResult>, cutoff: f32) {
+    if cutoff <= 0.0 {
+        return;
+    }
+    let Some(top_score) = results.first().map(|result| result.score) else {
+        return;
+    };
+    if top_score <= 0 {
+        return;
+    }
+
+    let threshold = (top_score as f64 * f64::from(cutoff.min(1.0))).ceil() as i64;
+    results.retain(|result| result.score >= threshold);
+}
+
+fn push_web_search_result(
+    results: &mut Vec<SearchResult>,
+    pending_actions: &mut HashMap<String, PendingAction>,
+    counter: usize,
+    query: &str,
+    config: &AppConfig,
+) {
+    let search_id = format!("search-{counter}");
+    pending_actions.insert(
+        search_id.clone(),
+        PendingAction::Search(web_search_url(query, config)),
+    );
+    let engine = config.search_engine_name.trim();
+    results.push(SearchResult {
+        id: search_id,
+        title: tr_with(config.locale, "result.web_search", query).replacen("{engine}", engine, 1),
+        subtitle: tr(config.locale, "result.web_search_subtitle").replacen("{engine}", engine, 1),
+        icon: String::new(),
+        score: i64::MIN,
+        action_id: "search".to_string(),
+        secondary_actions: Vec::new(),
+        running: false,
+        quick_key: None,
+        drive_unavailable: false,
+    });
+}
+
+/// Fills the URL-encoded `query` into `search_engine_template`.
+fn web_search_url(query: &str, config: &AppConfig) -> String {
+    fill_search_template(&config.search_engine_template, query)
+}
+
+fn fill_search_template(template: &str, query: &str) -> String {
+    template
+        .trim()
+        .replace(SEARCH_QUERY_PLACEHOLDER, &urlencoding::encode(query))
+}
+
+/// Splits a `!bang terms` query into the lowercased bang and the search terms. `None` when
+/// the query doesn't start with a bang or has no terms after it.
+fn parse_bang(query: &str) -> Option<(String, &str)> {
+    let rest = query.strip_prefix('!')?;
+    let (bang, terms) = rest.split_once(char::is_whitespace)?;
+    let terms = terms.trim();
+    (!bang.is_empty() && !terms.is_empty()).then(|| (bang.to_lowercase(), terms))
+}
+
+/// The single result of a bang query: the mapped engine for a known bang, the default web
+/// search of the whole query otherwise.
+fn bang_result(
+    bang: &str,
+    terms: &str,
+    query: &str,
+    config: &AppConfig,
+) -> (Vec<SearchResult>, HashMap<String, PendingAction>) {
+    let mut results = Vec::new();
+    let mut pending_actions = HashMap::new();
+    let Some(template) = config.search_bangs.get(bang) else {
+        push_web_search_result(&mut results, &mut pending_actions, 0, query, config);
+        return (results, pending_actions);
+    };
+
+    let url = fill_search_template(template, terms);
+    let result_id = "bang-0".to_string();
+    pending_actions.insert(result_id.clone(), PendingAction::Search(url.clone()));
+    results.push(SearchResult {
+        id: result_id,
+        title: tr_with(config.locale, "result.bang_search", terms).replacen("{bang}", bang, 1),
+        subtitle: url,
+        icon: String::new(),
+        score: 0,
+        action_id: "search".to_string(),
+        secondary_actions: Vec::new(),
+        running: false,
+        quick_key: None,
+        drive_unavailable: false,
+    });
+    assign_quick_keys(&mut results);
+    (results, pending_actions)
+}
+
+/// Builds the action run when Enter is pressed on a query without results, following
+/// `empty_enter_action`: URL-like input is opened (with `https://` added when no scheme is
+/// given), anything else is searched on the web.
+pub fn fallback_action(query: &str, config: &AppConfig) -> Option<PendingAction> {
+    let trimmed = query.trim();
+    if trimmed.is_empty() {
+        return None;
+    }
+
+    match config.empty_enter_action {
+        EmptyEnterAction::Nothing => None,
+        EmptyEnterAction::WebSearch => Some(PendingAction::Search(web_search_url(trimmed, config))),
+        EmptyEnterAction::UrlOrSearch if is_url_like(trimmed) => {
+            let url = if trimmed.contains("://") {
+                trimmed.to_string()
+            } else {
+                format!("https://{trimmed}")
+            };
+            Some(PendingAction::Url(url))
+        }
+        EmptyEnterAction::UrlOrSearch => {
+            Some(PendingAction::Search(web_search_url(trimmed, config)))
+        }
+    }
+}
+
+/// Heuristic check for inputs that look like a local or UNC path (e.g. `C:\tools`, `\\server\share`, `%TEMP%`).
+pub fn is_path_like(input: &str) -> bool {
+    let mut chars = input.chars();
+    let has_drive_prefix = matches!(
+        (chars.next(), chars.next(), chars.next()),
+        (Some(letter), Some(':'), Some('\\' | '/')) if letter.is_ascii_alphabetic()
+    );
+
+    has_drive_prefix
+        || input.starts_with("\\\\")
+        || input.starts_with('~')
+        || (input.starts_with('%') && input[1..].contains('%'))
+}
+
+/// Splits `app arguments` into the first word and the remaining arguments, re-quoted as one
+/// command line. `None` without arguments or when a quote is left open.
+fn split_launch_arguments(query: &str) -> Option<(String, String)> {
+    let (head, rest) = query.split_once(char::is_whitespace)?;
+    let arguments = split_windows_arguments(rest.trim()).ok()?;
+    if arguments.is_empty() {
+        return None;
+    }
+    Some((head.to_string(), join_windows_arguments(&arguments)))
+}
+
+/// Whether `head` names `app` exactly, by its name or a keyword such as the executable's file
+/// stem. Only Win32 apps take arguments; Steam games launch through a URI.
+fn accepts_launch_arguments(app: &ApplicationInfo, head: &str) -> bool {
+    app.app_type == AppType::Win32
+        && !matches!(app.origin, Some(AppOrigin::Steam { .. }))
+        && std::iter::once(&app.name)
+            .chain(app.keywords.iter())
+            .any(|candidate| candidate.eq_ignore_ascii_case(head))
+}
+
+/// Whether `input` should be offered as a web address: either it starts with an explicit
+/// scheme (`https://…`), or it is a single token whose host part is `localhost`, an IPv4
+/// address or a domain ending in a known top-level domain.
+///
+/// Bare file names such as `readme.md` are rejected when nothing follows the host, since a few
+/// country-code domains collide with common file extensions.
+pub fn is_url_like(input: &str) -> bool {
+    if input.is_empty() || input.chars().any(char::is_whitespace) {
+        return false;
+    }
+    if has_url_scheme(input) {
+        return true;
+    }
+
+    let (authority, rest) = input
+        .find(['/', '?', '#'])
+        .map_or((input, ""), |index| input.split_at(index));
+    let host = match authority.rsplit_once(':') {
+        Some((host, port))
+            if !port.is_empty() && port.len() <= 5 && port.bytes().all(|b| b.is_ascii_digit()) =>
+        {
+            host
+        }
+        Some(_) => return false,
+        None => authority,
+    };
+
+    host.eq_ignore_ascii_case("localhost")
+        || is_ipv4_address(host)
+        || is_domain_name(host, !rest.is_empty())
+}
+
+/// Generic top-level domains accepted without a path; any two-letter country code is also
+/// accepted unless it doubles as a common file extension.
+const KNOWN_GENERIC_TLDS: &[&str] = &[
+    "com", "net", "org", "edu", "gov", "mil", "int", "info", "biz", "name", "pro", "io", "dev",
+    "app", "xyz", "top", "site", "online", "tech", "cloud", "blog", "shop", "store", "wiki",
+    "page", "club", "live", "news", "art", "design", "games", "link", "space", "website",
+];
+const FILE_EXTENSION_CCTLDS: &[&str] = &["md", "rs", "py", "js", "ts", "cs", "sh", "gz", "db"];
+
+fn has_url_scheme(input: &str) -> bool {
+    let Some((scheme, _)) = input.split_once("://") else {
+        return false;
+    };
+    let mut chars = scheme.chars();
+    chars
+        .next()
+        .is_some_and(|first| first.is_ascii_alphabetic())
+        && chars.all(|ch| ch.is_ascii_alphanumeric() || matches!(ch, '+' | '-' | '.'))
+}
+
+fn is_ipv4_address(host: &str) -> bool {
+    let octets: Vec<&str> = host.split('.').collect();
+    octets.len() == 4
+        && octets
+            .iter()
+            .all(|octet| !octet.is_empty() && octet.len() <= 3 && octet.parse::<u8>().is_ok())
+}
+
+fn is_domain_name(host: &str, has_path: bool) -> bool {
+    let labels: Vec<&str> = host.split('.').collect();
+    if labels.len() < 2 {
+        return false;
+    }
+    let valid_labels = labels.iter().all(|label| {
+        !label.is_empty()
+            && !label.starts_with('-')
+            && !label.ends_with('-')
+            && label.chars().all(|ch| ch.is_alphanumeric() || ch == '-')
+    });
+    if !valid_labels {
+        return false;
+    }
+
+    let tld = labels[labels.len() - 1].to_ascii_lowercase();
+    if !tld.chars().all(|ch| ch.is_ascii_alphabetic()) {
+        return false;
+    }
+    if KNOWN_GENERIC_TLDS.contains(&tld.as_str()) {
+        return true;
+    }
+    // 两个字母的国家顶级域名与部分文件扩展名重名，此时只有带路径才视为网址
+    tld.len() == 2 && (has_path || !FILE_EXTENSION_CCTLDS.contains(&tld.as_str()))
+}
+
+fn match_application(
+    matcher: &Matcher,
+    app: &ApplicationInfo,
+    query: &str,
+    config: &AppConfig,
+) -> Option<i64> {
+    let mut best = matcher.fuzzy_match(&app.name, query);
+
+    for keyword in &app.keywords {
+        if keyword.is_empty() {
+            continue;
+        }
+
+        if let Some(score) = matcher.fuzzy_match(keyword, query) {
+            let score = score - config.keyword_penalty; // prefer primary name by adding small penalty to keyword matches
+            if best.is_none_or(|current| score > current) {
+                best = Some(score);
+            }
+        }
+    }
+
+    let candidates =
+        std::iter::once(app.name.as_str()).chain(app.keywords.iter().map(String::as_str));
+    above_threshold(best, config)
+        .map(|score| score + prefix_bonus(candidates, query, config.prefix_boost))
+}
+
+/// Matches a folder by its name, or by its full path with `path_penalty`. Folder results rank
+/// a bit below bookmarks with the same score, since opening one bookmark is the common case.
+fn match_bookmark_folder(
+    matcher: &Matcher,
+    folder: &BookmarkFolder,
+    query: &str,
+    config: &AppConfig,
+) -> Option<i64> {
+    let by_name = matcher.fuzzy_match(&folder.name, query);
+    let by_path = matcher
+        .fuzzy_match(&folder.path, query)
+        .map(|score| score - config.path_penalty);
+    let best = by_name.into_iter().chain(by_path).max();
+    above_threshold(best, config).map(|score| score - 3)
+}
+
+fn match_bookmark(
+    matcher: &Matcher,
+    bookmark: &BookmarkEntry,
+    query: &str,
+    config: &AppConfig,
+) -> Option<i64> {
+    let mut best = matcher.fuzzy_match(&bookmark.title, query);
+
+    if let Some(path) = &bookmark.folder_path {
+        if let Some(score) = matcher.fuzzy_match(path, query) {
+            let score = score - config.path_penalty;
+            if best.is_none_or(|current| score > current) {
+                best = Some(score);
+            }
+        }
+    }
+
+    if let Some(score) = matcher
+        .fuzzy_match(&bookmark.url, query)
+        .map(|value| value - config.url_penalty)
+    {
+        if best.is_none_or(|current| score > current) {
+            best = Some(score);
+        }
+    }
+
+    for keyword in &bookmark.keywords {
+        if keyword.is_empty() {
+            continue;
+        }
+
+        if let Some(score) = matcher.fuzzy_match(keyword, query) {
+            let score = score - config.url_penalty;
+            if best.is_none_or(|current| score > current) {
+                best = Some(score);
+            }
+        }
+    }
+
+    let candidates = std::iter::once(bookmark.title.as_str())
+        .chain(bookmark.keywords.iter().map(String::as_str));
+    above_threshold(best, config)
+        .map(|score| score + prefix_bonus(candidates, query, config.prefix_boost))
+}
+
+/// Drops a match whose score, penalties included, is below `min_score_threshold` (0 keeps
+/// every match). Applied before any bonus so a boost can't rescue a barely matching name.
+fn above_threshold(score: Option<i64>, config: &AppConfig) -> Option<i64> {
+    let threshold = config.min_score_threshold;
+    score.filter(|score| threshold <= 0 || *score >= threshold)
+}
+
+/// `prefix_boost` when any candidate starts with the query (case-insensitive), else 0.
+/// Layered on top of the fuzzy score so a contiguous prefix match beats a scattered one.
+fn prefix_bonus<'a>(
+    mut candidates: impl Iterator<Item = &'a str>,
+    query: &str,
+    prefix_boost: i64,
+) -> i64 {
+    if prefix_boost <= 0 {
+        return 0;
+    }
+    let query = query.to_lowercase();
+    if candidates.any(|candidate| candidate.to_lowercase().starts_with(&query)) {
+        prefix_boost
+    } else {
+        0
+    }
+}
+
+/// Upper bound of [`usage_bonus`], below the default `prefix_boost` so a prefix match still wins.
+const MAX_USAGE_BONUS: i64 = 40;
+/// Ranks an app launched with typed arguments above fuzzy matches of the whole query.
+const LAUNCH_WITH_ARGUMENTS_SCORE: i64 = 260;
+
+/// Frequency bonus for a result launched `count` times: `usage_boost * ln(1 + count)`, capped at
+/// [`MAX_USAGE_BONUS`] so habits reorder close matches without burying a clearly better one.
+fn usage_bonus(counts: Option<&HashMap<String, u32>>, id: &str, usage_boost: f32) -> i64 {
+    let count = counts
+        .and_then(|counts| counts.get(id))
+        .copied()
+        .unwrap_or(0);
+    if count == 0 || usage_boost <= 0.0 {
+        return 0;
+    }
+    let bonus = (usage_boost as f64 * (count as f64).ln_1p()).round() as i64;
+    bonus.min(MAX_USAGE_BONUS)
+}
+
+fn match_history(
+    matcher: &Matcher,
+    entry: &HistoryEntry,
+    query: &str,
+    config: &AppConfig,
+) -> Option<i64> {
+    let mut best = matcher.fuzzy_match(&entry.title, query);
+
+    if let Some(score) = matcher
+        .fuzzy_match(&entry.url, query)
+        .map(|value| value - config.url_penalty)
+    {
+        if best.is_none_or(|current| score > current) {
+            best = Some(score);
+        }
+    }
+
+    for keyword in &entry.keywords {
+        if keyword.is_empty() {
+            continue;
+        }
+
+        if let Some(score) = matcher.fuzzy_match(keyword, query) {
+            let score = score - config.url_penalty;
+            if best.is_none_or(|current| score > current) {
+                best = Some(score);
+            }
+        }
+    }
+
+    // 历史记录整体略低于书签，常访问的页面按访问次数给予少量加分
+    let visit_bonus = (entry.visit_count.max(1) as f64).log2().round() as i64;
+    above_threshold(best, config).map(|score| score - 10 + visit_bonus.min(10))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::TypedLaunchArgs;
+
+    const ALL_RANKED_MODES: [QueryMode; 4] = [
+        QueryMode::All,
+        QueryMode::Application,
+        QueryMode::Bookmark,
+        QueryMode::Search,
+    ];
+
+    fn action_ids(results: &[SearchResult]) -> Vec<&str> {
+        results
+            .iter()
+            .map(|result| result.action_id.as_str())
+            .collect()
+    }
+
+    fn result_ids(results: &[SearchResult]) -> Vec<&str> {
+        results.iter().map(|result| result.id.as_str()).collect()
+    }
+
+    fn app(id: &str, name: &str) -> ApplicationInfo {
+        ApplicationInfo {
+            id: id.to_string(),
+            name: name.to_string(),
+            path: format!(r"C:\Apps\{id}.exe"),
+            source_path: None,
+            app_type: AppType::Win32,
+            icon_b64: String::new(),
+            description: None,
+            keywords: Vec::new(),
+            working_directory: None,
+            arguments: None,
+            origin: None,
+            icon_source: None,
+            launch_args: None,
+            indexed_name: None,
+        }
+    }
+
+    fn app_sources(apps: &[ApplicationInfo]) -> SearchSources<'_> {
+        SearchSources {
+            apps: Some(apps),
+            ..SearchSources::default()
+        }
+    }
+
+    fn scored(id: &str, score: i64) -> SearchResult {
+        SearchResult {
+            id: id.to_string(),
+            title: id.to_string(),
+            subtitle: String::new(),
+            icon: String::new(),
+            score,
+            action_id: "app".to_string(),
+            secondary_actions: Vec::new(),
+            running: false,
+            quick_key: None,
+            drive_unavailable: false,
+        }
+    }
+
+    #[test]
+    fn empty_match_offers_web_search_and_raw_open_in_every_mode() {
+        let config = AppConfig::default();
+        for mode in ALL_RANKED_MODES {
+            let (results, pending_actions) =
+                rank_results(r"C:\missing\tools", mode, &config, SearchSources::default());
+            assert_eq!(action_ids(&results), ["search", "open"], "{mode:?}");
+            assert!(results
+                .iter()
+                .all(|result| pending_actions.contains_key(&result.id)));
+        }
+    }
+
+    #[test]
+    fn empty_match_without_path_only_offers_web_search() {
+        let config = AppConfig::default();
+        for mode in ALL_RANKED_MODES {
+            let (results, _) = rank_results("qwxz", mode, &config, SearchSources::default());
+            assert_eq!(action_ids(&results), ["search"], "{mode:?}");
+        }
+    }
+
+    #[test]
+    fn disabled_fallback_leaves_gated_modes_empty() {
+        let config = AppConfig {
+            fallback_on_empty_results: false,
+            ..AppConfig::default()
+        };
+        let expected: [(QueryMode, &[&str]); 4] = [
+            (QueryMode::All, &["search"]),
+            (QueryMode::Application, &[]),
+            (QueryMode::Bookmark, &[]),
+            (QueryMode::Search, &["search"]),
+        ];
+        for (mode, action_ids_expected) in expected {
+            let (results, _) =
+                rank_results(r"C:\missing\tools", mode, &config, SearchSources::default());
+            assert_eq!(action_ids(&results), action_ids_expected, "{mode:?}");
+        }
+    }
+
+    #[test]
+    fn relative_cutoff_keeps_only_the_strong_match_of_a_steep_distribution() {
+        let mut results = vec![
+            scored("a", 200),
+            scored("b", 60),
+            scored("c", 50),
+            scored("d", 40),
+        ];
+        apply_relative_score_cutoff(&mut results, 0.5);
+        assert_eq!(result_ids(&results), ["a"]);
+    }
+
+    #[test]
+    fn relative_cutoff_keeps_a_flat_distribution() {
+        let mut results = vec![
+            scored("a", 100),
+            scored("b", 95),
+            scored("c", 90),
+            scored("d", 85),
+        ];
+        apply_relative_score_cutoff(&mut results, 0.5);
+        assert_eq!(result_ids(&results), ["a", "b", "c", "d"]);
+    }
+
+    #[test]
+    fn relative_cutoff_of_zero_keeps_everything() {
+        let mut results = vec![scored("a", 200), scored("b", 1)];
+        apply_relative_score_cutoff(&mut results, 0.0);
+        assert_eq!(result_ids(&results), ["a", "b"]);
+    }
+
+    #[test]
+    fn url_like_query_is_offered_as_url_before_web_search() {
+        let config = AppConfig::default();
+        let (results, pending_actions) = rank_results(
+            "example.com",
+            QueryMode::All,
+            &config,
+            SearchSources::default(),
+        );
+        assert_eq!(action_ids(&results), ["url", "search"]);
+        assert!(matches!(
+            pending_actions.get(&results[0].id),
+            Some(PendingAction::Url(url)) if url == "example.com"
+        ));
+    }
+
+    #[test]
+    fn web_search_is_only_reserved_in_modes_that_allow_it() {
+        let config = AppConfig::default();
+        let apps = [app("code", "Code")];
+        for mode in ALL_RANKED_MODES {
+            let (results, _) = rank_results("code", mode, &config, app_sources(&apps));
+            let has_web_search = results.iter().any(|result| result.action_id == "search");
+            assert_eq!(has_web_search, mode.allows_web_search(), "{mode:?}");
+        }
+    }
+
+    #[test]
+    fn results_are_sorted_by_score_with_prefix_matches_first() {
+        let config = AppConfig::default();
+        let apps = [
+            app("vscode", "Visual Studio Code"),
+            app("notepad", "Notepad"),
+            app("code", "Code"),
+        ];
+        let (results, _) =
+            rank_results("code", QueryMode::Application, &config, app_sources(&apps));
+        assert_eq!(result_ids(&results), ["app-code", "app-vscode"]);
+        assert!(results
+            .windows(2)
+            .all(|pair| pair[0].score >= pair[1].score));
+    }
+
+    #[test]
+    fn truncation_keeps_a_slot_for_web_search() {
+        let config = AppConfig {
+            max_results: 10,
+            ..AppConfig::default()
+        };
+        let apps: Vec<ApplicationInfo> = (1..=15)
+            .map(|n| app(&format!("tool{n:02}"), &format!("Tool {n:02}")))
+            .collect();
+        let (results, _) = rank_results("tool", QueryMode::All, &config, app_sources(&apps));
+        assert_eq!(results.len(), 10);
+        assert_eq!(results[9].action_id, "search");
+        assert!(results[..9].iter().all(|result| result.action_id == "app"));
+    }
+
+    #[test]
+    fn file_fallback_offers_open_with_as_secondary_action() {
+        let config = AppConfig::default();
+        let (results, pending_actions) = rank_results(
+            r"C:\missing\notes.txt",
+            QueryMode::Application,
+            &config,
+            SearchSources::default(),
+        );
+        assert_eq!(action_ids(&results), ["search", "open"]);
+        let open_with = &results[1].secondary_actions[0];
+        assert!(matches!(
+            pending_actions.get(&open_with.id),
+            Some(PendingAction::OpenWith(path)) if path == r"C:\missing\notes.txt"
+        ));
+    }
+
+    #[test]
+    fn content_results_offer_open_with_for_each_file() {
+        let matches = [ContentMatch {
+            path: r"C:\notes\todo.md".to_string(),
+            line_number: 3,
+            line: "buy milk".to_string(),
+        }];
+        let (results, pending_actions) = content_results(&matches, Locale::default());
+        assert_eq!(results.len(), 1);
+        let open_with = &results[0].secondary_actions[0];
+        assert!(matches!(
+            pending_actions.get(&open_with.id),
+            Some(PendingAction::OpenWith(path)) if path == r"C:\notes\todo.md"
+        ));
+    }
+
+    #[test]
+    fn hidden_result_stays_out_until_the_hidden_ids_are_cleared() {
+        let config = AppConfig::default();
+        let apps = [app("code", "Code"), app("codium", "Codium")];
+        let mut hidden_ids = HashSet::from(["app-code".to_string()]);
+
+        let sources = SearchSources {
+            hidden_ids: Some(&hidden_ids),
+            ..app_sources(&apps)
+        };
+        let (results, _) = rank_results("cod", QueryMode::Application, &config, sources);
+        assert_eq!(result_ids(&results), ["app-codium"]);
+
+        hidden_ids.clear();
+        let sources = SearchSources {
+            hidden_ids: Some(&hidden_ids),
+            ..app_sources(&apps)
+        };
+        let (results, _) = rank_results("cod", QueryMode::Application, &config, sources);
+        assert!(result_ids(&results).contains(&"app-code"));
+    }
+
+    fn quick_keys(results: &[SearchResult]) -> Vec<Option<u8>> {
+        results.iter().map(|result| result.quick_key).collect()
+    }
+
+    #[test]
+    fn quick_keys_number_the_first_nine_results_in_order() {
+        let mut results: Vec<SearchResult> =
+            (0..11).map(|n| scored(&format!("r{n}"), 100 - n)).collect();
+        assign_quick_keys(&mut results);
+        let expected: Vec<Option<u8>> = (1..=9).map(Some).chain([None, None]).collect();
+        assert_eq!(quick_keys(&results), expected);
+    }
+
+    #[test]
+    fn grouping_renumbers_quick_keys_in_display_order() {
+        let mut bookmark = scored("bookmark", 80);
+        bookmark.action_id = "bookmark".to_string();
+        let mut results = vec![scored("app-a", 100), bookmark, scored("app-b", 60)];
+        assign_quick_keys(&mut results);
+
+        let ordered = order_by_group(results);
+        assert_eq!(result_ids(&ordered), ["app-a", "app-b", "bookmark"]);
+        assert_eq!(quick_keys(&ordered), [Some(1), Some(2), Some(3)]);
+    }
+
+    #[test]
+    fn max_visible_results_caps_below_max_results() {
+        let config = AppConfig {
+            max_results: 10,
+            max_visible_results: 4,
+            ..AppConfig::default()
+        };
+        let apps: Vec<ApplicationInfo> = (1..=15)
+            .map(|n| app(&format!("tool{n:02}"), &format!("Tool {n:02}")))
+            .collect();
+        let (results, _) = rank_results("tool", QueryMode::All, &config, app_sources(&apps));
+        assert_eq!(results.len(), 4);
+        assert_eq!(results[3].quick_key, Some(4));
+    }
+
+    fn regex_config() -> AppConfig {
+        AppConfig {
+            regex_queries: true,
+            ..AppConfig::default()
+        }
+    }
+
+    #[test]
+    fn regex_query_matches_by_pattern() {
+        let apps = [
+            app("code", "Code"),
+            app("chrome", "Chrome"),
+            app("notepad", "Notepad"),
+        ];
+        let (results, _) = rank_results(
+            "/^c.*e$/",
+            QueryMode::Application,
+            &regex_config(),
+            app_sources(&apps),
+        );
+        let mut ids = result_ids(&results);
+        ids.sort_unstable();
+        assert_eq!(ids, ["app-chrome", "app-code"]);
+    }
+
+    #[test]
+    fn invalid_regex_query_returns_only_a_hint() {
+        let apps = [app("code", "Code")];
+        let (results, pending_actions) = rank_results(
+            "/co(de/",
+            QueryMode::All,
+            &regex_config(),
+            app_sources(&apps),
+        );
+        assert_eq!(action_ids(&results), ["hint"]);
+        assert!(pending_actions.is_empty());
+    }
+
+    #[test]
+    fn regex_query_is_case_sensitive_only_with_uppercase_letters() {
+        let apps = [app("code", "Code"), app("codec", "codec")];
+        let (results, _) = rank_results(
+            "/^Code/",
+            QueryMode::Application,
+            &regex_config(),
+            app_sources(&apps),
+        );
+        assert_eq!(result_ids(&results), ["app-code"]);
+
+        let (results, _) = rank_results(
+            "/^code/",
+            QueryMode::Application,
+            &regex_config(),
+            app_sources(&apps),
+        );
+        assert_eq!(results.len(), 2);
+    }
+
+    #[test]
+    fn slashes_are_plain_text_while_regex_queries_are_off() {
+        let apps = [app("code", "Code")];
+        let (results, _) = rank_results(
+            "/co(de/",
+            QueryMode::Application,
+            &AppConfig::default(),
+            app_sources(&apps),
+        );
+        assert!(!action_ids(&results).contains(&"hint"));
+    }
+
+    #[test]
+    fn url_like_inputs_are_recognized() {
+        for input in [
+            "example.com",
+            "http://x",
+            "localhost:3000",
+            "192.168.1.1/admin",
+            "docs.rs/regex",
+            "example.de",
+        ] {
+            assert!(is_url_like(input), "{input}");
+        }
+    }
+
+    #[test]
+    fn file_names_numbers_and_sentences_are_not_urls() {
+        for input in [
+            "file.txt",
+            "readme.md",
+            "3.14",
+            "hello world.com",
+            "",
+            "localhost:abc",
+        ] {
+            assert!(!is_url_like(input), "{input}");
+        }
+    }
+
+    #[test]
+    fn fallback_opens_url_like_input_with_https() {
+        let config = AppConfig::default();
+        assert!(matches!(
+            fallback_action("example.com", &config),
+            Some(PendingAction::Url(url)) if url == "https://example.com"
+        ));
+        assert!(matches!(
+            fallback_action("http://intranet/wiki", &config),
+            Some(PendingAction::Url(url)) if url == "http://intranet/wiki"
+        ));
+    }
+
+    #[test]
+    fn fallback_searches_the_web_for_free_text() {
+        let config = AppConfig::default();
+        let expected = web_search_url("rust closures", &config);
+        assert!(matches!(
+            fallback_action("  rust closures ", &config),
+            Some(PendingAction::Search(url)) if url == expected
+        ));
+
+        let always_search = AppConfig {
+            empty_enter_action: EmptyEnterAction::WebSearch,
+            ..AppConfig::default()
+        };
+        let expected = web_search_url("example.com", &always_search);
+        assert!(matches!(
+            fallback_action("example.com", &always_search),
+            Some(PendingAction::Search(url)) if url == expected
+        ));
+    }
+
+    #[test]
+    fn fallback_runs_nothing_when_disabled_or_blank() {
+        let nothing = AppConfig {
+            empty_enter_action: EmptyEnterAction::Nothing,
+            ..AppConfig::default()
+        };
+        assert!(fallback_action("example.com", &nothing).is_none());
+        assert!(fallback_action("   ", &AppConfig::default()).is_none());
+    }
+
+    fn matching_apps() -> [ApplicationInfo; 4] {
+        [
+            app("studio", "Studio Code"),
+            app("code", "Code Studio"),
+            app("colordesk", "Color Desk"),
+            app("paint", "Paint"),
+        ]
+    }
+
+    #[test]
+    fn skim_matches_scattered_characters() {
+        let config = AppConfig::default();
+        let apps = matching_apps();
+        let (results, _) =
+            rank_results("code", QueryMode::Application, &config, app_sources(&apps));
+        let ids = result_ids(&results);
+        assert_eq!(ids.len(), 3);
+        assert_eq!(ids[0], "app-code");
+        assert!(ids.contains(&"app-colordesk"));
+        assert!(!ids.contains(&"app-paint"));
+    }
+
+    #[test]
+    fn substring_matches_only_contiguous_text_and_prefers_earlier_matches() {
+        let config = AppConfig {
+            match_algorithm: MatchAlgorithm::Substring,
+            prefix_boost: 0,
+            ..AppConfig::default()
+        };
+        let apps = matching_apps();
+        let (results, _) =
+            rank_results("CODE", QueryMode::Application, &config, app_sources(&apps));
+        assert_eq!(result_ids(&results), ["app-code", "app-studio"]);
+    }
+
+    #[test]
+    fn substring_score_rewards_early_and_boundary_matches() {
+        let at_start = substring_score("Code Studio", "code").unwrap();
+        let after_space = substring_score("Studio Code", "code").unwrap();
+        let inside_word = substring_score("Decoder", "code").unwrap();
+        assert!(at_start > after_space);
+        assert!(after_space > inside_word);
+        assert_eq!(substring_score("Color Desk", "code"), None);
+        assert_eq!(substring_score("Code", ""), None);
+    }
+
+    fn bookmark(id: &str, title: &str) -> BookmarkEntry {
+        BookmarkEntry {
+            id: id.to_string(),
+            title: title.to_string(),
+            url: format!("https://example.com/{id}"),
+            folder_path: None,
+            browser: "Chrome".to_string(),
+            profile: "Default".to_string(),
+            keywords: Vec::new(),
+            icon_b64: String::new(),
+            date_added: None,
+        }
+    }
+
+    #[test]
+    fn bookmark_flood_does_not_push_apps_out() {
+        let config = AppConfig {
+            max_results: 10,
+            max_bookmark_results: 5,
+            ..AppConfig::default()
+        };
+        let apps = [
+            app("docscan", "Document Scanner"),
+            app("dosbox", "DOSBox Commander Suite"),
+        ];
+        let bookmarks: Vec<BookmarkEntry> = (0..50)
+            .map(|index| bookmark(&format!("b{index}"), &format!("Docs page {index}")))
+            .collect();
+        let sources = SearchSources {
+            apps: Some(&apps),
+            bookmarks: Some(&bookmarks),
+            ..SearchSources::default()
+        };
+
+        let (results, _) = rank_results("docs", QueryMode::All, &config, sources);
+        let ids = result_ids(&results);
+        assert!(ids.contains(&"app-docscan"), "{ids:?}");
+        assert!(ids.contains(&"app-dosbox"), "{ids:?}");
+        let bookmark_count = results
+            .iter()
+            .filter(|result| result.action_id == "bookmark")
+            .count();
+        assert_eq!(bookmark_count, 5);
+    }
+
+    #[test]
+    fn url_result_title_follows_the_locale() {
+        for (locale, expected) in [
+            (Locale::ZhCn, "打开网址: example.com"),
+            (Locale::En, "Open URL: example.com"),
+        ] {
+            let config = AppConfig {
+                locale,
+                ..AppConfig::default()
+            };
+            let (results, _) = rank_results(
+                "example.com",
+                QueryMode::All,
+                &config,
+                SearchSources::default(),
+            );
+            assert_eq!(results[0].action_id, "url");
+            assert_eq!(results[0].title, expected, "{locale:?}");
+        }
+    }
+
+    #[test]
+    fn prefix_match_outranks_a_higher_scoring_scattered_match() {
+        // 只有关键词以查询开头，关键词扣分后原始分数低于分散匹配，只能靠前缀加分胜出
+        let mut prefixed = app("xylo", "Xylophone");
+        prefixed.keywords = vec!["code-tools".to_string()];
+        let apps = [app("colordesk", "Color Desk"), prefixed];
+        let ranked = |prefix_boost| {
+            let config = AppConfig {
+                prefix_boost,
+                keyword_penalty: 60,
+                ..AppConfig::default()
+            };
+            let (results, _) =
+                rank_results("code", QueryMode::Application, &config, app_sources(&apps));
+            results
+        };
+
+        let without_boost = ranked(0);
+        assert_eq!(result_ids(&without_boost), ["app-colordesk", "app-xylo"]);
+
+        let with_boost = ranked(100);
+        assert_eq!(result_ids(&with_boost), ["app-xylo", "app-colordesk"]);
+    }
+
+    #[test]
+    fn prefix_bonus_only_applies_to_candidates_starting_with_the_query() {
+        assert_eq!(prefix_bonus(["Code Studio"].into_iter(), "code", 50), 50);
+        assert_eq!(prefix_bonus(["Studio", "CODE"].into_iter(), "co", 50), 50);
+        assert_eq!(prefix_bonus(["Studio Code"].into_iter(), "code", 50), 0);
+        assert_eq!(prefix_bonus(["Code"].into_iter(), "code", 0), 0);
+    }
+
+    #[test]
+    fn running_flag_follows_the_process_list() {
+        let config = AppConfig::default();
+        let mut store_code = app("storecode", "Code Store Edition");
+        store_code.app_type = AppType::Uwp;
+        let apps = [app("code", "Code"), app("vscode", "VS Code"), store_code];
+        // 进程路径的大小写和分隔符与索引中的不同
+        let running_exes: HashSet<String> = [r"c:/apps/CODE.exe", r"C:\Apps\storecode.exe"]
+            .into_iter()
+            .map(normalize_exe_path)
+            .collect();
+        let sources = SearchSources {
+            apps: Some(&apps),
+            running_exes: Some(&running_exes),
+            ..SearchSources::default()
+        };
+
+        let (results, _) = rank_results("code", QueryMode::Application, &config, sources);
+        let running: Vec<(&str, bool)> = results
+            .iter()
+            .map(|result| (result.id.as_str(), result.running))
+            .collect();
+        assert!(running.contains(&("app-code", true)), "{running:?}");
+        assert!(running.contains(&("app-vscode", false)), "{running:?}");
+        // 只有 Win32 应用会标记为运行中
+        assert!(running.contains(&("app-storecode", false)), "{running:?}");
+    }
+
+    #[test]
+    fn apps_on_missing_drives_are_flagged_or_excluded() {
+        let mut config = AppConfig::default();
+        let mut game = app("game", "Code Game");
+        game.path = r"E:\Games\game.exe".to_string();
+        let apps = [app("code", "Code"), game];
+        let available: HashSet<char> = ['C'].into_iter().collect();
+        let sources = SearchSources {
+            apps: Some(&apps),
+            available_drives: Some(&available),
+            ..SearchSources::default()
+        };
+
+        config.exclude_unavailable_drives = false;
+        let (results, _) = rank_results("code", QueryMode::Application, &config, sources);
+        let flags: Vec<(&str, bool)> = results
+            .iter()
+            .map(|result| (result.id.as_str(), result.drive_unavailable))
+            .collect();
+        assert!(flags.contains(&("app-code", false)), "{flags:?}");
+        assert!(flags.contains(&("app-game", true)), "{flags:?}");
+
+        config.exclude_unavailable_drives = true;
+        let (results, _) = rank_results("code", QueryMode::Application, &config, sources);
+        assert_eq!(result_ids(&results), ["app-code"]);
+    }
+
+    #[test]
+    fn copy_icon_is_offered_only_for_apps_with_an_icon() {
+        let config = AppConfig::default();
+        let mut with_icon = app("code", "Code");
+        with_icon.icon_b64 = "iVBORw0KGgo=".to_string();
+        let apps = [with_icon, app("codex", "Codex")];
+
+        let (results, pending_actions) =
+            rank_results("code", QueryMode::Application, &config, app_sources(&apps));
+        let copy_actions = |id: &str| -> Vec<String> {
+            results
+                .iter()
+                .find(|result| result.id == id)
+                .expect(id)
+                .secondary_actions
+                .iter()
+                .map(|action| action.id.clone())
+                .filter(|action_id| action_id.starts_with("copy-icon-"))
+                .collect()
+        };
+
+        assert_eq!(copy_actions("app-code"), ["copy-icon-code"]);
+        assert!(copy_actions("app-codex").is_empty());
+        assert!(matches!(
+            pending_actions.get("copy-icon-code"),
+            Some(PendingAction::CopyImage(icon)) if icon == "iVBORw0KGgo="
+        ));
+        assert!(!pending_actions.contains_key("copy-icon-codex"));
+    }
+
+    #[test]
+    fn typed_arguments_combine_with_saved_ones_per_the_policy() {
+        let mut notepad = app("notepad", "Notepad");
+        notepad.launch_args = Some("--saved".to_string());
+        let apps = [notepad];
+        let launched_args = |policy: TypedLaunchArgs| {
+            let config = AppConfig {
+                typed_launch_args: policy,
+                ..AppConfig::default()
+            };
+            let (_, pending_actions) = rank_results(
+                "notepad notes.txt",
+                QueryMode::Application,
+                &config,
+                app_sources(&apps),
+            );
+            match pending_actions.get("args-notepad") {
+                Some(PendingAction::Application(app)) => app.launch_args.clone(),
+                _ => panic!("no launch-with-arguments result"),
+            }
+        };
+
+        assert_eq!(
+            launched_args(TypedLaunchArgs::Append).as_deref(),
+            Some("--saved notes.txt")
+        );
+        assert_eq!(
+            launched_args(TypedLaunchArgs::Replace).as_deref(),
+            Some("notes.txt")
+        );
+    }
+}
@@ -1,9 +1,23 @@
 use std::{
-    collections::HashMap,
-    sync::{Arc, Mutex},
+    collections::{HashMap, HashSet, VecDeque},
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    time::Instant,
 };
 
-use crate::{bookmarks::BookmarkEntry, config::AppConfig, models::ApplicationInfo};
+use crate::{
+    bookmarks::{BookmarkEntry, BookmarkFolder},
+    clipboard_history::ClipboardEntry,
+    config::{AppConfig, ModeHotkey},
+    drives::DriveCache,
+    history::HistoryEntry,
+    models::ApplicationInfo,
+    processes::ProcessCache,
+    search::QueryMode,
+    usage::UsageEvent,
+};
 
 #[derive(Clone)]
 pub enum PendingAction {
@@ -11,15 +25,85 @@ pub enum PendingAction {
     Bookmark(BookmarkEntry),
     Url(String),
     Search(String),
+    RawOpen(String),
+    OpenWith(String),
+    PinToTaskbar(String),
+    RestartApp(ApplicationInfo),
+    CopyText(String),
+    /// Base64 encoded PNG to put on the clipboard as an image.
+    CopyImage(String),
+    /// A file or folder, opened with its default handler.
+    OpenPath(String),
+    /// Every URL of a bookmark folder, opened one after another.
+    OpenBookmarkFolder(Vec<String>),
+    Command {
+        target: String,
+        arguments: String,
+    },
+    /// A command line typed in run mode, started like the Run dialog would.
+    RunCommand(String),
+}
+
+/// Result ids reachable through Alt+1..9, in display order, for one query generation.
+#[derive(Debug, Default)]
+pub struct QuickKeys {
+    pub generation: u64,
+    pub ids: Vec<String>,
 }
 
 #[derive(Default)]
 pub struct AppState {
     pub app_index: Arc<Mutex<Vec<ApplicationInfo>>>,
     pub bookmark_index: Arc<Mutex<Vec<BookmarkEntry>>>,
+    /// `bookmark_index` grouped by folder, rebuilt together with it.
+    pub bookmark_folders: Arc<Mutex<Vec<BookmarkFolder>>>,
+    pub history_index: Arc<Mutex<Vec<HistoryEntry>>>,
     pub config: Arc<Mutex<AppConfig>>,
     pub registered_hotkey: Arc<Mutex<Option<String>>>,
+    /// Mode hotkeys bound through `bind_mode_hotkeys`, also kept while snoozed so they can be
+    /// restored. Lock after `registered_hotkey`.
+    pub registered_mode_hotkeys: Arc<Mutex<Vec<ModeHotkey>>>,
+    /// Set while the hotkey is snoozed; `registered_hotkey` then holds the accelerator to restore.
+    pub hotkey_suspended: Arc<AtomicBool>,
+    /// Bumped on every suspend/resume so a pending auto re-enable can tell it is outdated.
+    pub hotkey_snooze_generation: Arc<AtomicU64>,
     pub pending_actions: Arc<Mutex<HashMap<String, PendingAction>>>,
+    pub app_reindex_in_progress: Arc<AtomicBool>,
+    pub bookmark_reindex_in_progress: Arc<AtomicBool>,
+    pub history_reindex_in_progress: Arc<AtomicBool>,
+    /// Unix seconds when the app index was last rebuilt; 0 until the first rebuild finishes.
+    pub last_app_reindex: Arc<AtomicU64>,
+    pub process_cache: Arc<Mutex<ProcessCache>>,
+    pub drive_cache: Arc<Mutex<DriveCache>>,
+    /// Bumped by every content query so a running ripgrep can tell it has been superseded.
+    pub content_search_generation: Arc<AtomicU64>,
+    /// Result ids hidden via `hide_result_for_session`; in memory only, cleared on restart.
+    pub session_hidden: Arc<Mutex<HashSet<String>>>,
+    /// Bumped whenever a new result set is published; guards `execute_quick` against stale keys.
+    pub query_generation: Arc<AtomicU64>,
+    /// Bumped when a query starts; a query that finishes after a newer one started doesn't
+    /// publish its results, so overlapping queries can't replace newer results with older ones.
+    pub query_ticket: Arc<AtomicU64>,
+    pub quick_keys: Arc<Mutex<QuickKeys>>,
+    /// Why settings can't be persisted, as found by the startup check; `None` when they can.
+    pub config_persistence_error: Arc<Mutex<Option<String>>>,
+    /// Set once English input was forced for the current hotkey activation; the hotkey clears it.
+    pub english_forced_this_activation: Arc<AtomicBool>,
+    /// Whether the last submitted query contains non-Latin letters, e.g. typed through an IME.
+    pub query_non_latin: Arc<AtomicBool>,
+    /// Local launch log behind `get_analytics`, mirrored to `usage.json`.
+    pub usage_log: Arc<Mutex<Vec<UsageEvent>>>,
+    /// [`crate::usage::launch_counts`] of `usage_log`, kept for ranking. Lock after `usage_log`.
+    pub launch_counts: Arc<Mutex<HashMap<String, u32>>>,
+    /// Command lines started in run mode, newest first, mirrored to `run_history.json`.
+    pub run_history: Arc<Mutex<Vec<String>>>,
+    /// Mode of the last submitted query, recorded with each launch.
+    pub last_query_mode: Arc<Mutex<Option<QueryMode>>>,
+    /// Result id and time of the last action `execute_action(s)` started, for
+    /// `launch_cooldown_ms`. Claimed before the launch and dropped again when it fails.
+    pub last_execution: Arc<Mutex<Option<(String, Instant)>>>,
+    /// Recently copied text, newest first; in memory only and empty while disabled.
+    pub clipboard_history: Arc<Mutex<VecDeque<ClipboardEntry>>>,
 }
 
 impl AppState {
@@ -27,9 +111,45 @@ impl AppState {
         Self {
             app_index: Arc::new(Mutex::new(Vec::new())),
             bookmark_index: Arc::new(Mutex::new(Vec::new())),
+            bookmark_folders: Arc::new(Mutex::new(Vec::new())),
+            history_index: Arc::new(Mutex::new(Vec::new())),
             config: Arc::new(Mutex::new(AppConfig::default())),
             registered_hotkey: Arc::new(Mutex::new(None)),
+            registered_mode_hotkeys: Arc::new(Mutex::new(Vec::new())),
+            hotkey_suspended: Arc::new(AtomicBool::new(false)),
+            hotkey_snooze_generation: Arc::new(AtomicU64::new(0)),
             pending_actions: Arc::new(Mutex::new(HashMap::new())),
+            app_reindex_in_progress: Arc::new(AtomicBool::new(false)),
+            bookmark_reindex_in_progress: Arc::new(AtomicBool::new(false)),
+            history_reindex_in_progress: Arc::new(AtomicBool::new(false)),
+            last_app_reindex: Arc::new(AtomicU64::new(0)),
+            process_cache: Arc::new(Mutex::new(ProcessCache::default())),
+            drive_cache: Arc::new(Mutex::new(DriveCache::default())),
+            content_search_generation: Arc::new(AtomicU64::new(0)),
+            session_hidden: Arc::new(Mutex::new(HashSet::new())),
+            query_generation: Arc::new(AtomicU64::new(0)),
+            query_ticket: Arc::new(AtomicU64::new(0)),
+            quick_keys: Arc::new(Mutex::new(QuickKeys::default())),
+            config_persistence_error: Arc::new(Mutex::new(None)),
+            english_forced_this_activation: Arc::new(AtomicBool::new(false)),
+            query_non_latin: Arc::new(AtomicBool::new(false)),
+            usage_log: Arc::new(Mutex::new(Vec::new())),
+            launch_counts: Arc::new(Mutex::new(HashMap::new())),
+            run_history: Arc::new(Mutex::new(Vec::new())),
+            last_query_mode: Arc::new(Mutex::new(None)),
+            last_execution: Arc::new(Mutex::new(None)),
+            clipboard_history: Arc::new(Mutex::new(VecDeque::new())),
         }
     }
+
+    /// Whether any index is currently being rebuilt. Only reads the flags, never blocks.
+    pub fn is_reindexing(&self) -> bool {
+        [
+            &self.app_reindex_in_progress,
+            &self.bookmark_reindex_in_progress,
+            &self.history_reindex_in_progress,
+        ]
+        .iter()
+        .any(|flag| flag.load(Ordering::Acquire))
+    }
 }
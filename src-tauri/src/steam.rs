@@ -0,0 +1,248 @@
+use std::{
+    collections::HashSet,
+    env, fs,
+    io::Cursor,
+    path::{Path, PathBuf},
+};
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use image::{imageops::FilterType, ImageFormat};
+use log::{debug, warn};
+use winreg::{enums::HKEY_CURRENT_USER, RegKey};
+
+use crate::{
+    ids,
+    models::{AppOrigin, AppType, ApplicationInfo},
+    text_utils::extend_keywords_with_pinyin,
+};
+
+/// App ids of Steam's own tools that come with an app manifest but aren't games.
+const NON_GAME_APP_IDS: &[&str] = &[
+    "228980",  // Steamworks Common Redistributables
+    "1070560", // Steam Linux Runtime
+    "1391110", // Steam Linux Runtime - Soldier
+    "1628350", // Steam Linux Runtime - Sniper
+];
+/// Edge length of the PNG icons made from Steam's library artwork.
+const ICON_SIZE: u32 = 64;
+
+/// URI that asks the Steam client to start the game with `app_id`.
+pub fn launch_uri(app_id: &str) -> String {
+    format!("steam://rungameid/{app_id}")
+}
+
+/// Lists the installed games of every Steam library as launchable entries.
+///
+/// Libraries come from `steamapps\libraryfolders.vdf` under the Steam install, and each
+/// `appmanifest_<id>.acf` in them yields one game that launches through [`launch_uri`].
+/// Icons are read from Steam's `librarycache` when `extract_icons` is set.
+pub fn enumerate_steam_games(extract_icons: bool) -> Vec<ApplicationInfo> {
+    let Some(steam_dir) = steam_install_dir() else {
+        debug!("Steam is not installed, skipping games");
+        return Vec::new();
+    };
+
+    let mut seen = HashSet::new();
+    let mut games = Vec::new();
+    for library in library_folders(&steam_dir) {
+        let Ok(entries) = fs::read_dir(library.join("steamapps")) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let is_manifest = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with("appmanifest_") && name.ends_with(".acf"));
+            if !is_manifest {
+                continue;
+            }
+            let Some((app_id, name)) = read_app_manifest(&path) else {
+                continue;
+            };
+            if NON_GAME_APP_IDS.contains(&app_id.as_str()) || !seen.insert(app_id.clone()) {
+                continue;
+            }
+            games.push(game_to_application(
+                &steam_dir,
+                &app_id,
+                &name,
+                extract_icons,
+            ));
+        }
+    }
+    games
+}
+
+fn game_to_application(
+    steam_dir: &Path,
+    app_id: &str,
+    name: &str,
+    extract_icons: bool,
+) -> ApplicationInfo {
+    let uri = launch_uri(app_id);
+    let mut keywords = vec![name.to_string(), "Steam".to_string()];
+    extend_keywords_with_pinyin(&mut keywords);
+    keywords.sort();
+    keywords.dedup();
+    let icon_b64 = if extract_icons {
+        load_library_icon(steam_dir, app_id).unwrap_or_default()
+    } else {
+        String::new()
+    };
+
+    ApplicationInfo {
+        // 与开始菜单中 Steam 生成的 .url 快捷方式同 id，两者在去重时合并为一项
+        id: ids::url_id(&uri),
+        name: name.to_string(),
+        path: uri.clone(),
+        source_path: Some(uri),
+        app_type: AppType::Win32,
+        icon_b64,
+        description: Some("Steam".to_string()),
+        keywords,
+        working_directory: None,
+        arguments: None,
+        origin: Some(AppOrigin::Steam {
+            app_id: app_id.to_string(),
+        }),
+        icon_source: None,
+        launch_args: None,
+        indexed_name: None,
+    }
+}
+
+/// The Steam install directory from the client's registry key, falling back to the default
+/// location under Program Files (x86).
+fn steam_install_dir() -> Option<PathBuf> {
+    let from_registry = RegKey::predef(HKEY_CURRENT_USER)
+        .open_subkey("Software\\Valve\\Steam")
+        .and_then(|key| key.get_value::<String, _>("SteamPath"))
+        .ok()
+        .map(|path| PathBuf::from(path.replace('/', "\\")));
+    let fallback = env::var_os("ProgramFiles(x86)").map(|dir| PathBuf::from(dir).join("Steam"));
+    from_registry
+        .into_iter()
+        .chain(fallback)
+        .find(|dir| dir.join("steamapps").is_dir())
+}
+
+/// Library roots listed in `libraryfolders.vdf`; the install directory itself is always one.
+fn library_folders(steam_dir: &Path) -> Vec<PathBuf> {
+    let mut folders = vec![steam_dir.to_path_buf()];
+    let vdf_path = steam_dir.join("steamapps").join("libraryfolders.vdf");
+    let Ok(content) = fs::read_to_string(&vdf_path) else {
+        return folders;
+    };
+    for (key, value) in vdf_pairs(&content) {
+        if !key.eq_ignore_ascii_case("path") {
+            continue;
+        }
+        let folder = PathBuf::from(value);
+        if !folders
+            .iter()
+            .any(|known| known.as_os_str().eq_ignore_ascii_case(folder.as_os_str()))
+        {
+            folders.push(folder);
+        }
+    }
+    folders
+}
+
+/// `(app id, name)` from an `appmanifest_<id>.acf` file.
+fn read_app_manifest(path: &Path) -> Option<(String, String)> {
+    let content = fs::read_to_string(path)
+        .inspect_err(|err| warn!("failed to read Steam manifest {:?}: {err}", path))
+        .ok()?;
+    let mut app_id = None;
+    let mut name = None;
+    for (key, value) in vdf_pairs(&content) {
+        if app_id.is_none() && key.eq_ignore_ascii_case("appid") {
+            app_id = Some(value);
+        } else if name.is_none() && key.eq_ignore_ascii_case("name") {
+            name = Some(value);
+        }
+    }
+    let app_id = app_id.filter(|id| !id.is_empty() && id.bytes().all(|b| b.is_ascii_digit()))?;
+    let name = name
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())?;
+    Some((app_id, name))
+}
+
+/// Every `"key" "value"` pair of a Valve KeyValues (VDF/ACF) document, in order and at any
+/// depth. Nesting isn't tracked since the keys read here are unique where they appear.
+fn vdf_pairs(content: &str) -> Vec<(String, String)> {
+    let mut pairs = Vec::new();
+    let mut pending_key: Option<String> = None;
+    let mut chars = content.chars();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '"' => {
+                let mut token = String::new();
+                while let Some(ch) = chars.next() {
+                    match ch {
+                        '"' => break,
+                        '\\' => {
+                            if let Some(escaped) = chars.next() {
+                                token.push(escaped);
+                            }
+                        }
+                        other => token.push(other),
+                    }
+                }
+                match pending_key.take() {
+                    Some(key) => pairs.push((key, token)),
+                    None => pending_key = Some(token),
+                }
+            }
+            // 花括号表示该键对应一个子节点而不是值
+            '{' | '}' => pending_key = None,
+            _ => {}
+        }
+    }
+    pairs
+}
+
+/// The game's icon from `appcache\librarycache`, converted to a base64 PNG like the other
+/// app icons.
+fn load_library_icon(steam_dir: &Path, app_id: &str) -> Option<String> {
+    let cache_dir = steam_dir.join("appcache").join("librarycache");
+    let path = [
+        cache_dir.join(format!("{app_id}_icon.jpg")),
+        cache_dir.join(format!("{app_id}_library_600x900.jpg")),
+    ]
+    .into_iter()
+    .find(|candidate| candidate.is_file())?;
+    let image = image::open(&path)
+        .inspect_err(|err| debug!("failed to decode Steam icon {:?}: {err}", path))
+        .ok()?;
+    let icon = image.resize(ICON_SIZE, ICON_SIZE, FilterType::Triangle);
+    let mut png = Vec::new();
+    icon.write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+        .ok()?;
+    Some(BASE64.encode(png))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn library_icon_is_loaded_only_with_icons_on() {
+        let steam_dir = env::temp_dir().join(format!("steam-icons-{}", std::process::id()));
+        let cache_dir = steam_dir.join("appcache").join("librarycache");
+        fs::create_dir_all(&cache_dir).unwrap();
+        image::RgbImage::new(4, 4)
+            .save(cache_dir.join("570_icon.jpg"))
+            .unwrap();
+
+        let with_icons = game_to_application(&steam_dir, "570", "Dota 2", true);
+        let without_icons = game_to_application(&steam_dir, "570", "Dota 2", false);
+        let _ = fs::remove_dir_all(&steam_dir);
+
+        assert!(!with_icons.icon_b64.is_empty());
+        assert!(without_icons.icon_b64.is_empty());
+        assert_eq!(with_icons.id, without_icons.id);
+    }
+}
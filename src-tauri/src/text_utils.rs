@@ -1,4 +1,7 @@
+use std::borrow::Cow;
+
 use pinyin::ToPinyin;
+use unicode_normalization::{char::is_combining_mark, UnicodeNormalization};
 
 /// Extend the given keyword list with pinyin variants so that
 /// fuzzy matching can work with full pinyin and initials.
@@ -16,14 +19,27 @@ pub fn extend_keywords_with_pinyin(keywords: &mut Vec<String>) {
 }
 
 fn extend_single_keyword(source: &str, target: &mut Vec<String>) {
-    // Track whether at least one Chinese character produced a syllable.
     let mut syllables: Vec<String> = Vec::new();
     let mut initials = String::new();
+    // Track whether at least one Chinese character produced a syllable.
+    let mut has_chinese = false;
+    // 夹在汉字之间的字母数字原样保留，例如 "QQ音乐" 生成 "qqyinyue" 与 "qqyy"
+    let mut latin = String::new();
 
-    for maybe in source.to_pinyin() {
+    for (ch, maybe) in source.chars().zip(source.to_pinyin()) {
         let Some(pinyin) = maybe else {
+            if ch.is_ascii_alphanumeric() {
+                latin.push(ch.to_ascii_lowercase());
+            } else if !latin.is_empty() {
+                initials.push_str(&latin);
+                syllables.push(std::mem::take(&mut latin));
+            }
             continue;
         };
+        if !latin.is_empty() {
+            initials.push_str(&latin);
+            syllables.push(std::mem::take(&mut latin));
+        }
         let plain = pinyin.plain();
         if plain.is_empty() {
             continue;
@@ -38,11 +54,16 @@ fn extend_single_keyword(source: &str, target: &mut Vec<String>) {
             initials.push(initial);
         }
         syllables.push(syllable);
+        has_chinese = true;
     }
 
-    if syllables.is_empty() {
+    if !has_chinese {
         return;
     }
+    if !latin.is_empty() {
+        initials.push_str(&latin);
+        syllables.push(latin);
+    }
 
     // 连写形式，例如 "weixin"。
     let joined = syllables.join("");
@@ -63,3 +84,18 @@ fn extend_single_keyword(source: &str, target: &mut Vec<String>) {
         target.push(initials);
     }
 }
+
+/// Strips diacritics so `Café` and `cafe` compare equal: decomposes the text, drops the
+/// combining marks and recomposes the rest, which keeps Hangul and other precomposed
+/// scripts intact. ASCII text is returned as is.
+pub fn fold_accents(text: &str) -> Cow<'_, str> {
+    if text.is_ascii() {
+        return Cow::Borrowed(text);
+    }
+    Cow::Owned(
+        text.nfd()
+            .filter(|ch| !is_combining_mark(*ch))
+            .nfc()
+            .collect(),
+    )
+}
@@ -0,0 +1,97 @@
+use tauri::{
+    menu::{Menu, MenuBuilder},
+    tray::TrayIconBuilder,
+    App, AppHandle, Emitter, Manager, Runtime,
+};
+
+use std::sync::atomic::Ordering;
+
+use crate::{
+    commands::{emit_hide_window, HideReason, OPEN_SETTINGS_EVENT},
+    hotkey,
+    i18n::{tr, Locale},
+    show_window,
+    state::AppState,
+};
+
+const TRAY_ID: &str = "main-tray";
+const MENU_SHOW: &str = "tray-show";
+const MENU_HIDE: &str = "tray-hide";
+const MENU_SETTINGS: &str = "tray-settings";
+const MENU_TOGGLE_HOTKEY: &str = "tray-toggle-hotkey";
+const MENU_QUIT: &str = "tray-quit";
+
+pub(crate) fn build_tray_menu<R: Runtime, M: Manager<R>>(
+    manager: &M,
+    locale: Locale,
+) -> tauri::Result<Menu<R>> {
+    let hotkey_suspended = manager
+        .try_state::<AppState>()
+        .is_some_and(|state| state.hotkey_suspended.load(Ordering::Acquire));
+    let toggle_hotkey_label = if hotkey_suspended {
+        tr(locale, "tray.enable_hotkey")
+    } else {
+        tr(locale, "tray.disable_hotkey")
+    };
+
+    MenuBuilder::new(manager)
+        .text(MENU_SHOW, tr(locale, "tray.show"))
+        .text(MENU_HIDE, tr(locale, "tray.hide"))
+        .separator()
+        .text(MENU_TOGGLE_HOTKEY, toggle_hotkey_label)
+        .text(MENU_SETTINGS, tr(locale, "tray.settings"))
+        .separator()
+        .text(MENU_QUIT, tr(locale, "tray.quit"))
+        .build()
+}
+
+pub(crate) fn create_tray(app: &App, locale: Locale) -> tauri::Result<()> {
+    let tray_menu = build_tray_menu(app, locale)?;
+
+    let tray_builder = if let Some(icon) = app.default_window_icon().cloned() {
+        TrayIconBuilder::with_id(TRAY_ID).icon(icon)
+    } else {
+        TrayIconBuilder::with_id(TRAY_ID)
+    };
+
+    tray_builder
+        .menu(&tray_menu)
+        .tooltip("RustLauncher")
+        .on_menu_event(|app_handle, event| match event.id().as_ref() {
+            MENU_SHOW => show_window(app_handle),
+            MENU_HIDE => {
+                // 通过事件通知前端统一执行“重置搜索 + 隐藏窗口”逻辑
+                emit_hide_window(app_handle, HideReason::Tray);
+            }
+            MENU_TOGGLE_HOTKEY => {
+                let state = app_handle.state::<AppState>();
+                let result = if state.hotkey_suspended.load(Ordering::Acquire) {
+                    hotkey::resume_hotkey(app_handle, &state)
+                } else {
+                    hotkey::suspend_hotkey(app_handle, &state, None)
+                };
+                if let Err(err) = result {
+                    log::warn!("failed to toggle hotkey from tray: {err}");
+                }
+            }
+            MENU_SETTINGS => {
+                let _ = app_handle.emit(OPEN_SETTINGS_EVENT, ());
+            }
+            MENU_QUIT => {
+                app_handle.exit(0);
+            }
+            _ => {}
+        })
+        .build(app)?;
+
+    Ok(())
+}
+
+/// Rebuilds the tray menu in `locale`; the menu event handler is kept since the item ids don't change.
+pub(crate) fn refresh_tray_menu(app_handle: &AppHandle, locale: Locale) -> Result<(), String> {
+    let Some(tray) = app_handle.tray_by_id(TRAY_ID) else {
+        return Err("托盘图标尚未创建".into());
+    };
+    let menu = build_tray_menu(app_handle, locale).map_err(|err| err.to_string())?;
+    tray.set_menu(Some(menu)).map_err(|err| err.to_string())
+}
@@ -0,0 +1,277 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs,
+    path::PathBuf,
+};
+
+use chrono::{Duration, Local, TimeZone};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+use crate::{search::QueryMode, state::PendingAction};
+
+const USAGE_FILE: &str = "usage.json";
+/// Oldest events are dropped beyond this, which keeps roughly a year of heavy use.
+const MAX_EVENTS: usize = 20_000;
+const TOP_ENTRIES: usize = 10;
+
+/// What kind of result a recorded launch was.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UsageKind {
+    App,
+    Bookmark,
+    Url,
+    Search,
+    Command,
+    Open,
+}
+
+/// One launch, as stored in `usage.json`. Only kept on this machine.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UsageEvent {
+    /// Unix seconds.
+    pub at: i64,
+    pub kind: UsageKind,
+    /// App or bookmark id, otherwise the launched target.
+    pub key: String,
+    pub title: String,
+    /// Mode of the query the result came from.
+    pub mode: String,
+}
+
+impl UsageEvent {
+    /// The event for running `action`, or `None` for actions that don't launch anything,
+    /// such as copying text or pinning to the taskbar.
+    pub fn from_action(action: &PendingAction, mode: QueryMode, at: i64) -> Option<Self> {
+        let (kind, key, title) = match action {
+            PendingAction::Application(app) => (UsageKind::App, app.id.clone(), app.name.clone()),
+            PendingAction::Bookmark(entry) => {
+                (UsageKind::Bookmark, entry.id.clone(), entry.title.clone())
+            }
+            PendingAction::Url(url) => (UsageKind::Url, url.clone(), url.clone()),
+            PendingAction::Search(url) => (UsageKind::Search, url.clone(), url.clone()),
+            PendingAction::Command { target, .. } => {
+                (UsageKind::Command, target.clone(), target.clone())
+            }
+            PendingAction::RunCommand(command) => {
+                (UsageKind::Command, command.clone(), command.clone())
+            }
+            PendingAction::RawOpen(target) | PendingAction::OpenPath(target) => {
+                (UsageKind::Open, target.clone(), target.clone())
+            }
+            _ => return None,
+        };
+        Some(Self {
+            at,
+            kind,
+            key,
+            title,
+            mode: mode.label().to_string(),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DailyLaunches {
+    /// Local date, `YYYY-MM-DD`.
+    pub date: String,
+    pub count: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct UsageCount {
+    pub key: String,
+    pub title: String,
+    pub count: usize,
+}
+
+/// Launch statistics over the last `range_days` days, computed from the local usage log.
+#[derive(Debug, Clone, Serialize)]
+pub struct UsageAnalytics {
+    pub range_days: u32,
+    pub total_launches: usize,
+    /// One entry per day in the range, oldest first, including days without launches.
+    pub launches_per_day: Vec<DailyLaunches>,
+    pub top_apps: Vec<UsageCount>,
+    pub top_bookmarks: Vec<UsageCount>,
+    /// Launches per query mode (`all`, `app`, `bookmark`, `search`).
+    pub mode_usage: BTreeMap<String, usize>,
+}
+
+/// Aggregates `events` into [`UsageAnalytics`] for the `range_days` days ending today.
+pub fn aggregate(events: &[UsageEvent], range_days: u32, now: i64) -> UsageAnalytics {
+    let range_days = range_days.max(1);
+    let today = local_date(now);
+    let first_day = today - Duration::days(i64::from(range_days) - 1);
+
+    let mut per_day: BTreeMap<String, usize> = (0..i64::from(range_days))
+        .map(|offset| {
+            let day = first_day + Duration::days(offset);
+            (day.format("%Y-%m-%d").to_string(), 0)
+        })
+        .collect();
+    let mut apps: HashMap<&str, (&str, usize)> = HashMap::new();
+    let mut bookmarks: HashMap<&str, (&str, usize)> = HashMap::new();
+    let mut mode_usage = BTreeMap::new();
+    let mut total_launches = 0;
+
+    for event in events {
+        let day = local_date(event.at);
+        if day < first_day || day > today {
+            continue;
+        }
+        total_launches += 1;
+        *per_day
+            .entry(day.format("%Y-%m-%d").to_string())
+            .or_default() += 1;
+        *mode_usage.entry(event.mode.clone()).or_default() += 1;
+        let counts = match event.kind {
+            UsageKind::App => &mut apps,
+            UsageKind::Bookmark => &mut bookmarks,
+            _ => continue,
+        };
+        // 同一条目以最近一次记录的标题为准
+        let slot = counts
+            .entry(event.key.as_str())
+            .or_insert((event.title.as_str(), 0));
+        slot.0 = event.title.as_str();
+        slot.1 += 1;
+    }
+
+    UsageAnalytics {
+        range_days,
+        total_launches,
+        launches_per_day: per_day
+            .into_iter()
+            .map(|(date, count)| DailyLaunches { date, count })
+            .collect(),
+        top_apps: top_counts(apps),
+        top_bookmarks: top_counts(bookmarks),
+        mode_usage,
+    }
+}
+
+fn top_counts(counts: HashMap<&str, (&str, usize)>) -> Vec<UsageCount> {
+    let mut top: Vec<UsageCount> = counts
+        .into_iter()
+        .map(|(key, (title, count))| UsageCount {
+            key: key.to_string(),
+            title: title.to_string(),
+            count,
+        })
+        .collect();
+    top.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.title.cmp(&b.title)));
+    top.truncate(TOP_ENTRIES);
+    top
+}
+
+fn local_date(at: i64) -> chrono::NaiveDate {
+    Local
+        .timestamp_opt(at, 0)
+        .single()
+        .unwrap_or_else(Local::now)
+        .date_naive()
+}
+
+/// How often each app and bookmark was launched, keyed by app or bookmark id.
+pub fn launch_counts(events: &[UsageEvent]) -> HashMap<String, u32> {
+    let mut counts = HashMap::new();
+    for event in events {
+        if matches!(event.kind, UsageKind::App | UsageKind::Bookmark) {
+            *counts.entry(event.key.clone()).or_default() += 1;
+        }
+    }
+    counts
+}
+
+/// Reads the usage log; a missing or unreadable file yields an empty log.
+pub fn load(handle: &AppHandle) -> Vec<UsageEvent> {
+    usage_path(handle)
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+pub fn save(handle: &AppHandle, events: &[UsageEvent]) -> Result<(), String> {
+    let path = usage_path(handle).ok_or_else(|| "无法确定配置目录".to_string())?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|err| err.to_string())?;
+    }
+    let data = serde_json::to_string(events).map_err(|err| err.to_string())?;
+    fs::write(path, data).map_err(|err| err.to_string())
+}
+
+/// Appends `event`, dropping the oldest events beyond [`MAX_EVENTS`].
+pub fn push_event(events: &mut Vec<UsageEvent>, event: UsageEvent) {
+    events.push(event);
+    if events.len() > MAX_EVENTS {
+        let excess = events.len() - MAX_EVENTS;
+        events.drain(..excess);
+    }
+}
+
+pub(crate) fn usage_path(handle: &AppHandle) -> Option<PathBuf> {
+    handle
+        .path()
+        .app_config_dir()
+        .ok()
+        .map(|dir| dir.join(USAGE_FILE))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn local_time(day: u32, hour: u32) -> i64 {
+        Local
+            .with_ymd_and_hms(2026, 3, day, hour, 0, 0)
+            .single()
+            .unwrap()
+            .timestamp()
+    }
+
+    fn launch(at: i64, kind: UsageKind, key: &str) -> UsageEvent {
+        UsageEvent {
+            at,
+            kind,
+            key: key.to_string(),
+            title: key.to_string(),
+            mode: "all".to_string(),
+        }
+    }
+
+    #[test]
+    fn aggregate_buckets_launches_by_local_day() {
+        let events = [
+            launch(local_time(1, 12), UsageKind::App, "old"),
+            launch(local_time(9, 12), UsageKind::Bookmark, "docs"),
+            launch(local_time(10, 8), UsageKind::App, "code"),
+            launch(local_time(10, 20), UsageKind::App, "code"),
+        ];
+        let analytics = aggregate(&events, 7, local_time(10, 21));
+
+        assert_eq!(analytics.total_launches, 3);
+        let days: Vec<(&str, usize)> = analytics
+            .launches_per_day
+            .iter()
+            .map(|day| (day.date.as_str(), day.count))
+            .collect();
+        assert_eq!(
+            days,
+            [
+                ("2026-03-04", 0),
+                ("2026-03-05", 0),
+                ("2026-03-06", 0),
+                ("2026-03-07", 0),
+                ("2026-03-08", 0),
+                ("2026-03-09", 1),
+                ("2026-03-10", 2),
+            ]
+        );
+        assert_eq!(analytics.top_apps[0].key, "code");
+        assert_eq!(analytics.top_apps[0].count, 2);
+        assert_eq!(analytics.top_apps.len(), 1);
+        assert_eq!(analytics.top_bookmarks[0].key, "docs");
+    }
+}
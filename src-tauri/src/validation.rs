@@ -0,0 +1,373 @@
+use serde::Serialize;
+
+use crate::{
+    bookmarks,
+    config::{
+        normalize_prefix, AppConfig, ICON_SIZES, MAX_WINDOW_OPACITY, MIN_WINDOW_OPACITY,
+        SEARCH_QUERY_PLACEHOLDER,
+    },
+    double_tap, hotkey,
+    search::QueryMode,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IssueSeverity {
+    /// The value can't be applied as-is.
+    Error,
+    /// The value is usable but will be adjusted (e.g. clamped) or is likely a mistake.
+    Warning,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfigIssue {
+    pub field: String,
+    pub severity: IssueSeverity,
+    pub message: String,
+}
+
+impl ConfigIssue {
+    fn error(field: &str, message: impl Into<String>) -> Self {
+        Self {
+            field: field.to_string(),
+            severity: IssueSeverity::Error,
+            message: message.into(),
+        }
+    }
+
+    fn warning(field: &str, message: impl Into<String>) -> Self {
+        Self {
+            field: field.to_string(),
+            severity: IssueSeverity::Warning,
+            message: message.into(),
+        }
+    }
+}
+
+/// Checks the whole config and reports every problem found instead of stopping at the first one.
+pub fn validate_config(config: &AppConfig) -> Vec<ConfigIssue> {
+    let mut issues = Vec::new();
+
+    validate_hotkey(config, &mut issues);
+    validate_ranges(config, &mut issues);
+    validate_prefixes(config, &mut issues);
+
+    if !config
+        .search_engine_template
+        .contains(SEARCH_QUERY_PLACEHOLDER)
+    {
+        issues.push(ConfigIssue::error(
+            "search_engine_template",
+            format!("搜索引擎地址缺少 {SEARCH_QUERY_PLACEHOLDER} 占位符"),
+        ));
+    }
+
+    if config.search_engine_name.trim().is_empty() {
+        issues.push(ConfigIssue::error(
+            "search_engine_name",
+            "搜索引擎名称不能为空",
+        ));
+    }
+
+    for (bang, template) in &config.search_bangs {
+        if bang.is_empty() || bang.contains(char::is_whitespace) {
+            issues.push(ConfigIssue::error(
+                "search_bangs",
+                "bang 不能为空且不能包含空格",
+            ));
+        }
+        if !template.contains(SEARCH_QUERY_PLACEHOLDER) {
+            issues.push(ConfigIssue::error(
+                "search_bangs",
+                format!("!{bang} 的搜索地址缺少 {SEARCH_QUERY_PLACEHOLDER} 占位符"),
+            ));
+        }
+    }
+
+    for browser in &config.bookmark_browsers {
+        if !bookmarks::is_supported_browser(browser) {
+            issues.push(ConfigIssue::warning(
+                "bookmark_browsers",
+                format!("不支持的浏览器 {browser}，将被忽略"),
+            ));
+        } else if browser.eq_ignore_ascii_case(bookmarks::EDGE) && !config.enable_edge_bookmarks {
+            issues.push(ConfigIssue::warning(
+                "bookmark_browsers",
+                "Edge 书签未启用，需打开 enable_edge_bookmarks",
+            ));
+        } else if browser.eq_ignore_ascii_case(bookmarks::FIREFOX)
+            && !config.enable_firefox_bookmarks
+        {
+            issues.push(ConfigIssue::warning(
+                "bookmark_browsers",
+                "Firefox 书签未启用，需打开 enable_firefox_bookmarks",
+            ));
+        }
+    }
+
+    if !config.enable_app_results
+        && !config.enable_bookmark_results
+        && !config.enable_history_results
+    {
+        issues.push(ConfigIssue::warning(
+            "enable_app_results",
+            "应用、书签与历史记录结果均已关闭，只会显示网络搜索",
+        ));
+    }
+
+    issues
+}
+
+fn validate_hotkey(config: &AppConfig, issues: &mut Vec<ConfigIssue>) {
+    if let Err(err) = hotkey::validate_hotkey(&config.global_hotkey) {
+        issues.push(ConfigIssue::error("global_hotkey", err));
+    }
+    if let Err(err) = hotkey::check_mode_hotkeys(&config.mode_hotkeys, Some(&config.global_hotkey))
+    {
+        issues.push(ConfigIssue::error("mode_hotkeys", err));
+    }
+    if let Some(modifier) = config.double_tap_modifier.as_deref() {
+        if let Err(err) = double_tap::validate_double_tap_modifier(modifier) {
+            issues.push(ConfigIssue::error("double_tap_modifier", err));
+        }
+    }
+    for binding in &config.mode_hotkeys {
+        let mode = QueryMode::from_option(Some(binding.mode.clone()));
+        if mode == QueryMode::All && !binding.mode.trim().eq_ignore_ascii_case("all") {
+            issues.push(ConfigIssue::warning(
+                "mode_hotkeys",
+                format!(
+                    "未知的模式 {}，{} 将以全部模式打开",
+                    binding.mode, binding.hotkey
+                ),
+            ));
+        }
+    }
+}
+
+fn validate_ranges(config: &AppConfig, issues: &mut Vec<ConfigIssue>) {
+    let advanced = &config.advanced;
+    if advanced.min_query_delay_ms > advanced.max_query_delay_ms {
+        issues.push(ConfigIssue::error(
+            "advanced.min_query_delay_ms",
+            "搜索延迟下限不能大于上限",
+        ));
+    }
+    if advanced.min_result_limit > advanced.max_result_limit {
+        issues.push(ConfigIssue::error(
+            "advanced.min_result_limit",
+            "结果数量下限不能大于上限",
+        ));
+    }
+    if advanced.query_delay_bounds() != (advanced.min_query_delay_ms, advanced.max_query_delay_ms) {
+        issues.push(ConfigIssue::warning(
+            "advanced.max_query_delay_ms",
+            "搜索延迟边界超出允许范围，将被自动调整",
+        ));
+    }
+    if advanced.result_limit_bounds() != (advanced.min_result_limit, advanced.max_result_limit) {
+        issues.push(ConfigIssue::warning(
+            "advanced.max_result_limit",
+            "结果数量边界超出允许范围，将被自动调整",
+        ));
+    }
+
+    let (min_delay, max_delay) = advanced.query_delay_bounds();
+    if config.query_delay_ms != 0 && !(min_delay..=max_delay).contains(&config.query_delay_ms) {
+        issues.push(ConfigIssue::warning(
+            "query_delay_ms",
+            format!("搜索延迟应在 {min_delay}-{max_delay} ms 之间，将被自动调整"),
+        ));
+    }
+
+    let (min_limit, max_limit) = advanced.result_limit_bounds();
+    if !(min_limit..=max_limit).contains(&config.max_results) {
+        issues.push(ConfigIssue::warning(
+            "max_results",
+            format!("最大结果数应在 {min_limit}-{max_limit} 之间，将被自动调整"),
+        ));
+    }
+
+    if !(MIN_WINDOW_OPACITY..=MAX_WINDOW_OPACITY).contains(&config.window_opacity) {
+        issues.push(ConfigIssue::warning(
+            "window_opacity",
+            format!(
+                "窗口不透明度应在 {MIN_WINDOW_OPACITY}-{MAX_WINDOW_OPACITY} 之间，将被自动调整"
+            ),
+        ));
+    }
+
+    if !ICON_SIZES.contains(&config.icon_size) {
+        issues.push(ConfigIssue::error(
+            "icon_size",
+            format!("不支持的图标尺寸 {}，可选 {ICON_SIZES:?}", config.icon_size),
+        ));
+    }
+
+    if !(0.0..=1.0).contains(&config.relative_score_cutoff) {
+        issues.push(ConfigIssue::warning(
+            "relative_score_cutoff",
+            "相对分数阈值应在 0-1 之间，将被自动调整",
+        ));
+    }
+}
+
+fn validate_prefixes(config: &AppConfig, issues: &mut Vec<ConfigIssue>) {
+    let prefixes = [
+        ("prefix_app", "应用模式", config.prefix_app.as_str()),
+        (
+            "prefix_bookmark",
+            "书签模式",
+            config.prefix_bookmark.as_str(),
+        ),
+        ("prefix_search", "搜索模式", config.prefix_search.as_str()),
+        ("prefix_file", "文件模式", config.prefix_file.as_str()),
+        (
+            "prefix_clipboard",
+            "剪贴板模式",
+            config.prefix_clipboard.as_str(),
+        ),
+        ("prefix_run", "运行模式", config.prefix_run.as_str()),
+    ];
+
+    let mut letters: Vec<(&str, char)> = Vec::new();
+    for (field, label, raw) in prefixes {
+        let Some(normalized) = normalize_prefix(raw) else {
+            issues.push(ConfigIssue::error(
+                field,
+                format!("{label}前缀需为单个字母，可选跟随空格或冒号"),
+            ));
+            continue;
+        };
+        let Some(letter) = normalized.chars().next() else {
+            continue;
+        };
+
+        if let Some((other_label, _)) = letters.iter().find(|(_, used)| *used == letter) {
+            issues.push(ConfigIssue::error(
+                field,
+                format!("{label}前缀 {letter} 与{other_label}重复"),
+            ));
+        }
+        letters.push((label, letter));
+    }
+}
+
+/// Fails with every error [`validate_config`] finds, joined into one message; warnings pass.
+pub fn ensure_valid(config: &AppConfig) -> Result<(), String> {
+    let errors: Vec<String> = validate_config(config)
+        .into_iter()
+        .filter(|issue| issue.severity == IssueSeverity::Error)
+        .map(|issue| issue.message)
+        .collect();
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors.join("；"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::AdvancedConfig;
+
+    fn issues_of(config: &AppConfig, severity: IssueSeverity) -> Vec<String> {
+        validate_config(config)
+            .into_iter()
+            .filter(|issue| issue.severity == severity)
+            .map(|issue| issue.field)
+            .collect()
+    }
+
+    fn error_fields(config: &AppConfig) -> Vec<String> {
+        issues_of(config, IssueSeverity::Error)
+    }
+
+    #[test]
+    fn default_config_has_no_errors() {
+        assert!(error_fields(&AppConfig::default()).is_empty());
+    }
+
+    #[test]
+    fn invalid_hotkey_is_an_error() {
+        let config = AppConfig {
+            global_hotkey: "Ctrl+Banana".to_string(),
+            ..AppConfig::default()
+        };
+        assert_eq!(error_fields(&config), ["global_hotkey"]);
+    }
+
+    #[test]
+    fn inverted_bounds_are_errors_and_out_of_range_values_warnings() {
+        let config = AppConfig {
+            max_results: 5,
+            advanced: AdvancedConfig {
+                min_query_delay_ms: 500,
+                max_query_delay_ms: 100,
+                ..AdvancedConfig::default()
+            },
+            ..AppConfig::default()
+        };
+        assert_eq!(error_fields(&config), ["advanced.min_query_delay_ms"]);
+        assert!(issues_of(&config, IssueSeverity::Warning).contains(&"max_results".to_string()));
+    }
+
+    #[test]
+    fn duplicate_prefix_is_reported_on_the_later_mode() {
+        let config = AppConfig {
+            prefix_bookmark: "r".to_string(),
+            ..AppConfig::default()
+        };
+        assert_eq!(error_fields(&config), ["prefix_bookmark"]);
+    }
+
+    #[test]
+    fn duplicate_prefix_is_found_regardless_of_separator() {
+        let config = AppConfig {
+            prefix_search: "B:".to_string(),
+            ..AppConfig::default()
+        };
+        assert_eq!(error_fields(&config), ["prefix_search"]);
+    }
+
+    #[test]
+    fn invalid_prefix_separator_is_an_error() {
+        let config = AppConfig {
+            prefix_file: "F-".to_string(),
+            ..AppConfig::default()
+        };
+        assert_eq!(error_fields(&config), ["prefix_file"]);
+    }
+
+    #[test]
+    fn search_templates_need_the_placeholder() {
+        let mut config = AppConfig {
+            search_engine_template: "https://example.com/search".to_string(),
+            ..AppConfig::default()
+        };
+        config
+            .search_bangs
+            .insert("x".to_string(), "https://example.com/x".to_string());
+        assert_eq!(
+            error_fields(&config),
+            ["search_engine_template", "search_bangs"]
+        );
+    }
+
+    #[test]
+    fn unsupported_icon_size_and_empty_names_are_errors() {
+        let mut config = AppConfig {
+            icon_size: 40,
+            search_engine_name: "  ".to_string(),
+            ..AppConfig::default()
+        };
+        config
+            .search_bangs
+            .insert(String::new(), "https://example.com/?q={query}".to_string());
+        assert_eq!(
+            error_fields(&config),
+            ["icon_size", "search_engine_name", "search_bangs"]
+        );
+    }
+}
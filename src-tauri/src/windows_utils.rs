@@ -17,7 +17,7 @@ use windows::Win32::UI::Input::KeyboardAndMouse::{
 use windows::{
     core::{Error, Interface, Result, PCWSTR},
     Win32::{
-        Foundation::RPC_E_CHANGED_MODE,
+        Foundation::{HANDLE, HGLOBAL, HWND, RPC_E_CHANGED_MODE},
         Graphics::Gdi::{
             CreateCompatibleDC, DeleteDC, DeleteObject, GetDIBits, GetObjectW, BITMAP, BITMAPINFO,
             BITMAPINFOHEADER, BI_RGB, DIB_RGB_COLORS, HDC,
@@ -28,10 +28,22 @@ use windows::{
                 CoCreateInstance, CoInitializeEx, CoUninitialize, IPersistFile,
                 CLSCTX_INPROC_SERVER, COINIT_APARTMENTTHREADED, STGM_READ,
             },
+            DataExchange::{
+                CloseClipboard, EmptyClipboard, GetClipboardData, GetClipboardSequenceNumber,
+                IsClipboardFormatAvailable, OpenClipboard, RegisterClipboardFormatW,
+                SetClipboardData,
+            },
             Environment::ExpandEnvironmentStringsW,
+            Memory::{
+                GlobalAlloc, GlobalFree, GlobalLock, GlobalSize, GlobalUnlock, GMEM_MOVEABLE,
+            },
+            Ole::{CF_DIB, CF_UNICODETEXT},
         },
         UI::{
-            Shell::{ExtractIconExW, IShellLinkW, ShellLink, SLGP_RAWPATH, SLGP_UNCPRIORITY},
+            Shell::{
+                ExtractIconExW, IShellLinkW, SHDefExtractIconW, ShellLink, SLGP_RAWPATH,
+                SLGP_UNCPRIORITY,
+            },
             WindowsAndMessaging::{DestroyIcon, GetIconInfo, HICON, ICONINFO},
         },
     },
@@ -39,6 +51,10 @@ use windows::{
 #[cfg(target_os = "windows")]
 use winreg::{enums::*, RegKey};
 
+/// Edge length of the icons `ExtractIconExW` returns at 100% scaling; larger requested sizes go
+/// through `SHDefExtractIconW`.
+pub(crate) const SYSTEM_ICON_SIZE: u32 = 32;
+
 /// RAII guard for COM initialization on the current thread.
 pub(crate) struct ComGuard {
     initialized: bool,
@@ -265,17 +281,21 @@ pub(crate) fn expand_env_vars(value: &str) -> Option<String> {
     }
 }
 
-/// Extracts a large application icon and returns it as PNG encoded base64.
-pub(crate) fn extract_icon_from_path(path: &str, icon_index: i32) -> Option<String> {
+/// Extracts an application icon and returns it as PNG encoded base64.
+///
+/// `icon_size` above [`SYSTEM_ICON_SIZE`] asks the shell for an icon of that edge length so it
+/// stays sharp on high-DPI displays; when the file has no such image the system-size icon is
+/// used instead. Each size is cached separately.
+pub(crate) fn extract_icon_from_path(
+    path: &str,
+    icon_index: i32,
+    icon_size: u32,
+) -> Option<String> {
     if path.is_empty() {
         return None;
     }
 
-    let resolved = if path.contains('%') {
-        expand_env_vars(path).unwrap_or_else(|| path.to_string())
-    } else {
-        path.to_string()
-    };
+    let resolved = resolve_icon_path(path);
 
     if !Path::new(&resolved).exists() {
         return None;
@@ -284,22 +304,36 @@ pub(crate) fn extract_icon_from_path(path: &str, icon_index: i32) -> Option<Stri
     let wide_path = os_str_to_wide(OsStr::new(&resolved));
     let mut icon = HICON::default();
     let icon_index = icon_index.max(0);
-    let cache_key = icon_cache_key(&resolved, icon_index);
+    let cache_key = icon_cache_key(&resolved, icon_index, icon_size);
 
     if let Some(encoded) = load_cached_icon(&cache_key) {
         return Some(encoded);
     }
 
     unsafe {
-        let extracted = ExtractIconExW(
-            PCWSTR(wide_path.as_ptr()),
-            icon_index,
-            Some(&mut icon),
-            None,
-            1,
-        );
-        if extracted == 0 || icon.is_invalid() {
-            return None;
+        // 只请求大图标，高位的小图标尺寸置 0
+        let sized = icon_size > SYSTEM_ICON_SIZE
+            && SHDefExtractIconW(
+                PCWSTR(wide_path.as_ptr()),
+                icon_index,
+                0,
+                Some(&mut icon),
+                None,
+                icon_size & 0xFFFF,
+            )
+            .is_ok()
+            && !icon.is_invalid();
+        if !sized {
+            let extracted = ExtractIconExW(
+                PCWSTR(wide_path.as_ptr()),
+                icon_index,
+                Some(&mut icon),
+                None,
+                1,
+            );
+            if extracted == 0 || icon.is_invalid() {
+                return None;
+            }
         }
 
         let encoded = icon_to_base64(icon);
@@ -311,10 +345,35 @@ pub(crate) fn extract_icon_from_path(path: &str, icon_index: i32) -> Option<Stri
     }
 }
 
-fn icon_cache_key(path: &str, icon_index: i32) -> String {
+/// Deletes the cached icon of `path`, so the next [`extract_icon_from_path`] reads the file
+/// again.
+pub(crate) fn invalidate_cached_icon(path: &str, icon_index: i32, icon_size: u32) {
+    let key = icon_cache_key(&resolve_icon_path(path), icon_index.max(0), icon_size);
+    if let Some(cache_file) = cache_file_path(&key) {
+        if let Err(err) = fs::remove_file(&cache_file) {
+            if err.kind() != std::io::ErrorKind::NotFound {
+                warn!("failed to delete cached icon {cache_file:?}: {err}");
+            }
+        }
+    }
+}
+
+fn resolve_icon_path(path: &str) -> String {
+    if path.contains('%') {
+        expand_env_vars(path).unwrap_or_else(|| path.to_string())
+    } else {
+        path.to_string()
+    }
+}
+
+fn icon_cache_key(path: &str, icon_index: i32, icon_size: u32) -> String {
     let mut hasher = Sha1::new();
     hasher.update(path.to_lowercase().as_bytes());
     hasher.update(icon_index.to_le_bytes());
+    // 系统尺寸沿用原有的缓存键，已缓存的图标仍然有效
+    if icon_size > SYSTEM_ICON_SIZE {
+        hasher.update(icon_size.to_le_bytes());
+    }
     let digest = hasher.finalize();
     let mut hex = String::with_capacity(digest.len() * 2);
     const LUT: &[u8; 16] = b"0123456789abcdef";
@@ -374,9 +433,14 @@ fn decode_utf16(data: &[u8], little_endian: bool) -> String {
     String::from_utf16_lossy(&units)
 }
 
-fn icon_cache_dir() -> Option<PathBuf> {
+/// Per-user directory for caches that can be rebuilt at any time, under `LOCALAPPDATA`.
+pub(crate) fn local_data_dir() -> Option<PathBuf> {
     let base = env::var("LOCALAPPDATA").ok()?;
-    Some(Path::new(&base).join("RustLauncher").join("icons"))
+    Some(Path::new(&base).join("RustLauncher"))
+}
+
+pub(crate) fn icon_cache_dir() -> Option<PathBuf> {
+    Some(local_data_dir()?.join("icons"))
 }
 
 unsafe fn icon_to_base64(icon: HICON) -> Option<String> {
@@ -506,6 +570,134 @@ pub(crate) fn switch_to_english_input_method() {
     }
 }
 
+/// Replaces the clipboard contents with `text` as `CF_UNICODETEXT`.
+pub(crate) fn copy_text_to_clipboard(text: &str) -> std::result::Result<(), String> {
+    let wide: Vec<u16> = text.encode_utf16().chain(std::iter::once(0)).collect();
+
+    unsafe {
+        OpenClipboard(HWND::default()).map_err(|err| format!("无法打开剪贴板: {err}"))?;
+        let result = write_clipboard_text(&wide);
+        let _ = CloseClipboard();
+        result.map_err(|err| format!("写入剪贴板失败: {err}"))
+    }
+}
+
+unsafe fn write_clipboard_text(wide: &[u16]) -> Result<()> {
+    EmptyClipboard()?;
+    let bytes: Vec<u8> = wide.iter().flat_map(|unit| unit.to_le_bytes()).collect();
+    set_clipboard_bytes(u32::from(CF_UNICODETEXT.0), &bytes)
+}
+
+/// Counter the system bumps on every clipboard change; cheap enough to poll.
+pub(crate) fn clipboard_sequence_number() -> u32 {
+    unsafe { GetClipboardSequenceNumber() }
+}
+
+/// Reads the clipboard as Unicode text. `None` when it holds no text or the copying program
+/// asked clipboard monitors to skip it, as password managers do.
+pub(crate) fn read_clipboard_text() -> Option<String> {
+    unsafe {
+        IsClipboardFormatAvailable(u32::from(CF_UNICODETEXT.0)).ok()?;
+        let excluded = RegisterClipboardFormatW(windows::core::w!(
+            "ExcludeClipboardContentFromMonitorProcessing"
+        ));
+        if excluded != 0 && IsClipboardFormatAvailable(excluded).is_ok() {
+            return None;
+        }
+
+        OpenClipboard(HWND::default()).ok()?;
+        let text = read_clipboard_unicode();
+        let _ = CloseClipboard();
+        text
+    }
+}
+
+unsafe fn read_clipboard_unicode() -> Option<String> {
+    let handle = GetClipboardData(u32::from(CF_UNICODETEXT.0)).ok()?;
+    let global = HGLOBAL(handle.0);
+    let locked = GlobalLock(global) as *const u16;
+    if locked.is_null() {
+        return None;
+    }
+    // 数据块可能比字符串长，以第一个 NUL 作为结尾
+    let capacity = GlobalSize(global) / 2;
+    let units = std::slice::from_raw_parts(locked, capacity);
+    let len = units.iter().position(|&unit| unit == 0).unwrap_or(capacity);
+    let text = String::from_utf16_lossy(&units[..len]);
+    let _ = GlobalUnlock(global);
+    Some(text)
+}
+
+/// Replaces the clipboard contents with a base64 encoded PNG, both as `CF_DIB` for classic
+/// programs and as the registered `PNG` format, which keeps transparency where supported.
+pub(crate) fn copy_png_to_clipboard(png_b64: &str) -> std::result::Result<(), String> {
+    let png = BASE64
+        .decode(png_b64.trim())
+        .map_err(|err| format!("图标数据无效: {err}"))?;
+    let image = image::load_from_memory_with_format(&png, image::ImageFormat::Png)
+        .map_err(|err| format!("无法解码图标: {err}"))?
+        .to_rgba8();
+    let dib = rgba_to_dib(image.width(), image.height(), image.as_raw());
+
+    unsafe {
+        OpenClipboard(HWND::default()).map_err(|err| format!("无法打开剪贴板: {err}"))?;
+        let result = EmptyClipboard()
+            .and_then(|_| set_clipboard_bytes(u32::from(CF_DIB.0), &dib))
+            .map(|_| {
+                let png_format = RegisterClipboardFormatW(windows::core::w!("PNG"));
+                if png_format != 0 {
+                    // PNG 格式只是附加项，写入失败时仍保留 DIB
+                    let _ = set_clipboard_bytes(png_format, &png);
+                }
+            });
+        let _ = CloseClipboard();
+        result.map_err(|err| format!("写入剪贴板失败: {err}"))
+    }
+}
+
+/// Packs RGBA pixels into a `CF_DIB` block: a `BITMAPINFOHEADER` followed by bottom-up BGRA rows.
+fn rgba_to_dib(width: u32, height: u32, rgba: &[u8]) -> Vec<u8> {
+    const HEADER_SIZE: u32 = 40;
+    let image_size = width * height * 4;
+    let mut dib = Vec::with_capacity((HEADER_SIZE + image_size) as usize);
+    dib.extend_from_slice(&HEADER_SIZE.to_le_bytes());
+    dib.extend_from_slice(&(width as i32).to_le_bytes());
+    // 正的高度表示自下而上的行序
+    dib.extend_from_slice(&(height as i32).to_le_bytes());
+    dib.extend_from_slice(&1u16.to_le_bytes());
+    dib.extend_from_slice(&32u16.to_le_bytes());
+    dib.extend_from_slice(&BI_RGB.0.to_le_bytes());
+    dib.extend_from_slice(&image_size.to_le_bytes());
+    dib.extend_from_slice(&[0u8; 16]);
+
+    let row_len = (width * 4) as usize;
+    for row in rgba.chunks_exact(row_len).rev() {
+        for pixel in row.chunks_exact(4) {
+            dib.extend_from_slice(&[pixel[2], pixel[1], pixel[0], pixel[3]]);
+        }
+    }
+    dib
+}
+
+/// Copies `bytes` into movable global memory and hands it to the open clipboard as `format`.
+unsafe fn set_clipboard_bytes(format: u32, bytes: &[u8]) -> Result<()> {
+    let handle = GlobalAlloc(GMEM_MOVEABLE, bytes.len())?;
+    let locked = GlobalLock(handle) as *mut u8;
+    if locked.is_null() {
+        let _ = GlobalFree(handle);
+        return Err(Error::from_win32());
+    }
+    ptr::copy_nonoverlapping(bytes.as_ptr(), locked, bytes.len());
+    let _ = GlobalUnlock(handle);
+
+    // 设置成功后内存归系统所有，失败时需自行释放
+    if let Err(err) = SetClipboardData(format, HANDLE(handle.0)) {
+        let _ = GlobalFree(handle);
+        return Err(err);
+    }
+    Ok(())
+}
+
 /// Enables or disables Windows auto-start via the "Run" registry key.
 pub(crate) fn configure_launch_on_startup(enable: bool) -> std::result::Result<(), String> {
     #[cfg(target_os = "windows")]
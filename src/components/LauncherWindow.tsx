@@ -19,8 +19,11 @@ import {
   detectModeFromInput,
 } from "../constants/modes";
 import {
+  ACTION_FAILED_EVENT,
+  CONFIG_UNAVAILABLE_EVENT,
   FOCUS_INPUT_EVENT,
   HIDE_WINDOW_EVENT,
+  NEW_APPS_DETECTED_EVENT,
   OPEN_SETTINGS_EVENT,
   SETTINGS_UPDATED_EVENT,
 } from "../constants/events";
@@ -28,7 +31,24 @@ import {
   initialLauncherState,
   launcherReducer,
 } from "../state/launcherReducer";
-import type { AppSettings, SearchResult } from "../types";
+import type {
+  ActionFailedPayload,
+  AppError,
+  AppPaths,
+  AppSettings,
+  EnterOnRunningApp,
+  ExecuteOutcome,
+  FocusInputPayload,
+  GroupedSearchResponse,
+  HideWindowPayload,
+  IndexDiff,
+  ModeId,
+  ResultAction,
+  ResultGroupKind,
+  SearchResponse,
+  SearchResult,
+} from "../types";
+import { describeError } from "../utils/errors";
 import { applyWindowOpacityVariable } from "../utils/theme";
 
 const SETTINGS_WINDOW_LABEL = "settings";
@@ -61,8 +81,11 @@ export const LauncherWindow = () => {
   const searchInputRef = useRef<HTMLInputElement | null>(null);
   const toastTimerRef = useRef<ReturnType<typeof setTimeout> | null>(null);
   const latestQueryRef = useRef("");
+  const resultGenerationRef = useRef(0);
   const currentWindow = useMemo(() => getCurrentWindow(), []);
-  const queryDelayMs = state.settings?.query_delay_ms ?? 120;
+  const clearQueryOnHide = state.settings?.clear_query_on_hide ?? true;
+  const escapeBehavior = state.settings?.escape_behavior ?? "always_hide";
+  const hasInput = state.inputValue.length > 0;
   const modeConfigs = useMemo(
     () => buildModeConfigsFromSettings(state.settings),
     [state.settings],
@@ -211,9 +234,23 @@ export const LauncherWindow = () => {
 
     const register = async () => {
       try {
-        unlisten = await listen(FOCUS_INPUT_EVENT, () => {
-          focusSearchInput();
-        });
+        unlisten = await listen<FocusInputPayload>(
+          FOCUS_INPUT_EVENT,
+          (event) => {
+            const modeId = event.payload?.mode;
+            if (modeId) {
+              // 模式快捷键唤起：以该模式的前缀替换当前输入
+              const mode = modeConfigs[modeId as ModeId];
+              if (mode?.prefix) {
+                const delimited = /[\s:]$/.test(mode.prefix);
+                applyInputValue(delimited ? mode.prefix : `${mode.prefix} `);
+              } else if (mode) {
+                applyInputValue("");
+              }
+            }
+            focusSearchInput();
+          },
+        );
       } catch (error) {
         console.error("Failed to listen focus input event", error);
       }
@@ -226,7 +263,7 @@ export const LauncherWindow = () => {
         unlisten();
       }
     };
-  }, [focusSearchInput]);
+  }, [applyInputValue, focusSearchInput, modeConfigs]);
 
   useEffect(() => {
     let unlisten: UnlistenFn | undefined;
@@ -257,6 +294,93 @@ export const LauncherWindow = () => {
     };
   }, [loadSettings]);
 
+  // 配置目录不可用时提醒用户设置不会被保存；启动时的事件可能早于监听，因此同时主动查询一次
+  useEffect(() => {
+    let unlisten: UnlistenFn | undefined;
+    const warn = (reason: string | null) => {
+      showToast(`设置无法保存：${reason ?? "配置目录不可用"}`);
+    };
+
+    const register = async () => {
+      try {
+        unlisten = await listen<AppError>(CONFIG_UNAVAILABLE_EVENT, (event) => {
+          warn(event.payload.message);
+        });
+        const paths = await invoke<AppPaths>("get_paths");
+        if (!paths.config_persistent) {
+          warn(paths.config_error);
+        }
+      } catch (error) {
+        console.error("Failed to check config persistence", error);
+      }
+    };
+
+    void register();
+
+    return () => {
+      if (unlisten) {
+        unlisten();
+      }
+    };
+  }, [showToast]);
+
+  // 执行失败时窗口保持显示，在此统一提示原因
+  useEffect(() => {
+    let unlisten: UnlistenFn | undefined;
+
+    const register = async () => {
+      try {
+        unlisten = await listen<ActionFailedPayload>(
+          ACTION_FAILED_EVENT,
+          (event) => {
+            showToast(
+              describeError(event.payload.error, "执行失败，请检查目标是否存在"),
+            );
+          },
+        );
+      } catch (error) {
+        console.error("Failed to listen for action failures", error);
+      }
+    };
+
+    void register();
+
+    return () => {
+      if (unlisten) {
+        unlisten();
+      }
+    };
+  }, [showToast]);
+
+  // 重建索引后提示新出现的应用，超过三个时只列出前三个
+  useEffect(() => {
+    let unlisten: UnlistenFn | undefined;
+
+    const register = async () => {
+      try {
+        unlisten = await listen<IndexDiff>(NEW_APPS_DETECTED_EVENT, (event) => {
+          const names = event.payload.added.map((app) => app.name);
+          if (names.length === 0) {
+            return;
+          }
+          const shown = names.slice(0, 3).join("、");
+          const more = names.length > 3 ? ` 等 ${names.length} 个应用` : "";
+          showToast(`现在可以搜索：${shown}${more}`);
+        });
+      } catch (error) {
+        console.error("Failed to listen for new apps", error);
+      }
+    };
+
+    void register();
+
+    return () => {
+      if (unlisten) {
+        unlisten();
+      }
+    };
+  }, [showToast]);
+
   useEffect(() => {
     void invoke("trigger_reindex").catch((error: unknown) => {
       console.error("Failed to trigger reindex", error);
@@ -287,11 +411,28 @@ export const LauncherWindow = () => {
     };
   }, [openSettingsWindow, showToast]);
 
+  useEffect(() => {
+    // 模式切换时让后端按该模式的配置决定是否切换为英文输入法
+    if (state.activeMode.id === modeConfigs.all.id) {
+      return;
+    }
+    void invoke("sync_input_method", { mode: state.activeMode.id }).catch(
+      (error: unknown) => {
+        console.error("Failed to sync input method", error);
+      },
+    );
+  }, [modeConfigs.all.id, state.activeMode.id]);
+
   useEffect(() => {
     const handleEsc = (event: KeyboardEvent) => {
       if (event.key === "Escape") {
-        // 按下 Esc 时也走统一的“重置 + 隐藏”事件通路
-        void invoke("emit", { event: HIDE_WINDOW_EVENT, payload: null });
+        // clear_then_hide：输入非空时第一次 Esc 只清空查询
+        if (escapeBehavior === "clear_then_hide" && hasInput) {
+          resetSearchState();
+          return;
+        }
+        // 其余情况走统一的“重置 + 隐藏”事件通路
+        void invoke("request_hide");
       }
     };
 
@@ -301,10 +442,17 @@ export const LauncherWindow = () => {
 
     const register = async () => {
       try {
-        unlisten = await listen(HIDE_WINDOW_EVENT, () => {
-          resetSearchState();
-          void currentWindow.hide();
-        });
+        unlisten = await listen<HideWindowPayload>(
+          HIDE_WINDOW_EVENT,
+          (event) => {
+            // payload 为空（例如 Esc 触发）时回退到本地设置
+            const clearQuery = event.payload?.clear_query ?? clearQueryOnHide;
+            if (clearQuery) {
+              resetSearchState();
+            }
+            void currentWindow.hide();
+          },
+        );
       } catch (error) {
         console.error("Failed to listen hide window event", error);
         showToast("窗口事件监听失败");
@@ -319,17 +467,28 @@ export const LauncherWindow = () => {
         unlisten();
       }
     };
-  }, [currentWindow, resetSearchState, showToast]);
+  }, [
+    clearQueryOnHide,
+    currentWindow,
+    escapeBehavior,
+    hasInput,
+    resetSearchState,
+    showToast,
+  ]);
 
   useEffect(() => {
-    if (state.isComposing || state.isModePrefixOnly) {
+    // 剪贴板与运行模式只输入前缀时也查询，用于列出全部历史
+    const listsHistory =
+      state.activeMode.id === modeConfigs.clipboard.id ||
+      state.activeMode.id === modeConfigs.run.id;
+    if (state.isComposing || (state.isModePrefixOnly && !listsHistory)) {
       return;
     }
 
     latestQueryRef.current = state.searchQuery;
     const trimmed = state.searchQuery.trim();
 
-    if (!trimmed) {
+    if (!trimmed && !listsHistory) {
       dispatch({ type: "SET_RESULTS", payload: [] });
       dispatch({ type: "SET_SELECTED_INDEX", payload: 0 });
       return;
@@ -340,55 +499,237 @@ export const LauncherWindow = () => {
       payload.mode = state.activeMode.id;
     }
 
-    const timeoutId = window.setTimeout(async () => {
+    // 以内容搜索前缀开头时交给 ripgrep，后端会取消上一次未完成的搜索
+    const contentSearch = state.settings?.content_search;
+    const contentPrefix = contentSearch?.prefix.trim().toLowerCase() ?? "";
+    const isContentQuery =
+      !!contentSearch?.enabled &&
+      state.activeMode.id === modeConfigs.all.id &&
+      contentPrefix.length > 0 &&
+      trimmed.toLowerCase().startsWith(contentPrefix) &&
+      /^\s/.test(trimmed.slice(contentPrefix.length));
+
+    const isFileQuery = state.activeMode.id === modeConfigs.file.id;
+
+    // 防抖由后端按 query_delay_ms 处理，被更新查询取代的请求不会带来新的结果集
+    const runQuery = async () => {
       try {
-        const newResults = await invoke<SearchResult[]>(
-          "submit_query",
-          payload,
-        );
-        if (latestQueryRef.current === state.searchQuery) {
-          dispatch({ type: "SET_RESULTS", payload: newResults });
+        let groups: Record<string, ResultGroupKind> = {};
+        let response: SearchResponse;
+        if (isContentQuery) {
+          response = await invoke<SearchResponse>("submit_content_query", {
+            query: trimmed,
+          });
+        } else if (isFileQuery) {
+          response = await invoke<SearchResponse>("submit_file_query", {
+            query: trimmed,
+          });
+        } else {
+          // 分组结果已按显示顺序排列，展开后快捷键编号与列表一致
+          const grouped = await invoke<GroupedSearchResponse>(
+            "submit_query_grouped",
+            payload,
+          );
+          response = {
+            results: grouped.groups.flatMap((group) => group.results),
+            stale_index: grouped.stale_index,
+            generation: grouped.generation,
+          };
+          groups = Object.fromEntries(
+            grouped.groups.flatMap((group) =>
+              group.results.map((result) => [result.id, group.kind]),
+            ),
+          );
+        }
+        // 请求可能交错返回，只接受比当前更新的结果集
+        if (
+          latestQueryRef.current === state.searchQuery &&
+          response.generation > resultGenerationRef.current
+        ) {
+          resultGenerationRef.current = response.generation;
+          dispatch({ type: "SET_RESULTS", payload: response.results });
+          dispatch({ type: "SET_RESULT_GROUPS", payload: groups });
+          dispatch({ type: "SET_INDEX_STALE", payload: response.stale_index });
           dispatch({ type: "SET_SELECTED_INDEX", payload: 0 });
         }
       } catch (error) {
         console.error("Failed to query", error);
         showToast("搜索失败，请稍后重试");
       }
-    }, queryDelayMs);
-
-    return () => {
-      window.clearTimeout(timeoutId);
     };
+    void runQuery();
   }, [
     state.searchQuery,
     state.activeMode,
     state.isComposing,
     state.isModePrefixOnly,
+    state.settings,
     showToast,
-    queryDelayMs,
   ]);
 
+  // execute_action 与 execute_quick 的返回值相同：需要确认时带上用户的选择再次调用
+  const runExecution = useCallback(
+    async (
+      command: "execute_action" | "execute_quick",
+      args: Record<string, unknown>,
+      keepOpen: boolean,
+    ) => {
+      let outcome = await invoke<ExecuteOutcome>(command, {
+        ...args,
+        keepOpen,
+      });
+      if (outcome.status === "needs_approval") {
+        // 一次打开大量链接前先确认，取消时保持窗口不变
+        if (!window.confirm(outcome.prompt)) {
+          return;
+        }
+        outcome = await invoke<ExecuteOutcome>(command, {
+          ...args,
+          keepOpen,
+          approved: true,
+        });
+      }
+      if (outcome.status === "needs_confirmation") {
+        // 应用已在运行：确定切换到已有窗口，取消则启动新实例
+        const runningChoice: EnterOnRunningApp = window.confirm(outcome.prompt)
+          ? "focus_existing"
+          : "launch_new";
+        outcome = await invoke<ExecuteOutcome>(command, {
+          ...args,
+          keepOpen,
+          runningChoice,
+        });
+      }
+      if (keepOpen) {
+        // Shift+Enter：保持窗口和结果列表，方便连续启动
+        return;
+      }
+      // 执行完成后，通过统一的隐藏事件让窗口隐藏并重置搜索
+      const hideEvent = new CustomEvent(HIDE_WINDOW_EVENT);
+      window.dispatchEvent(hideEvent);
+    },
+    [],
+  );
+
+  const executeById = useCallback(
+    async (id: string, keepOpen = false) => {
+      try {
+        await runExecution("execute_action", { id }, keepOpen);
+      } catch (error) {
+        console.error("Failed to execute action", error);
+        showToast(describeError(error, "执行失败，请检查目标是否存在"));
+      }
+    },
+    [runExecution, showToast],
+  );
+
   const executeSelected = useCallback(
+    async (selected?: SearchResult, keepOpen = false) => {
+      if (!selected) {
+        return;
+      }
+      await executeById(selected.id, keepOpen);
+    },
+    [executeById],
+  );
+
+  // Ctrl+Shift+Enter：以管理员身份运行选中项，不支持的结果类型由后端拒绝
+  const executeElevated = useCallback(
     async (selected?: SearchResult) => {
       if (!selected) {
         return;
       }
+      try {
+        await invoke("execute_action_elevated", { id: selected.id });
+        const hideEvent = new CustomEvent(HIDE_WINDOW_EVENT);
+        window.dispatchEvent(hideEvent);
+      } catch (error) {
+        console.error("Failed to execute elevated action", error);
+        showToast(describeError(error, "无法以管理员身份运行"));
+      }
+    },
+    [showToast],
+  );
 
+  // Alt+1..9：按编号执行，后端校验编号是否属于当前结果集；加按 Shift 时保持窗口
+  const executeQuick = useCallback(
+    async (quickKey: number, keepOpen = false) => {
       try {
-        await invoke("execute_action", {
-          id: selected.id,
-        });
-        // 执行完成后，通过统一的隐藏事件让窗口隐藏并重置搜索
+        await runExecution(
+          "execute_quick",
+          { n: quickKey, generation: resultGenerationRef.current },
+          keepOpen,
+        );
+      } catch (error) {
+        console.error("Failed to execute quick key", error);
+        showToast(describeError(error, "执行失败，请检查目标是否存在"));
+      }
+    },
+    [runExecution, showToast],
+  );
+
+  const handleSecondaryAction = useCallback(
+    (action: ResultAction) => {
+      // 会结束进程等破坏性操作需要用户再次确认
+      if (
+        action.requires_confirmation &&
+        !window.confirm(`确定要执行“${action.title}”吗？`)
+      ) {
+        return;
+      }
+      void executeById(action.id);
+    },
+    [executeById],
+  );
+
+  // 没有任何结果时按回车，交给后端按 empty_enter_action 决定打开网址或搜索
+  const executeFallback = useCallback(
+    async (query: string) => {
+      if (!query.trim()) {
+        return;
+      }
+      try {
+        const executed = await invoke<boolean>("execute_fallback", { query });
+        if (executed) {
+          const hideEvent = new CustomEvent(HIDE_WINDOW_EVENT);
+          window.dispatchEvent(hideEvent);
+        }
+      } catch (error) {
+        console.error("Failed to execute fallback", error);
+        showToast("执行失败，请稍后重试");
+      }
+    },
+    [showToast],
+  );
+
+  const executeMarked = useCallback(
+    async (ids: string[], keepOpen = false) => {
+      try {
+        const outcomes = await invoke<Array<{ Ok?: null; Err?: AppError }>>(
+          "execute_actions",
+          { ids, keepOpen },
+        );
+        const failed = outcomes.filter((outcome) => "Err" in outcome).length;
+        if (failed > 0) {
+          showToast(`${failed} 项执行失败，请检查目标是否存在`);
+        }
+        if (keepOpen || failed === outcomes.length) {
+          dispatch({ type: "CLEAR_MARKED" });
+          return;
+        }
         const hideEvent = new CustomEvent(HIDE_WINDOW_EVENT);
         window.dispatchEvent(hideEvent);
       } catch (error) {
-        console.error("Failed to execute action", error);
+        console.error("Failed to execute actions", error);
         showToast("执行失败，请检查目标是否存在");
       }
     },
     [showToast],
   );
 
+  const navWrap = state.settings?.nav_wrap ?? false;
+  const pageJumpSize = Math.max(1, state.settings?.page_jump_size ?? 5);
+
   const stepSelection = useCallback(
     (direction: 1 | -1) => {
       const resultsCount = state.results.length;
@@ -396,20 +737,77 @@ export const LauncherWindow = () => {
         dispatch({ type: "SET_SELECTED_INDEX", payload: 0 });
         return;
       }
-      const nextIndex = state.selectedIndex + direction;
+      let nextIndex = state.selectedIndex + direction;
       if (nextIndex < 0 || nextIndex >= resultsCount) {
-        return;
+        if (!navWrap) {
+          return;
+        }
+        nextIndex = (nextIndex + resultsCount) % resultsCount;
       }
       dispatch({
         type: "SET_SELECTED_INDEX",
         payload: nextIndex,
       });
     },
-    [state.results.length, state.selectedIndex],
+    [navWrap, state.results.length, state.selectedIndex],
   );
 
+  // 翻页不回绕，直接停在首尾
+  const jumpSelection = useCallback(
+    (direction: 1 | -1) => {
+      const resultsCount = state.results.length;
+      if (resultsCount === 0) {
+        return;
+      }
+      const nextIndex = Math.min(
+        resultsCount - 1,
+        Math.max(0, state.selectedIndex + direction * pageJumpSize),
+      );
+      dispatch({ type: "SET_SELECTED_INDEX", payload: nextIndex });
+    },
+    [pageJumpSize, state.results.length, state.selectedIndex],
+  );
+
+  // Ctrl+Delete：在本次运行期间隐藏当前结果，重启后恢复
+  const hideSelectedForSession = useCallback(async () => {
+    const selected = state.results[state.selectedIndex];
+    if (!selected) {
+      return;
+    }
+    try {
+      await invoke("hide_result_for_session", { id: selected.id });
+      const remaining = state.results.filter((item) => item.id !== selected.id);
+      dispatch({ type: "SET_RESULTS", payload: remaining });
+      dispatch({
+        type: "SET_SELECTED_INDEX",
+        payload: Math.min(state.selectedIndex, Math.max(remaining.length - 1, 0)),
+      });
+      showToast("已在本次会话中隐藏该结果");
+    } catch (error) {
+      console.error("Failed to hide result", error);
+      showToast("隐藏失败");
+    }
+  }, [showToast, state.results, state.selectedIndex]);
+
   const handleKeyDown = useCallback(
     (event: InputKeyboardEvent<HTMLInputElement>) => {
+      if (event.ctrlKey && event.key === "Delete") {
+        event.preventDefault();
+        void hideSelectedForSession();
+        return;
+      }
+      // 按物理按键判断，Shift 按下时 event.key 会变成 ! @ # 等符号
+      const digit = event.altKey
+        ? /^(?:Digit|Numpad)([1-9])$/.exec(event.code)
+        : null;
+      if (digit) {
+        const quickKey = Number(digit[1]);
+        if (state.results.some((item) => item.quick_key === quickKey)) {
+          event.preventDefault();
+          void executeQuick(quickKey, event.shiftKey);
+        }
+        return;
+      }
       if ((event.ctrlKey || event.metaKey) && event.key === ",") {
         event.preventDefault();
         void openSettingsWindow();
@@ -427,15 +825,43 @@ export const LauncherWindow = () => {
         return;
       }
 
+      if (event.key === "PageDown" || event.key === "PageUp") {
+        event.preventDefault();
+        jumpSelection(event.key === "PageDown" ? 1 : -1);
+        return;
+      }
+
       if (event.key === "Enter") {
         event.preventDefault();
-        void executeSelected(state.results[state.selectedIndex]);
+        if (event.ctrlKey && event.shiftKey) {
+          void executeElevated(state.results[state.selectedIndex]);
+          return;
+        }
+        if (state.markedIds.length > 0) {
+          void executeMarked(state.markedIds, event.shiftKey);
+          return;
+        }
+        if (state.results.length === 0) {
+          void executeFallback(state.searchQuery);
+          return;
+        }
+        void executeSelected(
+          state.results[state.selectedIndex],
+          event.shiftKey,
+        );
       }
     },
     [
+      executeElevated,
+      executeFallback,
+      executeMarked,
       executeSelected,
+      hideSelectedForSession,
+      jumpSelection,
       openSettingsWindow,
+      state.markedIds,
       state.results,
+      state.searchQuery,
       state.selectedIndex,
       stepSelection,
     ],
@@ -448,10 +874,26 @@ export const LauncherWindow = () => {
         return "应用";
       case "bookmark":
         return "书签";
+      case "history":
+        return "历史";
       case "url":
         return "网址";
+      case "instant":
+        return "答案";
       case "search":
         return "搜索";
+      case "file":
+        return "文件";
+      case "folder":
+        return "文件夹";
+      case "clipboard":
+        return "剪贴板";
+      case "run":
+        return "运行";
+      case "custom":
+        return "自定义";
+      case "open":
+        return "路径";
       default:
         return "其他";
     }
@@ -461,6 +903,10 @@ export const LauncherWindow = () => {
     dispatch({ type: "SET_SELECTED_INDEX", payload: index });
   }, []);
 
+  const handleResultToggleMark = useCallback((item: SearchResult) => {
+    dispatch({ type: "TOGGLE_MARKED", payload: item.id });
+  }, []);
+
   const handleResultActivate = useCallback(
     (item: SearchResult) => {
       void executeSelected(item);
@@ -507,11 +953,18 @@ export const LauncherWindow = () => {
       {isIdle ? null : (
         <section className="content-area content-area--single">
           <div className="results-panel">
+            {state.isIndexStale ? (
+              <div className="index-stale-hint">索引更新中…</div>
+            ) : null}
             {hasMatches ? (
               <ResultList
                 results={state.results}
+                resultGroups={state.resultGroups}
                 selectedIndex={state.selectedIndex}
+                markedIds={state.markedIds}
                 onSelect={handleResultSelect}
+                onToggleMark={handleResultToggleMark}
+                onSecondaryAction={handleSecondaryAction}
                 onActivate={handleResultActivate}
                 resolveResultTag={resolveResultTag}
               />
@@ -1,19 +1,37 @@
-import { useEffect, useRef } from "react";
-import type { SearchResult } from "../types";
+import { Fragment, useEffect, useRef } from "react";
+import type { ResultAction, ResultGroupKind, SearchResult } from "../types";
 import { pickFallbackIcon } from "../utils/fallbackIcon";
 
+const GROUP_LABELS: Record<ResultGroupKind, string> = {
+  applications: "应用",
+  bookmarks: "书签与历史",
+  web: "网络搜索",
+  url: "网址",
+  calculator: "计算",
+  other: "其他",
+};
+
 export type ResultListProps = {
   results: SearchResult[];
+  // 结果 id 到类别；类别变化处显示分组标题，没有类别的结果不显示
+  resultGroups: Record<string, ResultGroupKind>;
   selectedIndex: number;
+  markedIds: string[];
   onSelect: (index: number) => void;
+  onToggleMark: (item: SearchResult) => void;
+  onSecondaryAction: (action: ResultAction) => void;
   onActivate: (item: SearchResult) => void;
   resolveResultTag: (item: SearchResult) => string;
 };
 
 export const ResultList = ({
   results,
+  resultGroups,
   selectedIndex,
+  markedIds,
   onSelect,
+  onToggleMark,
+  onSecondaryAction,
   onActivate,
   resolveResultTag,
 }: ResultListProps) => {
@@ -45,53 +63,105 @@ export const ResultList = ({
     >
       {results.map((item, index) => {
         const isActive = index === selectedIndex;
+        const isMarked = markedIds.includes(item.id);
+        const itemClassName = [
+          "result-item",
+          isActive ? "active" : null,
+          isMarked ? "marked" : null,
+        ]
+          .filter(Boolean)
+          .join(" ");
         const visual = pickFallbackIcon(item);
+        const group = resultGroups[item.id];
+        const startsGroup =
+          group !== undefined &&
+          (index === 0 || resultGroups[results[index - 1].id] !== group);
         return (
-          <div
-            key={item.id}
-            className={isActive ? "result-item active" : "result-item"}
-            role="option"
-            aria-selected={isActive}
-            data-result-id={item.id}
-          >
-            <button
-              type="button"
-              className="result-button"
-              onClick={() => onSelect(index)}
-              onDoubleClick={() => onActivate(item)}
-              onMouseEnter={() => onSelect(index)}
+          <Fragment key={item.id}>
+            {startsGroup ? (
+              <div className="result-group-header" role="presentation">
+                {GROUP_LABELS[group]}
+              </div>
+            ) : null}
+            <div
+              className={itemClassName}
+              role="option"
+              aria-selected={isActive}
+              data-result-id={item.id}
             >
-              {item.icon ? (
-                <img
-                  src={`data:image/png;base64,${item.icon}`}
-                  className="result-icon"
-                  alt="result icon"
-                />
-              ) : (
-                <div
-                  className="result-icon placeholder"
-                  style={{
-                    background: visual.background,
-                    color: visual.color,
-                  }}
-                >
-                  {visual.glyph}
+              <button
+                type="button"
+                className="result-button"
+                onClick={(event) => {
+                  if (event.ctrlKey || event.metaKey || event.shiftKey) {
+                    onToggleMark(item);
+                  }
+                  onSelect(index);
+                }}
+                onDoubleClick={() => onActivate(item)}
+                onMouseEnter={() => onSelect(index)}
+              >
+                {item.icon ? (
+                  <img
+                    src={`data:image/png;base64,${item.icon}`}
+                    className="result-icon"
+                    alt="result icon"
+                  />
+                ) : (
+                  <div
+                    className="result-icon placeholder"
+                    style={{
+                      background: visual.background,
+                      color: visual.color,
+                    }}
+                  >
+                    {visual.glyph}
+                  </div>
+                )}
+                <div className="result-meta">
+                  <div className="result-title-row">
+                    <span className="result-title">{item.title}</span>
+                    {item.running ? (
+                      <span className="result-running" title="正在运行">
+                        运行中
+                      </span>
+                    ) : null}
+                    {item.drive_unavailable ? (
+                      <span
+                        className="result-drive-unavailable"
+                        title="目标所在驱动器未连接"
+                      >
+                        驱动器未连接
+                      </span>
+                    ) : null}
+                    <span className="result-tag">{resolveResultTag(item)}</span>
+                  </div>
+                  <div className="result-subtitle" title={item.subtitle}>
+                    {item.subtitle}
+                  </div>
                 </div>
-              )}
-              <div className="result-meta">
-                <div className="result-title-row">
-                  <span className="result-title">{item.title}</span>
-                  <span className="result-tag">{resolveResultTag(item)}</span>
+                <div className="result-shortcut" aria-hidden="true">
+                  {item.quick_key !== null
+                    ? `Alt+${item.quick_key}`
+                    : String(index + 1).padStart(2, "0")}
                 </div>
-                <div className="result-subtitle" title={item.subtitle}>
-                  {item.subtitle}
+              </button>
+              {isActive && item.secondary_actions.length > 0 ? (
+                <div className="result-secondary-actions">
+                  {item.secondary_actions.map((action) => (
+                    <button
+                      key={action.id}
+                      type="button"
+                      className="result-secondary-action"
+                      onClick={() => onSecondaryAction(action)}
+                    >
+                      {action.title}
+                    </button>
+                  ))}
                 </div>
-              </div>
-              <div className="result-shortcut" aria-hidden="true">
-                {String(index + 1).padStart(2, "0")}
-              </div>
-            </button>
-          </div>
+              ) : null}
+            </div>
+          </Fragment>
         );
       })}
     </div>
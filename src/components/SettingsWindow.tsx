@@ -4,6 +4,7 @@ import { invoke } from "@tauri-apps/api/core";
 import { getVersion } from "@tauri-apps/api/app";
 import type { AppSettings } from "../types";
 import { Toast } from "./Toast";
+import { describeError } from "../utils/errors";
 import { applyWindowOpacityVariable } from "../utils/theme";
 
 const MIN_QUERY_DELAY = 50;
@@ -104,8 +105,13 @@ const SECTION_DEFS: Array<{
 type BooleanSettingKey =
   | "enable_app_results"
   | "enable_bookmark_results"
+  | "enable_history_results"
+  | "enable_file_results"
+  | "enable_clipboard_history"
+  | "extract_icons"
   | "launch_on_startup"
   | "force_english_input"
+  | "force_english_first_show_only"
   | "debug_mode";
 
 const TRACKED_SETTING_KEYS: Array<keyof AppSettings> = [
@@ -114,11 +120,19 @@ const TRACKED_SETTING_KEYS: Array<keyof AppSettings> = [
   "max_results",
   "enable_app_results",
   "enable_bookmark_results",
+  "enable_history_results",
+  "enable_file_results",
+  "enable_clipboard_history",
+  "extract_icons",
   "prefix_app",
   "prefix_bookmark",
   "prefix_search",
+  "prefix_file",
+  "prefix_clipboard",
+  "prefix_run",
   "launch_on_startup",
   "force_english_input",
+  "force_english_first_show_only",
   "debug_mode",
   "window_opacity",
 ];
@@ -257,11 +271,13 @@ export const SettingsWindow = () => {
     if (!draft.global_hotkey.trim()) {
       return "快捷键不能为空";
     }
+    // 0 表示即时模式，不受最小延迟限制
     if (
-      draft.query_delay_ms < MIN_QUERY_DELAY ||
-      draft.query_delay_ms > MAX_QUERY_DELAY
+      draft.query_delay_ms !== 0 &&
+      (draft.query_delay_ms < MIN_QUERY_DELAY ||
+        draft.query_delay_ms > MAX_QUERY_DELAY)
     ) {
-      return `延迟需在 ${MIN_QUERY_DELAY}~${MAX_QUERY_DELAY}ms 之间`;
+      return `延迟需为 0 或在 ${MIN_QUERY_DELAY}~${MAX_QUERY_DELAY}ms 之间`;
     }
     if (
       draft.max_results < MIN_RESULT_LIMIT ||
@@ -293,7 +309,10 @@ export const SettingsWindow = () => {
     const prefixError =
       validatePrefix(draft.prefix_app, "应用模式") ||
       validatePrefix(draft.prefix_bookmark, "书签模式") ||
-      validatePrefix(draft.prefix_search, "搜索模式");
+      validatePrefix(draft.prefix_search, "搜索模式") ||
+      validatePrefix(draft.prefix_file, "文件模式") ||
+      validatePrefix(draft.prefix_clipboard, "剪贴板模式") ||
+      validatePrefix(draft.prefix_run, "运行模式");
     if (prefixError) {
       return prefixError;
     }
@@ -325,7 +344,7 @@ export const SettingsWindow = () => {
       showToast("设置已更新");
     } catch (error) {
       console.error("Failed to update settings", error);
-      showToast("更新设置失败");
+      showToast(describeError(error, "更新设置失败"));
     } finally {
       setIsSaving(false);
     }
@@ -392,6 +411,26 @@ export const SettingsWindow = () => {
     });
   }, [draft, showToast]);
 
+  const handleRestoreDefaults = useCallback(async () => {
+    if (!window.confirm("确定将所有设置恢复为默认值吗？此操作无法撤销。")) {
+      return;
+    }
+    try {
+      setIsSaving(true);
+      const defaults = await invoke<AppSettings>("reset_settings");
+      setSettings(defaults);
+      setDraft({ ...defaults });
+      setIsCapturingHotkey(false);
+      applyWindowOpacityVariable(defaults.window_opacity);
+      showToast("已恢复默认设置");
+    } catch (error) {
+      console.error("Failed to reset settings", error);
+      showToast(describeError(error, "恢复默认设置失败"));
+    } finally {
+      setIsSaving(false);
+    }
+  }, [showToast]);
+
   const handleReset = useCallback(() => {
     if (settings) {
       setDraft({ ...settings });
@@ -479,7 +518,7 @@ export const SettingsWindow = () => {
           <div className="settings-number">
             <input
               type="number"
-              min={MIN_QUERY_DELAY}
+              min={0}
               max={MAX_QUERY_DELAY}
               step={10}
               value={draft.query_delay_ms}
@@ -491,7 +530,7 @@ export const SettingsWindow = () => {
               }
             />
             <span className="settings-hint">
-              范围 {MIN_QUERY_DELAY}~{MAX_QUERY_DELAY} ms
+              范围 {MIN_QUERY_DELAY}~{MAX_QUERY_DELAY} ms，0 为即时模式
             </span>
           </div>
         </article>
@@ -563,6 +602,19 @@ export const SettingsWindow = () => {
                 </div>
               </div>
             </button>
+            <button
+              type="button"
+              className={`settings-toggle ${draft.force_english_first_show_only ? "on" : "off"}`}
+              onClick={() => toggleBoolean("force_english_first_show_only")}
+            >
+              <span className="toggle-pill" aria-hidden="true" />
+              <div>
+                <div className="toggle-title">仅在快捷键唤起时切换</div>
+                <div className="toggle-subtitle">
+                  托盘等方式再次显示窗口时保留当前输入法
+                </div>
+              </div>
+            </button>
             <button
               type="button"
               className={`settings-toggle ${draft.debug_mode ? "on" : "off"}`}
@@ -616,6 +668,50 @@ export const SettingsWindow = () => {
                 <div className="toggle-subtitle">同步 Chrome 收藏夹</div>
               </div>
             </button>
+            <button
+              type="button"
+              className={`settings-toggle ${draft.enable_history_results ? "on" : "off"}`}
+              onClick={() => toggleBoolean("enable_history_results")}
+            >
+              <span className="toggle-pill" aria-hidden="true" />
+              <div>
+                <div className="toggle-title">包含浏览历史</div>
+                <div className="toggle-subtitle">读取 Chrome / Edge 本地历史记录</div>
+              </div>
+            </button>
+            <button
+              type="button"
+              className={`settings-toggle ${draft.enable_file_results ? "on" : "off"}`}
+              onClick={() => toggleBoolean("enable_file_results")}
+            >
+              <span className="toggle-pill" aria-hidden="true" />
+              <div>
+                <div className="toggle-title">文件模式</div>
+                <div className="toggle-subtitle">用模式前缀按名称查找桌面、文档和下载中的文件</div>
+              </div>
+            </button>
+            <button
+              type="button"
+              className={`settings-toggle ${draft.enable_clipboard_history ? "on" : "off"}`}
+              onClick={() => toggleBoolean("enable_clipboard_history")}
+            >
+              <span className="toggle-pill" aria-hidden="true" />
+              <div>
+                <div className="toggle-title">剪贴板历史</div>
+                <div className="toggle-subtitle">记录最近复制的文本，仅保存在内存中，退出后清空</div>
+              </div>
+            </button>
+            <button
+              type="button"
+              className={`settings-toggle ${draft.extract_icons ? "on" : "off"}`}
+              onClick={() => toggleBoolean("extract_icons")}
+            >
+              <span className="toggle-pill" aria-hidden="true" />
+              <div>
+                <div className="toggle-title">提取应用图标</div>
+                <div className="toggle-subtitle">关闭后不加载图标，索引重建更快</div>
+              </div>
+            </button>
           </div>
         </article>
         <article className="settings-card">
@@ -711,6 +807,54 @@ export const SettingsWindow = () => {
               />
               <span className="settings-hint">例如 "s"、"s:" 或 "s "</span>
             </div>
+            <div className="settings-prefix-row">
+              <label className="settings-prefix-label" htmlFor="prefix_file">
+                文件模式
+              </label>
+              <input
+                id="prefix_file"
+                type="text"
+                maxLength={2}
+                className="settings-input settings-input--small"
+                value={draft.prefix_file}
+                onChange={(event: ChangeEvent<HTMLInputElement>) =>
+                  updateDraftValue("prefix_file", event.currentTarget.value)
+                }
+              />
+              <span className="settings-hint">需先开启文件模式，与内容搜索前缀相同时优先进入文件模式</span>
+            </div>
+            <div className="settings-prefix-row">
+              <label className="settings-prefix-label" htmlFor="prefix_clipboard">
+                剪贴板模式
+              </label>
+              <input
+                id="prefix_clipboard"
+                type="text"
+                maxLength={2}
+                className="settings-input settings-input--small"
+                value={draft.prefix_clipboard}
+                onChange={(event: ChangeEvent<HTMLInputElement>) =>
+                  updateDraftValue("prefix_clipboard", event.currentTarget.value)
+                }
+              />
+              <span className="settings-hint">需先开启剪贴板历史，只输入前缀时列出全部记录</span>
+            </div>
+            <div className="settings-prefix-row">
+              <label className="settings-prefix-label" htmlFor="prefix_run">
+                运行模式
+              </label>
+              <input
+                id="prefix_run"
+                type="text"
+                maxLength={2}
+                className="settings-input settings-input--small"
+                value={draft.prefix_run}
+                onChange={(event: ChangeEvent<HTMLInputElement>) =>
+                  updateDraftValue("prefix_run", event.currentTarget.value)
+                }
+              />
+              <span className="settings-hint">只输入前缀时列出最近运行的命令</span>
+            </div>
           </div>
         </article>
       </div>
@@ -796,6 +940,14 @@ export const SettingsWindow = () => {
           {validationMessage ?? (isDirty ? "有更改尚未保存" : "配置已同步")}
         </div>
         <div className="settings-footer__actions">
+          <button
+            type="button"
+            className="ghost-button"
+            onClick={() => void handleRestoreDefaults()}
+            disabled={isSaving}
+          >
+            恢复默认
+          </button>
           <button type="button" className="ghost-button" onClick={handleReset}>
             恢复已保存
           </button>
@@ -1,4 +1,7 @@
 export const HIDE_WINDOW_EVENT = "hide_window";
+export const ACTION_FAILED_EVENT = "action_failed";
 export const OPEN_SETTINGS_EVENT = "open_settings";
 export const SETTINGS_UPDATED_EVENT = "settings_updated";
 export const FOCUS_INPUT_EVENT = "focus_input";
+export const CONFIG_UNAVAILABLE_EVENT = "config_unavailable";
+export const NEW_APPS_DETECTED_EVENT = "new_apps_detected";
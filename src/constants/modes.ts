@@ -29,6 +29,27 @@ export const DEFAULT_MODE_CONFIGS: Record<ModeId, ModeConfig> = {
     description: "仅使用网络搜索",
     placeholder: "搜索模式 · 输入关键词，在浏览器中搜索",
   },
+  file: {
+    id: "file",
+    label: "文件模式",
+    prefix: "f",
+    description: "按名称查找文件与文件夹",
+    placeholder: "文件模式 · 输入文件或文件夹名称",
+  },
+  clipboard: {
+    id: "clipboard",
+    label: "剪贴板模式",
+    prefix: "v",
+    description: "从最近复制的文本中重新复制",
+    placeholder: "剪贴板模式 · 输入关键词筛选复制历史",
+  },
+  run: {
+    id: "run",
+    label: "运行模式",
+    prefix: "c",
+    description: "像“运行”对话框一样执行命令",
+    placeholder: "运行模式 · 输入要运行的命令",
+  },
 };
 
 // 根据设置构造当前模式配置，允许自定义前缀
@@ -53,6 +74,23 @@ export const buildModeConfigsFromSettings = (
       ...DEFAULT_MODE_CONFIGS.search,
       prefix: settings.prefix_search || DEFAULT_MODE_CONFIGS.search.prefix,
     },
+    // 文件搜索未启用时不注册前缀，输入照常按智能模式处理
+    file: {
+      ...DEFAULT_MODE_CONFIGS.file,
+      prefix: settings.enable_file_results
+        ? settings.prefix_file || DEFAULT_MODE_CONFIGS.file.prefix
+        : undefined,
+    },
+    clipboard: {
+      ...DEFAULT_MODE_CONFIGS.clipboard,
+      prefix: settings.enable_clipboard_history
+        ? settings.prefix_clipboard || DEFAULT_MODE_CONFIGS.clipboard.prefix
+        : undefined,
+    },
+    run: {
+      ...DEFAULT_MODE_CONFIGS.run,
+      prefix: settings.prefix_run || DEFAULT_MODE_CONFIGS.run.prefix,
+    },
   };
 };
 
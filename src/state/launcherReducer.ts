@@ -11,6 +11,9 @@ export const initialLauncherState: LauncherState = {
   activeMode: DEFAULT_MODE_CONFIGS.all,
   isModePrefixOnly: false,
   isComposing: false,
+  markedIds: [],
+  isIndexStale: false,
+  resultGroups: {},
 };
 
 export const launcherReducer = (
@@ -30,6 +33,18 @@ export const launcherReducer = (
       return {
         ...state,
         results: action.payload,
+        // 新结果对应新的待执行队列，旧的标记已失效
+        markedIds: [],
+      };
+    case "SET_INDEX_STALE":
+      return {
+        ...state,
+        isIndexStale: action.payload,
+      };
+    case "SET_RESULT_GROUPS":
+      return {
+        ...state,
+        resultGroups: action.payload,
       };
     case "SET_SELECTED_INDEX":
       return {
@@ -51,6 +66,18 @@ export const launcherReducer = (
         ...state,
         isComposing: action.payload,
       };
+    case "TOGGLE_MARKED":
+      return {
+        ...state,
+        markedIds: state.markedIds.includes(action.payload)
+          ? state.markedIds.filter((id) => id !== action.payload)
+          : [...state.markedIds, action.payload],
+      };
+    case "CLEAR_MARKED":
+      return {
+        ...state,
+        markedIds: [],
+      };
     case "RESET_SEARCH":
       return {
         ...state,
@@ -60,6 +87,8 @@ export const launcherReducer = (
         selectedIndex: 0,
         activeMode: DEFAULT_MODE_CONFIGS.all,
         isModePrefixOnly: false,
+        markedIds: [],
+        resultGroups: {},
       };
     default:
       return state;
@@ -5,25 +5,335 @@ export type SearchResult = {
   icon: string;
   score: number;
   action_id: string;
+  secondary_actions: ResultAction[];
+  // Win32 应用当前是否有进程在运行
+  running: boolean;
+  // 前 9 个结果的 Alt+数字 快捷键编号
+  quick_key: number | null;
+  // 目标所在驱动器当前未连接（如已拔出的移动硬盘）
+  drive_unavailable: boolean;
+};
+
+export type SearchResponse = {
+  results: SearchResult[];
+  // 重建索引期间为 true，结果来自旧索引
+  stale_index: boolean;
+  // 结果集编号，调用 execute_quick 时原样传回
+  generation: number;
+};
+
+export type ResultGroupKind =
+  | "applications"
+  | "bookmarks"
+  | "web"
+  | "url"
+  | "calculator"
+  | "other";
+
+export type ResultGroup = {
+  kind: ResultGroupKind;
+  results: SearchResult[];
+};
+
+// submit_query_grouped 的返回值：按类别分组，组按各自最佳结果排序
+export type GroupedSearchResponse = {
+  groups: ResultGroup[];
+  stale_index: boolean;
+  generation: number;
+};
+
+export type AppSummary = {
+  id: string;
+  name: string;
+};
+
+// 两次应用索引构建之间按 id 比较的差异
+export type IndexDiff = {
+  added: AppSummary[];
+  removed: AppSummary[];
+};
+
+export type AppErrorCode =
+  | "config_io"
+  | "hotkey_invalid"
+  | "action_expired"
+  | "launch_failed"
+  | "uwp"
+  | "elevation_cancelled"
+  | "not_applicable"
+  | "invalid_setting"
+  | "internal";
+
+// 所有命令失败时返回的错误
+export type AppError = {
+  code: AppErrorCode;
+  message: string;
+};
+
+export type ActionFailedPayload = {
+  // 执行的结果 id，无结果时的回车动作为 null
+  id: string | null;
+  error: AppError;
+};
+
+export type AppPaths = {
+  config_file: string | null;
+  // 配置目录不可写时为 false，设置只在本次运行内有效
+  config_persistent: boolean;
+  config_error: string | null;
+  icon_cache_dir: string | null;
+  favicon_cache_dir: string | null;
+  // 各配置方案的设置文件所在目录，显示名称和启动参数都保存在设置文件中
+  profiles_dir: string | null;
+  active_profile_file: string | null;
+  usage_file: string | null;
+  run_history_file: string | null;
+  app_index_cache_file: string | null;
+};
+
+// 配置方案列表，profiles 中始终包含 "default"
+export type ConfigProfiles = {
+  active: string;
+  profiles: string[];
+};
+
+export type UsageCount = {
+  key: string;
+  title: string;
+  count: number;
+};
+
+// 本地使用统计，仅由本机记录计算，不会上传
+export type UsageAnalytics = {
+  range_days: number;
+  total_launches: number;
+  // 按日期升序，包含没有启动记录的日期
+  launches_per_day: { date: string; count: number }[];
+  top_apps: UsageCount[];
+  top_bookmarks: UsageCount[];
+  mode_usage: Record<string, number>;
+};
+
+export type HealthCheck = {
+  name: "config" | "hotkey" | "app_index" | "com" | "icon_cache";
+  ok: boolean;
+  // 通过时为检查结果，失败时为原因
+  detail: string;
+};
+
+export type HealthReport = {
+  ok: boolean;
+  checks: HealthCheck[];
+};
+
+export type ResultAction = {
+  id: string;
+  title: string;
+  requires_confirmation: boolean;
+};
+
+export type ModeHotkey = {
+  hotkey: string;
+  // 模式名，如 "bookmark"、"app"
+  mode: string;
 };
 
 export type AppSettings = {
   global_hotkey: string;
+  // 额外的全局快捷键，按下后直接以对应模式打开窗口
+  mode_hotkeys: ModeHotkey[];
+  // 快速双击该修饰键（Ctrl/Shift/Alt/Super）切换窗口，null 表示关闭
+  double_tap_modifier: string | null;
   query_delay_ms: number;
   max_results: number;
+  max_app_results: number;
+  max_bookmark_results: number;
   enable_app_results: boolean;
+  // 索引时是否提取应用图标，关闭可加快重建
+  extract_icons: boolean;
+  // 应用图标边长（32/48/64/256），高分屏可选更大尺寸
+  icon_size: number;
+  // 扫描可执行文件时按文件名排除，支持 * 和 ? 通配符
+  exclude_exe_patterns: string[];
+  // 将已安装的 Steam 游戏加入应用索引，通过 Steam 客户端启动
+  enable_steam_games: boolean;
   enable_bookmark_results: boolean;
-  // 三种模式的前缀，可由设置页面自定义
+  enable_history_results: boolean;
+  // 文件模式：按名称搜索 file_search_roots 下的文件与文件夹，默认关闭
+  enable_file_results: boolean;
+  // 文件模式扫描的目录，为空时使用桌面、文档和下载
+  file_search_roots: string[];
+  // 剪贴板模式：记录最近复制的文本，仅保存在内存中，默认关闭
+  enable_clipboard_history: boolean;
+  // 剪贴板历史保留的条数
+  clipboard_history_size: number;
+  max_history_entries: number;
+  max_history_results: number;
+  // 每次最多显示的结果数（含网页搜索），0 表示不额外限制
+  max_visible_results: number;
+  // 各模式的前缀，可由设置页面自定义
   prefix_app: string;
   prefix_bookmark: string;
   prefix_search: string;
+  prefix_file: string;
+  prefix_clipboard: string;
+  prefix_run: string;
   launch_on_startup: boolean;
   force_english_input: boolean;
+  // 仅在快捷键唤起后的首次显示时切换英文输入
+  force_english_first_show_only: boolean;
   debug_mode: boolean;
   window_opacity: number;
+  fallback_on_empty_results: boolean;
+  empty_enter_action: "url_or_search" | "web_search" | "nothing";
+  // 网络搜索地址，{query} 会替换为编码后的搜索词
+  search_engine_template: string;
+  search_engine_name: string;
+  // bang（不含 !）到搜索地址，例如输入 !gh rust 使用 gh 对应的地址
+  search_bangs: Record<string, string>;
+  enable_instant_answers: boolean;
+  clear_query_on_hide: boolean;
+  escape_behavior: EscapeBehavior;
+  locale: Locale;
+  working_dir_mode: WorkingDirMode;
+  custom_working_dir: string;
+  // 同一结果在该毫秒数内重复执行会被忽略，0 表示关闭
+  launch_cooldown_ms: number;
+  // 一次打开的收藏夹链接数超过该值时先确认
+  bookmark_folder_confirm_threshold: number;
+  enter_on_running_app: EnterOnRunningApp;
+  // 在应用名称后输入的参数追加在已保存的启动参数之后，或替换它们
+  typed_launch_args: TypedLaunchArgs;
+  nav_wrap: boolean;
+  page_jump_size: number;
+  watch_bookmarks: boolean;
+  // 后台定时重建索引的间隔（分钟），0 表示关闭
+  reindex_interval_minutes: number;
+  // 读取书签的浏览器，靠前者优先；为空时读取全部支持的浏览器
+  bookmark_browsers: string[];
+  // 是否同时读取 Edge 书签，默认关闭
+  enable_edge_bookmarks: boolean;
+  // 是否同时读取 Firefox 的 places.sqlite 书签，默认关闭
+  enable_firefox_bookmarks: boolean;
+  // 为空时读取全部浏览器配置文件
+  bookmark_profiles: string[];
+  dedupe_bookmarks_across_profiles: boolean;
+  favicon_refresh_enabled: boolean;
+  favicon_max_age_hours: number;
+  relative_score_cutoff: number;
+  // 名称或关键词以查询开头时的加分，0 表示关闭
+  prefix_boost: number;
+  // 启动频率加分权重，0 表示不按使用频率排序
+  usage_boost: number;
+  // 关键词、书签目录路径与网址匹配时的扣分，用于偏向名称匹配
+  keyword_penalty: number;
+  path_penalty: number;
+  url_penalty: number;
+  // 低于该分数（未加分前）的模糊匹配直接丢弃，0 表示不过滤
+  min_score_threshold: number;
+  // skim 为模糊匹配，substring 只匹配连续子串
+  match_algorithm: MatchAlgorithm;
+  // 模糊匹配忽略大小写；关闭时查询含大写字母才区分大小写
+  ignore_case: boolean;
+  // 匹配前去掉变音符号，例如 cafe 可匹配 Café
+  ignore_accents: boolean;
+  // 为 true 时 /pattern/ 形式的输入按正则表达式匹配
+  regex_queries: boolean;
+  // 为 true 时直接隐藏驱动器未连接的应用，否则仅做标记
+  exclude_unavailable_drives: boolean;
+  // 收藏夹结果同分时，较新添加的排在前面
+  bookmark_recency_tiebreak: boolean;
+  // 先发布不含图标的应用索引，再在后台逐个提取图标
+  prewarm_icons: boolean;
+  // 重建索引后提示新出现的应用
+  notify_new_apps: boolean;
+  advanced: AdvancedSettings;
+  content_search: ContentSearchSettings;
+  custom_commands: CustomCommand[];
+  force_english_by_mode: ForceEnglishByMode;
+  // 应用 id 到自定义显示名称，通过 set_display_name / clear_display_name 修改
+  display_names: Record<string, string>;
+  // 应用 id 到每次启动时附加的参数，通过 set_launch_args 修改
+  launch_args: Record<string, string>;
+};
+
+// 未设置（null）的模式沿用全局 force_english_input
+export type ForceEnglishByMode = {
+  all: boolean | null;
+  app: boolean | null;
+  bookmark: boolean | null;
+  search: boolean | null;
+  file: boolean | null;
+  clipboard: boolean | null;
+  run: boolean | null;
+};
+
+// 关键词 + 参数的自定义启动项，target/arguments 中的 {input} 会被替换
+export type CustomCommand = {
+  keyword: string;
+  title: string;
+  kind: "url" | "command";
+  target: string;
+  arguments: string;
+};
+
+// 借助 ripgrep 搜索文件内容，需本机已安装 rg
+export type ContentSearchSettings = {
+  enabled: boolean;
+  prefix: string;
+  rg_path: string;
+  roots: string[];
+  globs: string[];
+  timeout_ms: number;
+  max_results: number;
+};
+
+export type AdvancedSettings = {
+  min_query_delay_ms: number;
+  max_query_delay_ms: number;
+  min_result_limit: number;
+  max_result_limit: number;
 };
 
-export type ModeId = "all" | "bookmark" | "app" | "search";
+// 后端生成的文本（托盘菜单等）所使用的语言
+export type Locale = "zh-CN" | "en";
+
+// Win32 程序启动时的工作目录
+export type WorkingDirMode = "exe_parent" | "inherit" | "custom";
+
+export type EnterOnRunningApp = "launch_new" | "focus_existing" | "ask";
+
+export type TypedLaunchArgs = "append" | "replace";
+
+export type ExecuteOutcome =
+  | { status: "done" }
+  | { status: "needs_confirmation"; prompt: string }
+  | { status: "needs_approval"; prompt: string };
+
+export type MatchAlgorithm = "skim" | "substring";
+
+export type EscapeBehavior = "always_hide" | "clear_then_hide";
+
+export type HideReason = "user" | "blur" | "tray" | "action";
+
+export type HideWindowPayload = {
+  clear_query: boolean;
+  reason: HideReason;
+} | null;
+
+export type FocusInputPayload = {
+  // 由模式快捷键唤起时为目标模式，呼出快捷键唤起时为 null
+  mode: string | null;
+} | null;
+
+export type ModeId =
+  | "all"
+  | "bookmark"
+  | "app"
+  | "search"
+  | "file"
+  | "clipboard"
+  | "run";
 
 export type ModeConfig = {
   id: ModeId;
@@ -49,6 +359,11 @@ export type LauncherState = {
   activeMode: ModeConfig;
   isModePrefixOnly: boolean;
   isComposing: boolean;
+  // 通过 Ctrl/Shift+点击标记的结果，回车时一并执行
+  markedIds: string[];
+  isIndexStale: boolean;
+  // submit_query_grouped 返回的结果类别，按结果 id 索引，用于显示分组标题
+  resultGroups: Record<string, ResultGroupKind>;
 };
 
 export type LauncherAction =
@@ -62,8 +377,18 @@ export type LauncherAction =
     };
   }
   | { type: "SET_RESULTS"; payload: SearchResult[] }
+  | { type: "SET_INDEX_STALE"; payload: boolean }
+  | { type: "SET_RESULT_GROUPS"; payload: Record<string, ResultGroupKind> }
   | { type: "SET_SELECTED_INDEX"; payload: number }
   | { type: "SET_TOAST"; payload: string | null }
   | { type: "SET_SETTINGS"; payload: AppSettings }
   | { type: "SET_COMPOSING"; payload: boolean }
+  | { type: "TOGGLE_MARKED"; payload: string }
+  | { type: "CLEAR_MARKED" }
   | { type: "RESET_SEARCH" };
+
+export type ConfigIssue = {
+  field: string;
+  severity: "error" | "warning";
+  message: string;
+};
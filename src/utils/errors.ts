@@ -0,0 +1,27 @@
+import type { AppError, AppErrorCode } from "../types";
+
+// 按错误码给出固定提示；未知错误码或内部错误时使用后端的 message 或调用方的默认提示
+const ERROR_MESSAGES: Partial<Record<AppErrorCode, string>> = {
+  config_io: "配置无法保存，请检查配置目录权限",
+  hotkey_invalid: "快捷键无效或已被其他程序占用",
+  action_expired: "结果已失效，请重新搜索",
+  launch_failed: "执行失败，请检查目标是否存在",
+  uwp: "UWP 应用启动失败",
+  elevation_cancelled: "已取消以管理员身份运行",
+};
+
+const isAppError = (error: unknown): error is AppError =>
+  typeof error === "object" &&
+  error !== null &&
+  typeof (error as AppError).code === "string" &&
+  typeof (error as AppError).message === "string";
+
+export const describeError = (error: unknown, fallback: string): string => {
+  if (isAppError(error)) {
+    return ERROR_MESSAGES[error.code] ?? (error.message || fallback);
+  }
+  if (typeof error === "string" && error) {
+    return error;
+  }
+  return fallback;
+};